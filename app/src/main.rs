@@ -29,7 +29,7 @@
 use cross_correlate::{Correlate, CrossCorrelationMode};
 
 fn main() {
-    let mut src = vec![
+    let src = vec![
         5.12, 6.2136, 7.2387, 1.52312, 2.52313, 3.52313, 4.52313, 5.23871,
     ];
     let dst = vec![0.31421, 0.421, 0.653, 0.121];
@@ -0,0 +1,156 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use num_complex::Complex;
+use num_traits::MulAdd;
+use std::sync::Arc;
+
+/// Computes `dst[i] += src[i] * conj(tap)` over two equally sized slices.
+///
+/// Direct correlation is evaluated tap by tap with this kernel, which vectorizes
+/// over output lags instead of over the (often short) kernel. Conjugation is a
+/// no-op for real samples.
+pub trait ScaledAccumulate<V> {
+    fn accumulate(&self, dst: &mut [V], src: &[V], tap: V);
+}
+
+#[derive(Copy, Clone, Default, Debug)]
+pub(crate) struct AccumulateSingle {}
+
+#[derive(Copy, Clone, Default, Debug)]
+pub(crate) struct AccumulateDouble {}
+
+impl ScaledAccumulate<f32> for AccumulateSingle {
+    fn accumulate(&self, dst: &mut [f32], src: &[f32], tap: f32) {
+        accumulate_impl(dst, src, tap);
+    }
+}
+
+impl ScaledAccumulate<Complex<f32>> for AccumulateSingle {
+    fn accumulate(&self, dst: &mut [Complex<f32>], src: &[Complex<f32>], tap: Complex<f32>) {
+        accumulate_impl(dst, src, tap.conj());
+    }
+}
+
+impl ScaledAccumulate<f64> for AccumulateDouble {
+    fn accumulate(&self, dst: &mut [f64], src: &[f64], tap: f64) {
+        accumulate_impl(dst, src, tap);
+    }
+}
+
+impl ScaledAccumulate<Complex<f64>> for AccumulateDouble {
+    fn accumulate(&self, dst: &mut [Complex<f64>], src: &[Complex<f64>], tap: Complex<f64>) {
+        accumulate_impl(dst, src, tap.conj());
+    }
+}
+
+#[inline(always)]
+fn accumulate_impl<V: Copy + MulAdd<V, Output = V>>(dst: &mut [V], src: &[V], scale: V) {
+    for (dst, &src) in dst.iter_mut().zip(src.iter()) {
+        *dst = MulAdd::mul_add(src, scale, *dst);
+    }
+}
+
+/// Names the accumulator each enabled backend provides for the sample type.
+pub(crate) trait AccumulateSample: Sized {
+    type Scalar: ScaledAccumulate<Self> + Default + Send + Sync + 'static;
+    #[cfg(all(target_arch = "x86_64", feature = "avx"))]
+    type Avx: ScaledAccumulate<Self> + Default + Send + Sync + 'static;
+    #[cfg(all(target_arch = "x86_64", feature = "sse"))]
+    type Sse: ScaledAccumulate<Self> + Default + Send + Sync + 'static;
+    #[cfg(all(target_arch = "aarch64", feature = "neon"))]
+    type Neon: ScaledAccumulate<Self> + Default + Send + Sync + 'static;
+}
+
+macro_rules! impl_accumulate_sample {
+    ($t: ty, $scalar: ident, $avx: ident, $sse: ident, $neon: ident) => {
+        impl AccumulateSample for $t {
+            type Scalar = $scalar;
+            #[cfg(all(target_arch = "x86_64", feature = "avx"))]
+            type Avx = crate::avx::$avx;
+            #[cfg(all(target_arch = "x86_64", feature = "sse"))]
+            type Sse = crate::sse::$sse;
+            #[cfg(all(target_arch = "aarch64", feature = "neon"))]
+            type Neon = crate::neon::$neon;
+        }
+    };
+}
+
+impl_accumulate_sample!(
+    f32,
+    AccumulateSingle,
+    AccumulateSingleAvxFma,
+    AccumulateSingleSse4_2,
+    AccumulateSingleNeon
+);
+impl_accumulate_sample!(
+    Complex<f32>,
+    AccumulateSingle,
+    AccumulateSingleAvxFma,
+    AccumulateSingleSse4_2,
+    AccumulateSingleNeon
+);
+impl_accumulate_sample!(
+    f64,
+    AccumulateDouble,
+    AccumulateDoubleAvxFma,
+    AccumulateDoubleSse4_2,
+    AccumulateDoubleNeon
+);
+impl_accumulate_sample!(
+    Complex<f64>,
+    AccumulateDouble,
+    AccumulateDoubleAvxFma,
+    AccumulateDoubleSse4_2,
+    AccumulateDoubleNeon
+);
+
+/// Selects the fastest available accumulator for the running CPU.
+pub(crate) fn make_accumulate<V: AccumulateSample>() -> Arc<dyn ScaledAccumulate<V> + Send + Sync> {
+    #[cfg(all(target_arch = "x86_64", feature = "avx"))]
+    {
+        if std::arch::is_x86_feature_detected!("avx2") && std::arch::is_x86_feature_detected!("fma")
+        {
+            return Arc::new(V::Avx::default());
+        }
+    }
+    #[cfg(all(target_arch = "x86_64", feature = "sse"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.2") {
+            return Arc::new(V::Sse::default());
+        }
+    }
+    #[cfg(all(target_arch = "aarch64", feature = "neon"))]
+    {
+        Arc::new(V::Neon::default())
+    }
+    #[cfg(not(all(target_arch = "aarch64", feature = "neon")))]
+    {
+        Arc::new(V::Scalar::default())
+    }
+}
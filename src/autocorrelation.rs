@@ -0,0 +1,333 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::error::try_vec;
+use crate::extract::extract_correlation;
+use crate::fft::{ComplexFft, ComplexToRealFft, RealToComplexFft};
+use crate::normalization::{LagScale, NormalizeSample, signal_energy};
+use crate::pad::pad_signal;
+use crate::scratch::as_complex_mut;
+use crate::spectrum::power_spectrum_in_place;
+use crate::{
+    CorrelateSample, CrossCorrelateError, CrossCorrelationMode, CrossCorrelationNormalization,
+    FftSizePolicy,
+};
+use num_complex::Complex;
+use num_traits::{AsPrimitive, Float};
+use std::fmt::Debug;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Selects which lags an autocorrelation returns.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum AutocorrelationLags {
+    /// Lags `-(n - 1)..=n - 1`, the same as a `Full` cross-correlation of the signal with itself (default)
+    #[default]
+    TwoSided,
+    /// Lags `0..=n - 1`, the negative lags mirror these (conjugated for complex signals)
+    OneSided,
+}
+
+impl AutocorrelationLags {
+    /// Returns the output length for a signal of `signal_length` samples.
+    #[inline]
+    pub fn output_length(self, signal_length: usize) -> usize {
+        match self {
+            AutocorrelationLags::TwoSided => 2 * signal_length - 1,
+            AutocorrelationLags::OneSided => signal_length,
+        }
+    }
+
+    /// Returns the lag of every output sample for a signal of `signal_length` samples.
+    #[inline]
+    pub fn lags(self, signal_length: usize) -> Range<isize> {
+        match self {
+            AutocorrelationLags::TwoSided => -(signal_length as isize - 1)..signal_length as isize,
+            AutocorrelationLags::OneSided => 0..signal_length as isize,
+        }
+    }
+}
+
+/// Trait for computing the autocorrelation of a signal.
+///
+/// The signal is transformed once and its power spectrum `|X|^2` is inverse
+/// transformed, which is cheaper than correlating the signal with itself.
+pub trait Autocorrelate<V: Clone + Debug + Default> {
+    /// Compute autocorrelation and store in pre-allocated output slice.
+    fn autocorrelate(&self, output: &mut [V], signal: &[V]) -> Result<(), CrossCorrelateError>;
+    /// Compute autocorrelation and return a new `Vec<V>` with the result.
+    fn autocorrelate_managed(&self, signal: &[V]) -> Result<Vec<V>, CrossCorrelateError>;
+    /// Returns the output length for a signal of `signal_length` samples.
+    fn output_length(&self, signal_length: usize) -> usize;
+    /// Returns the number of `V` elements [`Autocorrelate::autocorrelate_with_scratch`]
+    /// requires for its scratch buffer.
+    fn scratch_length(&self) -> usize;
+    /// Compute autocorrelation using a caller-provided scratch buffer.
+    ///
+    /// `scratch` must hold at least [`Autocorrelate::scratch_length`] elements.
+    fn autocorrelate_with_scratch(
+        &self,
+        output: &mut [V],
+        signal: &[V],
+        scratch: &mut [V],
+    ) -> Result<(), CrossCorrelateError>;
+}
+
+/// Resolves the normalization of the lags selected by `lags`.
+fn autocorrelation_scale<V: NormalizeSample>(
+    normalization: CrossCorrelationNormalization,
+    lags: AutocorrelationLags,
+    signal: &[V],
+) -> LagScale {
+    let length = signal.len();
+    let mut scale = normalization.lag_scale(CrossCorrelationMode::Full, length, length, || {
+        let energy = signal_energy(signal);
+        energy * energy
+    });
+    if let (AutocorrelationLags::OneSided, LagScale::Overlap { start, .. }) = (lags, &mut scale) {
+        *start = length - 1;
+    }
+    scale
+}
+
+/// Copies the selected lags out of a circular autocorrelation and scales them.
+fn extract_autocorrelation<V: NormalizeSample>(
+    output: &mut [V],
+    circular: &[V],
+    lags: AutocorrelationLags,
+    signal_length: usize,
+    scale: &LagScale,
+) {
+    match lags {
+        AutocorrelationLags::TwoSided => extract_correlation(
+            output,
+            circular,
+            CrossCorrelationMode::Full,
+            signal_length,
+            signal_length,
+            scale,
+        ),
+        AutocorrelationLags::OneSided => {
+            output.copy_from_slice(&circular[..signal_length]);
+            scale.apply(output);
+        }
+    }
+}
+
+fn check_lengths(
+    output: &[impl Sized],
+    signal_length: usize,
+    fft_length: usize,
+    lags: AutocorrelationLags,
+    fft_size_policy: FftSizePolicy,
+) -> Result<(), CrossCorrelateError> {
+    if signal_length == 0 || output.is_empty() {
+        return Err(CrossCorrelateError::BuffersMustNotHaveZeroSize);
+    }
+    let fft_size =
+        fft_size_policy.fft_size(CrossCorrelationMode::Full, signal_length, signal_length);
+    if fft_size != fft_length {
+        return Err(CrossCorrelateError::FftAndBuffersSizeDoNotMatch(
+            fft_length, fft_size,
+        ));
+    }
+    let data_length = lags.output_length(signal_length);
+    if output.len() != data_length {
+        return Err(CrossCorrelateError::OutputSizeDoNotMatch(
+            data_length,
+            output.len(),
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) struct AutocorrelateReal<T: CorrelateSample> {
+    pub(crate) fft_forward: Arc<dyn RealToComplexFft<T> + Send + Sync>,
+    pub(crate) fft_inverse: Arc<dyn ComplexToRealFft<T> + Send + Sync>,
+    pub(crate) lags: AutocorrelationLags,
+    pub(crate) normalization: CrossCorrelationNormalization,
+    pub(crate) fft_size_policy: FftSizePolicy,
+}
+
+impl<T: CorrelateSample + Float> Autocorrelate<T> for AutocorrelateReal<T>
+where
+    f64: AsPrimitive<T>,
+{
+    fn autocorrelate(&self, output: &mut [T], signal: &[T]) -> Result<(), CrossCorrelateError> {
+        let mut scratch = try_vec![T::default(); self.scratch_length()];
+        self.autocorrelate_with_scratch(output, signal, &mut scratch)
+    }
+
+    fn autocorrelate_managed(&self, signal: &[T]) -> Result<Vec<T>, CrossCorrelateError> {
+        let mut output = try_vec![T::default(); self.output_length(signal.len())];
+        self.autocorrelate(&mut output, signal).map(|_| output)
+    }
+
+    fn output_length(&self, signal_length: usize) -> usize {
+        self.lags.output_length(signal_length)
+    }
+
+    fn scratch_length(&self) -> usize {
+        // Padded real signal followed by its half-spectrum and the work space of the
+        // executors stored as interleaved scalars.
+        let fft_size = self.fft_forward.real_length();
+        let work_length = self
+            .fft_forward
+            .scratch_length()
+            .max(self.fft_inverse.scratch_length());
+        fft_size + (fft_size / 2 + 1 + work_length) * 2
+    }
+
+    fn autocorrelate_with_scratch(
+        &self,
+        output: &mut [T],
+        signal: &[T],
+        scratch: &mut [T],
+    ) -> Result<(), CrossCorrelateError> {
+        let fft_size = self.fft_forward.real_length();
+        if fft_size != self.fft_inverse.real_length() {
+            return Err(CrossCorrelateError::FftSizesDoNotMatch(
+                fft_size,
+                self.fft_inverse.real_length(),
+            ));
+        }
+        check_lengths(
+            output,
+            signal.len(),
+            fft_size,
+            self.lags,
+            self.fft_size_policy,
+        )?;
+
+        let scratch_length = self.scratch_length();
+        if scratch.len() < scratch_length {
+            return Err(CrossCorrelateError::ScratchSizeIsTooSmall(
+                scratch_length,
+                scratch.len(),
+            ));
+        }
+
+        let (padded, spectrum) = scratch[..scratch_length].split_at_mut(fft_size);
+        let (spectrum, work) = as_complex_mut(spectrum).split_at_mut(fft_size / 2 + 1);
+
+        pad_signal(padded, signal);
+        self.fft_forward
+            .execute_with_scratch(padded, spectrum, work)?;
+        power_spectrum_in_place(spectrum, fft_size);
+        self.fft_inverse
+            .execute_with_scratch(spectrum, padded, work)?;
+
+        let scale = autocorrelation_scale(self.normalization, self.lags, signal);
+        extract_autocorrelation(output, padded, self.lags, signal.len(), &scale);
+        Ok(())
+    }
+}
+
+pub(crate) struct AutocorrelateComplex<T: CorrelateSample> {
+    pub(crate) fft_forward: Arc<dyn ComplexFft<T> + Send + Sync>,
+    pub(crate) fft_inverse: Arc<dyn ComplexFft<T> + Send + Sync>,
+    pub(crate) lags: AutocorrelationLags,
+    pub(crate) normalization: CrossCorrelationNormalization,
+    pub(crate) fft_size_policy: FftSizePolicy,
+}
+
+impl<T: CorrelateSample + Float> Autocorrelate<Complex<T>> for AutocorrelateComplex<T>
+where
+    f64: AsPrimitive<T>,
+    Complex<T>: NormalizeSample,
+{
+    fn autocorrelate(
+        &self,
+        output: &mut [Complex<T>],
+        signal: &[Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        let mut scratch = try_vec![Complex::<T>::default(); self.scratch_length()];
+        self.autocorrelate_with_scratch(output, signal, &mut scratch)
+    }
+
+    fn autocorrelate_managed(
+        &self,
+        signal: &[Complex<T>],
+    ) -> Result<Vec<Complex<T>>, CrossCorrelateError> {
+        let mut output = try_vec![Complex::<T>::default(); self.output_length(signal.len())];
+        self.autocorrelate(&mut output, signal).map(|_| output)
+    }
+
+    fn output_length(&self, signal_length: usize) -> usize {
+        self.lags.output_length(signal_length)
+    }
+
+    fn scratch_length(&self) -> usize {
+        // Padded signal followed by the work space of the executors.
+        let work_length = self
+            .fft_forward
+            .scratch_length()
+            .max(self.fft_inverse.scratch_length());
+        self.fft_forward.length() + work_length
+    }
+
+    fn autocorrelate_with_scratch(
+        &self,
+        output: &mut [Complex<T>],
+        signal: &[Complex<T>],
+        scratch: &mut [Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        let fft_size = self.fft_forward.length();
+        if fft_size != self.fft_inverse.length() {
+            return Err(CrossCorrelateError::FftSizesDoNotMatch(
+                fft_size,
+                self.fft_inverse.length(),
+            ));
+        }
+        check_lengths(
+            output,
+            signal.len(),
+            fft_size,
+            self.lags,
+            self.fft_size_policy,
+        )?;
+
+        let scratch_length = self.scratch_length();
+        if scratch.len() < scratch_length {
+            return Err(CrossCorrelateError::ScratchSizeIsTooSmall(
+                scratch_length,
+                scratch.len(),
+            ));
+        }
+
+        let (padded, work) = scratch[..scratch_length].split_at_mut(fft_size);
+        pad_signal(padded, signal);
+        self.fft_forward.execute_with_scratch(padded, work)?;
+        power_spectrum_in_place(padded, fft_size);
+        self.fft_inverse.execute_with_scratch(padded, work)?;
+
+        let scale = autocorrelation_scale(self.normalization, self.lags, signal);
+        extract_autocorrelation(output, padded, self.lags, signal.len(), &scale);
+        Ok(())
+    }
+}
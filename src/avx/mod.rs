@@ -26,8 +26,12 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
+mod accumulate_f32;
+mod accumulate_f64;
 mod mul_spectrum_f32;
 mod mul_spectrum_f64;
 
+pub(crate) use accumulate_f32::AccumulateSingleAvxFma;
+pub(crate) use accumulate_f64::AccumulateDoubleAvxFma;
 pub(crate) use mul_spectrum_f32::MulSpectrumSingleAvxFma;
 pub(crate) use mul_spectrum_f64::MulSpectrumDoubleAvxFma;
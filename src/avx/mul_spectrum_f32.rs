@@ -26,7 +26,9 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
-use crate::spectrum::SpectrumMultiplier;
+use crate::spectrum::{
+    SpectrumMultiplier, mul_spectrum_gains_impl, mul_spectrum_phat_impl, mul_spectrum_roth_impl,
+};
 use num_complex::Complex;
 use std::arch::x86_64::*;
 
@@ -36,13 +38,54 @@ pub(crate) struct MulSpectrumSingleAvxFma {}
 impl SpectrumMultiplier<f32> for MulSpectrumSingleAvxFma {
     fn mul_spectrum(&self, buffer: &mut [Complex<f32>], other: &[Complex<f32>], len: usize) {
         unsafe {
-            mul_spectrum_in_place_f32_impl(buffer, other, len);
+            mul_spectrum_in_place_f32_impl::<true>(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_convolve(
+        &self,
+        buffer: &mut [Complex<f32>],
+        other: &[Complex<f32>],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_in_place_f32_impl::<false>(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_phat(
+        &self,
+        buffer: &mut [Complex<f32>],
+        other: &[Complex<f32>],
+        gains: &[f32],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_phat_f32_impl(buffer, other, gains, len);
+        }
+    }
+
+    fn mul_spectrum_roth(&self, buffer: &mut [Complex<f32>], other: &[Complex<f32>], len: usize) {
+        unsafe {
+            mul_spectrum_roth_f32_impl(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_gains(
+        &self,
+        buffer: &mut [Complex<f32>],
+        other: &[Complex<f32>],
+        gains: &[f32],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_gains_f32_impl(buffer, other, gains, len);
         }
     }
 }
 
 #[target_feature(enable = "avx2", enable = "fma")]
-unsafe fn mul_spectrum_in_place_f32_impl(
+unsafe fn mul_spectrum_in_place_f32_impl<const CONJ: bool>(
     value1: &mut [Complex<f32>],
     other: &[Complex<f32>],
     len: usize,
@@ -51,6 +94,8 @@ unsafe fn mul_spectrum_in_place_f32_impl(
         let normalization_factor = (1f64 / len as f64) as f32;
 
         let v_norm_factor = _mm256_set1_ps(normalization_factor);
+        // Products are taken against the conjugate, so a convolution conjugates the kernel first
+        let v_conj = _mm256_setr_ps(0.0, -0.0, 0.0, -0.0, 0.0, -0.0, 0.0, -0.0);
         let value1 = &mut value1[..];
         let other = &other;
 
@@ -60,10 +105,17 @@ unsafe fn mul_spectrum_in_place_f32_impl(
             let vd2 = _mm256_loadu_ps(dst.get_unchecked(8..).as_ptr().cast());
             let vd3 = _mm256_loadu_ps(dst.get_unchecked(12..).as_ptr().cast());
 
-            let vk0 = _mm256_loadu_ps(kernel.as_ptr().cast());
-            let vk1 = _mm256_loadu_ps(kernel.get_unchecked(4..).as_ptr().cast());
-            let vk2 = _mm256_loadu_ps(kernel.get_unchecked(8..).as_ptr().cast());
-            let vk3 = _mm256_loadu_ps(kernel.get_unchecked(12..).as_ptr().cast());
+            let mut vk0 = _mm256_loadu_ps(kernel.as_ptr().cast());
+            let mut vk1 = _mm256_loadu_ps(kernel.get_unchecked(4..).as_ptr().cast());
+            let mut vk2 = _mm256_loadu_ps(kernel.get_unchecked(8..).as_ptr().cast());
+            let mut vk3 = _mm256_loadu_ps(kernel.get_unchecked(12..).as_ptr().cast());
+
+            if !CONJ {
+                vk0 = _mm256_xor_ps(vk0, v_conj);
+                vk1 = _mm256_xor_ps(vk1, v_conj);
+                vk2 = _mm256_xor_ps(vk2, v_conj);
+                vk3 = _mm256_xor_ps(vk3, v_conj);
+            }
 
             let mut d0 = _m256_fcmul_a_by_b_conj(vd0, vk0);
             let mut d1 = _m256_fcmul_a_by_b_conj(vd1, vk1);
@@ -86,7 +138,11 @@ unsafe fn mul_spectrum_in_place_f32_impl(
 
         for (dst, kernel) in dst_rem.chunks_exact_mut(4).zip(src_rem.chunks_exact(4)) {
             let a0 = _mm256_loadu_ps(dst.as_ptr().cast());
-            let b0 = _mm256_loadu_ps(kernel.as_ptr().cast());
+            let mut b0 = _mm256_loadu_ps(kernel.as_ptr().cast());
+
+            if !CONJ {
+                b0 = _mm256_xor_ps(b0, v_conj);
+            }
 
             let mut d0 = _m256_fcmul_a_by_b_conj(a0, b0);
 
@@ -100,7 +156,12 @@ unsafe fn mul_spectrum_in_place_f32_impl(
 
         for (dst, kernel) in dst_rem.iter_mut().zip(src_rem.iter()) {
             let v0 = _mm_loadu_si64(dst as *const Complex<f32> as *const _);
-            let v1 = _mm_castsi128_ps(_mm_loadu_si64(kernel as *const Complex<f32> as *const _));
+            let mut v1 =
+                _mm_castsi128_ps(_mm_loadu_si64(kernel as *const Complex<f32> as *const _));
+
+            if !CONJ {
+                v1 = _mm_xor_ps(v1, _mm256_castps256_ps128(v_conj));
+            }
 
             let mut lo = _mm_fcmul_a_by_b_conj(_mm_castsi128_ps(v0), v1);
 
@@ -111,6 +172,88 @@ unsafe fn mul_spectrum_in_place_f32_impl(
     }
 }
 
+// Sums squared re/im pairs, so both lanes of every complex hold its squared magnitude
+#[inline]
+#[target_feature(enable = "avx2", enable = "fma")]
+fn _m256_power_ps(a: __m256) -> __m256 {
+    let squared = _mm256_mul_ps(a, a);
+    _mm256_add_ps(squared, _mm256_shuffle_ps::<0b10110001>(squared, squared))
+}
+
+#[target_feature(enable = "avx2", enable = "fma")]
+unsafe fn mul_spectrum_phat_f32_impl(
+    value1: &mut [Complex<f32>],
+    other: &[Complex<f32>],
+    gains: &[f32],
+    len: usize,
+) {
+    unsafe {
+        let v_norm_factor = _mm256_set1_ps((1f64 / len as f64) as f32);
+        let v_min_power = _mm256_set1_ps(f32::MIN_POSITIVE);
+
+        for (i, (dst, kernel)) in value1
+            .chunks_exact_mut(4)
+            .zip(other.chunks_exact(4))
+            .enumerate()
+        {
+            let a0 = _mm256_loadu_ps(dst.as_ptr().cast());
+            let b0 = _mm256_loadu_ps(kernel.as_ptr().cast());
+
+            let product = _m256_fcmul_a_by_b_conj(a0, b0);
+            let power = _m256_power_ps(product);
+            let mut factor = _mm256_div_ps(v_norm_factor, _mm256_sqrt_ps(power));
+            if !gains.is_empty() {
+                let g = _mm_loadu_ps(gains.get_unchecked(i * 4..).as_ptr());
+                let g = _mm256_setr_m128(_mm_unpacklo_ps(g, g), _mm_unpackhi_ps(g, g));
+                factor = _mm256_mul_ps(factor, g);
+            }
+            let mask = _mm256_cmp_ps::<_CMP_GT_OQ>(power, v_min_power);
+            let d0 = _mm256_and_ps(_mm256_mul_ps(product, factor), mask);
+
+            _mm256_storeu_ps(dst.as_mut_ptr().cast(), d0);
+        }
+
+        let processed = value1.len().min(other.len()) / 4 * 4;
+        mul_spectrum_phat_impl(
+            value1.chunks_exact_mut(4).into_remainder(),
+            other.chunks_exact(4).remainder(),
+            gains.get(processed..).unwrap_or_default(),
+            len,
+        );
+    }
+}
+
+#[target_feature(enable = "avx2", enable = "fma")]
+unsafe fn mul_spectrum_roth_f32_impl(
+    value1: &mut [Complex<f32>],
+    other: &[Complex<f32>],
+    len: usize,
+) {
+    unsafe {
+        let v_norm_factor = _mm256_set1_ps((1f64 / len as f64) as f32);
+        let v_min_power = _mm256_set1_ps(f32::MIN_POSITIVE);
+
+        for (dst, kernel) in value1.chunks_exact_mut(4).zip(other.chunks_exact(4)) {
+            let a0 = _mm256_loadu_ps(dst.as_ptr().cast());
+            let b0 = _mm256_loadu_ps(kernel.as_ptr().cast());
+
+            let product = _m256_fcmul_a_by_b_conj(a0, b0);
+            let power = _m256_power_ps(b0);
+            let factor = _mm256_div_ps(v_norm_factor, power);
+            let mask = _mm256_cmp_ps::<_CMP_GT_OQ>(power, v_min_power);
+            let d0 = _mm256_and_ps(_mm256_mul_ps(product, factor), mask);
+
+            _mm256_storeu_ps(dst.as_mut_ptr().cast(), d0);
+        }
+
+        mul_spectrum_roth_impl(
+            value1.chunks_exact_mut(4).into_remainder(),
+            other.chunks_exact(4).remainder(),
+            len,
+        );
+    }
+}
+
 // a * b.conj()
 #[inline]
 #[target_feature(enable = "avx2", enable = "fma")]
@@ -137,3 +280,39 @@ fn _mm_fcmul_a_by_b_conj(a: __m128, b: __m128) -> __m128 {
     let mul2 = _mm_shuffle_ps::<0xB1>(mul2, mul2);
     _mm_fmsubadd_ps(a, temp1, mul2)
 }
+
+#[target_feature(enable = "avx2", enable = "fma")]
+unsafe fn mul_spectrum_gains_f32_impl(
+    value1: &mut [Complex<f32>],
+    other: &[Complex<f32>],
+    gains: &[f32],
+    len: usize,
+) {
+    unsafe {
+        let v_norm_factor = _mm256_set1_ps((1f64 / len as f64) as f32);
+
+        for ((dst, kernel), gain) in value1
+            .chunks_exact_mut(4)
+            .zip(other.chunks_exact(4))
+            .zip(gains.chunks_exact(4))
+        {
+            let a0 = _mm256_loadu_ps(dst.as_ptr().cast());
+            let b0 = _mm256_loadu_ps(kernel.as_ptr().cast());
+
+            let g = _mm_loadu_ps(gain.as_ptr());
+            let g = _mm256_setr_m128(_mm_unpacklo_ps(g, g), _mm_unpackhi_ps(g, g));
+            let factor = _mm256_mul_ps(v_norm_factor, g);
+            let d0 = _mm256_mul_ps(_m256_fcmul_a_by_b_conj(a0, b0), factor);
+
+            _mm256_storeu_ps(dst.as_mut_ptr().cast(), d0);
+        }
+
+        let processed = value1.len().min(other.len()).min(gains.len()) / 4 * 4;
+        mul_spectrum_gains_impl(
+            &mut value1[processed..],
+            other.get(processed..).unwrap_or_default(),
+            gains.get(processed..).unwrap_or_default(),
+            len,
+        );
+    }
+}
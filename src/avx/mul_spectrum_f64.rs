@@ -26,7 +26,9 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
-use crate::spectrum::SpectrumMultiplier;
+use crate::spectrum::{
+    SpectrumMultiplier, mul_spectrum_gains_impl, mul_spectrum_phat_impl, mul_spectrum_roth_impl,
+};
 use num_complex::Complex;
 use std::arch::x86_64::*;
 
@@ -36,7 +38,48 @@ pub(crate) struct MulSpectrumDoubleAvxFma {}
 impl SpectrumMultiplier<f64> for MulSpectrumDoubleAvxFma {
     fn mul_spectrum(&self, buffer: &mut [Complex<f64>], other: &[Complex<f64>], len: usize) {
         unsafe {
-            mul_spectrum_in_place_f64_impl(buffer, other, len);
+            mul_spectrum_in_place_f64_impl::<true>(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_convolve(
+        &self,
+        buffer: &mut [Complex<f64>],
+        other: &[Complex<f64>],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_in_place_f64_impl::<false>(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_phat(
+        &self,
+        buffer: &mut [Complex<f64>],
+        other: &[Complex<f64>],
+        gains: &[f64],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_phat_f64_impl(buffer, other, gains, len);
+        }
+    }
+
+    fn mul_spectrum_roth(&self, buffer: &mut [Complex<f64>], other: &[Complex<f64>], len: usize) {
+        unsafe {
+            mul_spectrum_roth_f64_impl(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_gains(
+        &self,
+        buffer: &mut [Complex<f64>],
+        other: &[Complex<f64>],
+        gains: &[f64],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_gains_f64_impl(buffer, other, gains, len);
         }
     }
 }
@@ -91,7 +134,7 @@ unsafe fn _mm_fcmul_pd_conj_b(a: __m128d, b: __m128d) -> __m128d {
 }
 
 #[target_feature(enable = "avx2", enable = "fma")]
-unsafe fn mul_spectrum_in_place_f64_impl(
+unsafe fn mul_spectrum_in_place_f64_impl<const CONJ: bool>(
     value1: &mut [Complex<f64>],
     other: &[Complex<f64>],
     len: usize,
@@ -100,6 +143,8 @@ unsafe fn mul_spectrum_in_place_f64_impl(
         let normalization_factor = 1f64 / len as f64;
 
         let v_norm_factor = _mm256_set1_pd(normalization_factor);
+        // Products are taken against the conjugate, so a convolution conjugates the kernel first
+        let v_conj = _mm256_setr_pd(0.0, -0.0, 0.0, -0.0);
         let value1 = &mut value1[..];
         let other = &other;
 
@@ -109,10 +154,17 @@ unsafe fn mul_spectrum_in_place_f64_impl(
             let vd2 = _mm256_loadu_pd(dst.get_unchecked(4..).as_ptr().cast());
             let vd3 = _mm256_loadu_pd(dst.get_unchecked(6..).as_ptr().cast());
 
-            let vk0 = _mm256_loadu_pd(kernel.as_ptr().cast());
-            let vk1 = _mm256_loadu_pd(kernel.get_unchecked(2..).as_ptr().cast());
-            let vk2 = _mm256_loadu_pd(kernel.get_unchecked(4..).as_ptr().cast());
-            let vk3 = _mm256_loadu_pd(kernel.get_unchecked(6..).as_ptr().cast());
+            let mut vk0 = _mm256_loadu_pd(kernel.as_ptr().cast());
+            let mut vk1 = _mm256_loadu_pd(kernel.get_unchecked(2..).as_ptr().cast());
+            let mut vk2 = _mm256_loadu_pd(kernel.get_unchecked(4..).as_ptr().cast());
+            let mut vk3 = _mm256_loadu_pd(kernel.get_unchecked(6..).as_ptr().cast());
+
+            if !CONJ {
+                vk0 = _mm256_xor_pd(vk0, v_conj);
+                vk1 = _mm256_xor_pd(vk1, v_conj);
+                vk2 = _mm256_xor_pd(vk2, v_conj);
+                vk3 = _mm256_xor_pd(vk3, v_conj);
+            }
 
             let d0 = _mm256_mul_pd(_m256_fcmul_pd_conj_b(vd0, vk0), v_norm_factor);
             let d1 = _mm256_mul_pd(_m256_fcmul_pd_conj_b(vd1, vk1), v_norm_factor);
@@ -130,7 +182,11 @@ unsafe fn mul_spectrum_in_place_f64_impl(
 
         for (dst, kernel) in dst_rem.chunks_exact_mut(2).zip(src_rem.chunks_exact(2)) {
             let a0 = _mm256_loadu_pd(dst.as_ptr().cast());
-            let b0 = _mm256_loadu_pd(kernel.as_ptr().cast());
+            let mut b0 = _mm256_loadu_pd(kernel.as_ptr().cast());
+
+            if !CONJ {
+                b0 = _mm256_xor_pd(b0, v_conj);
+            }
 
             let d0 = _mm256_mul_pd(_m256_fcmul_pd_conj_b(a0, b0), v_norm_factor);
 
@@ -142,7 +198,11 @@ unsafe fn mul_spectrum_in_place_f64_impl(
 
         for (dst, kernel) in dst_rem.iter_mut().zip(src_rem.iter()) {
             let v0 = _mm_loadu_pd(dst as *const Complex<f64> as *const _);
-            let v1 = _mm_loadu_pd(kernel as *const Complex<f64> as *const _);
+            let mut v1 = _mm_loadu_pd(kernel as *const Complex<f64> as *const _);
+
+            if !CONJ {
+                v1 = _mm_xor_pd(v1, _mm256_castpd256_pd128(v_conj));
+            }
 
             let lo = _mm_mul_pd(
                 _mm_fcmul_pd_conj_b(v0, v1),
@@ -153,3 +213,121 @@ unsafe fn mul_spectrum_in_place_f64_impl(
         }
     }
 }
+
+// Sums squared re/im pairs, so both lanes of every complex hold its squared magnitude
+#[inline]
+#[target_feature(enable = "avx2", enable = "fma")]
+fn _m256_power_pd(a: __m256d) -> __m256d {
+    let squared = _mm256_mul_pd(a, a);
+    _mm256_add_pd(squared, _mm256_shuffle_pd::<0b0101>(squared, squared))
+}
+
+#[target_feature(enable = "avx2", enable = "fma")]
+unsafe fn mul_spectrum_phat_f64_impl(
+    value1: &mut [Complex<f64>],
+    other: &[Complex<f64>],
+    gains: &[f64],
+    len: usize,
+) {
+    unsafe {
+        let v_norm_factor = _mm256_set1_pd(1f64 / len as f64);
+        let v_min_power = _mm256_set1_pd(f64::MIN_POSITIVE);
+
+        for (i, (dst, kernel)) in value1
+            .chunks_exact_mut(2)
+            .zip(other.chunks_exact(2))
+            .enumerate()
+        {
+            let a0 = _mm256_loadu_pd(dst.as_ptr().cast());
+            let b0 = _mm256_loadu_pd(kernel.as_ptr().cast());
+
+            let product = _m256_fcmul_pd_conj_b(a0, b0);
+            let power = _m256_power_pd(product);
+            let mut factor = _mm256_div_pd(v_norm_factor, _mm256_sqrt_pd(power));
+            if !gains.is_empty() {
+                let g = _mm_loadu_pd(gains.get_unchecked(i * 2..).as_ptr());
+                let g = _mm256_permute4x64_pd::<0b01_01_00_00>(_mm256_castpd128_pd256(g));
+                factor = _mm256_mul_pd(factor, g);
+            }
+            let mask = _mm256_cmp_pd::<_CMP_GT_OQ>(power, v_min_power);
+            let d0 = _mm256_and_pd(_mm256_mul_pd(product, factor), mask);
+
+            _mm256_storeu_pd(dst.as_mut_ptr().cast(), d0);
+        }
+
+        let processed = value1.len().min(other.len()) / 2 * 2;
+        mul_spectrum_phat_impl(
+            value1.chunks_exact_mut(2).into_remainder(),
+            other.chunks_exact(2).remainder(),
+            gains.get(processed..).unwrap_or_default(),
+            len,
+        );
+    }
+}
+
+#[target_feature(enable = "avx2", enable = "fma")]
+unsafe fn mul_spectrum_roth_f64_impl(
+    value1: &mut [Complex<f64>],
+    other: &[Complex<f64>],
+    len: usize,
+) {
+    unsafe {
+        let v_norm_factor = _mm256_set1_pd(1f64 / len as f64);
+        let v_min_power = _mm256_set1_pd(f64::MIN_POSITIVE);
+
+        for (dst, kernel) in value1.chunks_exact_mut(2).zip(other.chunks_exact(2)) {
+            let a0 = _mm256_loadu_pd(dst.as_ptr().cast());
+            let b0 = _mm256_loadu_pd(kernel.as_ptr().cast());
+
+            let product = _m256_fcmul_pd_conj_b(a0, b0);
+            let power = _m256_power_pd(b0);
+            let factor = _mm256_div_pd(v_norm_factor, power);
+            let mask = _mm256_cmp_pd::<_CMP_GT_OQ>(power, v_min_power);
+            let d0 = _mm256_and_pd(_mm256_mul_pd(product, factor), mask);
+
+            _mm256_storeu_pd(dst.as_mut_ptr().cast(), d0);
+        }
+
+        mul_spectrum_roth_impl(
+            value1.chunks_exact_mut(2).into_remainder(),
+            other.chunks_exact(2).remainder(),
+            len,
+        );
+    }
+}
+
+#[target_feature(enable = "avx2", enable = "fma")]
+unsafe fn mul_spectrum_gains_f64_impl(
+    value1: &mut [Complex<f64>],
+    other: &[Complex<f64>],
+    gains: &[f64],
+    len: usize,
+) {
+    unsafe {
+        let v_norm_factor = _mm256_set1_pd(1f64 / len as f64);
+
+        for ((dst, kernel), gain) in value1
+            .chunks_exact_mut(2)
+            .zip(other.chunks_exact(2))
+            .zip(gains.chunks_exact(2))
+        {
+            let a0 = _mm256_loadu_pd(dst.as_ptr().cast());
+            let b0 = _mm256_loadu_pd(kernel.as_ptr().cast());
+
+            let g = _mm_loadu_pd(gain.as_ptr());
+            let g = _mm256_permute4x64_pd::<0b01_01_00_00>(_mm256_castpd128_pd256(g));
+            let factor = _mm256_mul_pd(v_norm_factor, g);
+            let d0 = _mm256_mul_pd(_m256_fcmul_pd_conj_b(a0, b0), factor);
+
+            _mm256_storeu_pd(dst.as_mut_ptr().cast(), d0);
+        }
+
+        let processed = value1.len().min(other.len()).min(gains.len()) / 2 * 2;
+        mul_spectrum_gains_impl(
+            &mut value1[processed..],
+            other.get(processed..).unwrap_or_default(),
+            gains.get(processed..).unwrap_or_default(),
+            len,
+        );
+    }
+}
@@ -0,0 +1,184 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::error::try_vec;
+#[cfg(feature = "rayon")]
+use crate::parallel::worker_scratch;
+use crate::real_2d::check_image;
+use crate::{CrossCorrelateError, CrossCorrelateTemplate};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::fmt::Debug;
+
+/// Checks the channel layout and returns the output length of one channel.
+fn check_channels<V, C>(
+    correlator: &C,
+    output: &[V],
+    output_stride: usize,
+    channels: &[V],
+    (channel_stride, channel_length, channel_count): (usize, usize, usize),
+) -> Result<usize, CrossCorrelateError>
+where
+    V: Clone + Debug + Default,
+    C: CrossCorrelateTemplate<V> + ?Sized,
+{
+    if channel_length == 0 {
+        return Err(CrossCorrelateError::BuffersMustNotHaveZeroSize);
+    }
+    let output_length = correlator.output_length(channel_length);
+    check_image(channels, channel_length, channel_count, channel_stride)?;
+    check_image(output, output_length, channel_count, output_stride)?;
+    Ok(output_length)
+}
+
+/// Default implementation of [`CrossCorrelateTemplate::correlate_channels`], correlating
+/// the channels one after another with a single scratch buffer.
+pub(crate) fn correlate_channels_serial<V, C>(
+    correlator: &C,
+    output: &mut [V],
+    output_stride: usize,
+    channels: &[V],
+    layout: (usize, usize, usize),
+) -> Result<(), CrossCorrelateError>
+where
+    V: Clone + Debug + Default,
+    C: CrossCorrelateTemplate<V> + ?Sized,
+{
+    let (channel_stride, channel_length, channel_count) = layout;
+    if channel_count == 0 {
+        return Ok(());
+    }
+    let output_length = check_channels(correlator, output, output_stride, channels, layout)?;
+    let mut scratch = try_vec![V::default(); correlator.scratch_length()];
+    for (dst, channel) in output
+        .chunks_mut(output_stride)
+        .zip(channels.chunks(channel_stride))
+        .take(channel_count)
+    {
+        correlator.correlate_with_scratch(
+            &mut dst[..output_length],
+            &channel[..channel_length],
+            &mut scratch,
+        )?;
+    }
+    Ok(())
+}
+
+/// Default implementation of [`CrossCorrelateTemplate::correlate_channels_managed`].
+pub(crate) fn correlate_channels_managed_serial<V, C>(
+    correlator: &C,
+    channels: &[&[V]],
+) -> Result<Vec<Vec<V>>, CrossCorrelateError>
+where
+    V: Clone + Debug + Default,
+    C: CrossCorrelateTemplate<V> + ?Sized,
+{
+    let mut scratch = try_vec![V::default(); correlator.scratch_length()];
+    let mut outputs = Vec::with_capacity(channels.len());
+    for channel in channels.iter() {
+        let mut output = try_vec![V::default(); correlator.output_length(channel.len())];
+        correlator.correlate_with_scratch(&mut output, channel, &mut scratch)?;
+        outputs.push(output);
+    }
+    Ok(outputs)
+}
+
+/// Parallel implementation of [`CrossCorrelateTemplate::correlate_channels`].
+///
+/// Channels are independent, so with `rayon` they are spread over the thread pool with
+/// one scratch buffer per worker.
+pub(crate) fn correlate_channels<V, C>(
+    correlator: &C,
+    output: &mut [V],
+    output_stride: usize,
+    channels: &[V],
+    layout: (usize, usize, usize),
+) -> Result<(), CrossCorrelateError>
+where
+    V: Clone + Debug + Default + Send + Sync,
+    C: CrossCorrelateTemplate<V> + Sync + ?Sized,
+{
+    #[cfg(feature = "rayon")]
+    {
+        let (channel_stride, channel_length, channel_count) = layout;
+        if channel_count == 0 {
+            return Ok(());
+        }
+        let output_length = check_channels(correlator, output, output_stride, channels, layout)?;
+        output
+            .par_chunks_mut(output_stride)
+            .zip(channels.par_chunks(channel_stride))
+            .take(channel_count)
+            .try_for_each_init(
+                || worker_scratch(correlator.scratch_length()),
+                |scratch, (dst, channel)| {
+                    let scratch = scratch.as_mut().map_err(|e| e.clone())?;
+                    correlator.correlate_with_scratch(
+                        &mut dst[..output_length],
+                        &channel[..channel_length],
+                        scratch,
+                    )
+                },
+            )
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        correlate_channels_serial(correlator, output, output_stride, channels, layout)
+    }
+}
+
+/// Parallel implementation of [`CrossCorrelateTemplate::correlate_channels_managed`].
+pub(crate) fn correlate_channels_managed<V, C>(
+    correlator: &C,
+    channels: &[&[V]],
+) -> Result<Vec<Vec<V>>, CrossCorrelateError>
+where
+    V: Clone + Debug + Default + Send + Sync,
+    C: CrossCorrelateTemplate<V> + Sync + ?Sized,
+{
+    #[cfg(feature = "rayon")]
+    {
+        channels
+            .par_iter()
+            .map_init(
+                || worker_scratch(correlator.scratch_length()),
+                |scratch, channel| {
+                    let scratch = scratch.as_mut().map_err(|e| e.clone())?;
+                    let mut output =
+                        try_vec![V::default(); correlator.output_length(channel.len())];
+                    correlator.correlate_with_scratch(&mut output, channel, scratch)?;
+                    Ok(output)
+                },
+            )
+            .collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        correlate_channels_managed_serial(correlator, channels)
+    }
+}
@@ -0,0 +1,226 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::batch;
+use crate::error::try_vec;
+use crate::extract::extract_correlation;
+use crate::fft::ComplexFft;
+use crate::normalization::{NormalizeSample, signal_energy};
+use crate::pad::pad_signal;
+use crate::spectrum::SpectrumMultiplier;
+use crate::weighting::SpectrumWeighting;
+use crate::{
+    CorrelateOptions, CorrelateSample, CrossCorrelateError, CrossCorrelateTemplate,
+    CrossCorrelationMode, CrossCorrelationNormalization, FftSizePolicy,
+};
+use num_complex::Complex;
+use std::sync::Arc;
+
+pub(crate) struct CrossCorrelateComplexTemplate<T: CorrelateSample> {
+    pub(crate) fft_forward: Arc<dyn ComplexFft<T> + Send + Sync>,
+    pub(crate) fft_inverse: Arc<dyn ComplexFft<T> + Send + Sync>,
+    pub(crate) multiplier: Arc<dyn SpectrumMultiplier<T> + Send + Sync>,
+    pub(crate) mode: CrossCorrelationMode,
+    pub(crate) normalization: CrossCorrelationNormalization,
+    pub(crate) weighting: SpectrumWeighting<T>,
+    pub(crate) fft_size_policy: FftSizePolicy,
+    pub(crate) template_spectrum: Vec<Complex<T>>,
+    pub(crate) template_length: usize,
+    pub(crate) template_energy: f64,
+}
+
+impl<T: CorrelateSample> CrossCorrelateComplexTemplate<T>
+where
+    Complex<T>: NormalizeSample,
+{
+    pub(crate) fn new(
+        fft_forward: Arc<dyn ComplexFft<T> + Send + Sync>,
+        fft_inverse: Arc<dyn ComplexFft<T> + Send + Sync>,
+        multiplier: Arc<dyn SpectrumMultiplier<T> + Send + Sync>,
+        options: &CorrelateOptions,
+        template: &[Complex<T>],
+    ) -> Result<Self, CrossCorrelateError> {
+        if template.is_empty() {
+            return Err(CrossCorrelateError::BuffersMustNotHaveZeroSize);
+        }
+        if fft_forward.length() != fft_inverse.length() {
+            return Err(CrossCorrelateError::FftSizesDoNotMatch(
+                fft_forward.length(),
+                fft_inverse.length(),
+            ));
+        }
+        let mut template_spectrum = try_vec![Complex::<T>::default(); fft_forward.length()];
+        pad_signal(&mut template_spectrum, template);
+        fft_forward.execute(&mut template_spectrum)?;
+        let weighting = options.weighting.resolve(fft_forward.length())?;
+        Ok(CrossCorrelateComplexTemplate {
+            fft_forward,
+            fft_inverse,
+            multiplier,
+            mode: options.mode,
+            normalization: options.normalization,
+            weighting,
+            fft_size_policy: options.fft_size_policy,
+            template_spectrum,
+            template_length: template.len(),
+            template_energy: signal_energy(template),
+        })
+    }
+
+    /// Values of work space handed to one transform at a time.
+    fn executor_scratch_length(&self) -> usize {
+        self.fft_forward
+            .scratch_length()
+            .max(self.fft_inverse.scratch_length())
+    }
+}
+
+impl<T: CorrelateSample> CrossCorrelateTemplate<Complex<T>> for CrossCorrelateComplexTemplate<T>
+where
+    Complex<T>: NormalizeSample,
+{
+    fn correlate(
+        &self,
+        output: &mut [Complex<T>],
+        buffer: &[Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        let mut scratch = try_vec![Complex::<T>::default(); self.scratch_length()];
+        self.correlate_with_scratch(output, buffer, &mut scratch)
+    }
+
+    fn correlate_managed(
+        &self,
+        buffer: &[Complex<T>],
+    ) -> Result<Vec<Complex<T>>, CrossCorrelateError> {
+        let data_length = self.mode.get_size(buffer.len(), self.template_length);
+        let mut output = try_vec![Complex::<T>::default(); data_length];
+        self.correlate(&mut output, buffer).map(|_| output)
+    }
+
+    fn scratch_length(&self) -> usize {
+        self.fft_forward.length() + self.executor_scratch_length()
+    }
+
+    fn template_length(&self) -> usize {
+        self.template_length
+    }
+
+    fn output_length(&self, buffer_length: usize) -> usize {
+        self.mode.get_size(buffer_length, self.template_length)
+    }
+
+    fn correlate_with_scratch(
+        &self,
+        output: &mut [Complex<T>],
+        buffer: &[Complex<T>],
+        scratch: &mut [Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        if buffer.is_empty() || output.is_empty() {
+            return Err(CrossCorrelateError::BuffersMustNotHaveZeroSize);
+        }
+        let data_length = self.mode.get_size(buffer.len(), self.template_length);
+        let fft_size = self
+            .fft_size_policy
+            .fft_size(self.mode, buffer.len(), self.template_length);
+
+        if fft_size != self.fft_forward.length() {
+            return Err(CrossCorrelateError::FftAndBuffersSizeDoNotMatch(
+                self.fft_forward.length(),
+                fft_size,
+            ));
+        }
+
+        if output.len() != data_length {
+            return Err(CrossCorrelateError::OutputSizeDoNotMatch(
+                data_length,
+                output.len(),
+            ));
+        }
+
+        let scratch_length = self.scratch_length();
+        if scratch.len() < scratch_length {
+            return Err(CrossCorrelateError::ScratchSizeIsTooSmall(
+                scratch_length,
+                scratch.len(),
+            ));
+        }
+
+        let (padded_src, work) = scratch[..scratch_length].split_at_mut(fft_size);
+
+        pad_signal(padded_src, buffer);
+        self.fft_forward.execute_with_scratch(padded_src, work)?;
+        self.weighting.multiply(
+            self.multiplier.as_ref(),
+            padded_src,
+            &self.template_spectrum,
+            fft_size,
+        );
+        self.fft_inverse.execute_with_scratch(padded_src, work)?;
+
+        let scale =
+            self.normalization
+                .lag_scale(self.mode, buffer.len(), self.template_length, || {
+                    signal_energy(buffer) * self.template_energy
+                });
+        extract_correlation(
+            output,
+            padded_src,
+            self.mode,
+            buffer.len(),
+            self.template_length,
+            &scale,
+        );
+
+        Ok(())
+    }
+
+    fn correlate_channels(
+        &self,
+        output: &mut [Complex<T>],
+        output_stride: usize,
+        channels: &[Complex<T>],
+        channel_stride: usize,
+        channel_length: usize,
+        channel_count: usize,
+    ) -> Result<(), CrossCorrelateError> {
+        batch::correlate_channels(
+            self,
+            output,
+            output_stride,
+            channels,
+            (channel_stride, channel_length, channel_count),
+        )
+    }
+
+    fn correlate_channels_managed(
+        &self,
+        channels: &[&[Complex<T>]],
+    ) -> Result<Vec<Vec<Complex<T>>>, CrossCorrelateError> {
+        batch::correlate_channels_managed(self, channels)
+    }
+}
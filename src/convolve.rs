@@ -0,0 +1,399 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::correlate_complex::CrossCorrelateComplex;
+use crate::fft::{FftPlanner, ZaftPlanner};
+use crate::real::CrossCorrelateReal;
+use crate::weighting::SpectrumWeighting;
+use crate::{
+    CorrelateOptions, CorrelateSample, CrossCorrelate, CrossCorrelateError, CrossCorrelationMode,
+    CrossCorrelationNormalization, FftPlanCache,
+};
+use num_complex::Complex;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Selects which product of the two spectra an FFT engine forms.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub(crate) enum SpectrumProduct {
+    /// `X * conj(Y)`, the cross-correlation.
+    #[default]
+    Correlation,
+    /// `X * Y`, the linear convolution.
+    Convolution,
+}
+
+/// Trait for computing the linear convolution of two signals.
+///
+/// Output lengths follow [`CrossCorrelationMode`]: `Full` returns all
+/// `n + m - 1` samples, `Same` the centered `max(n, m)` samples and `Valid`
+/// only those where the shorter signal fully overlaps the longer one.
+pub trait Convolution<V: Clone + Debug + Default> {
+    /// Compute convolution and store in pre-allocated output slice.
+    fn convolve(
+        &self,
+        output: &mut [V],
+        buffer: &[V],
+        other: &[V],
+    ) -> Result<(), CrossCorrelateError>;
+    /// Compute convolution and return a new `Vec<V>` with the result.
+    fn convolve_managed(&self, buffer: &[V], other: &[V]) -> Result<Vec<V>, CrossCorrelateError>;
+    /// Returns the number of `V` elements [`Convolution::convolve_with_scratch`]
+    /// requires for its scratch buffer.
+    fn scratch_length(&self) -> usize;
+    /// Compute convolution using a caller-provided scratch buffer.
+    ///
+    /// `scratch` must hold at least [`Convolution::scratch_length`] elements.
+    fn convolve_with_scratch(
+        &self,
+        output: &mut [V],
+        buffer: &[V],
+        other: &[V],
+        scratch: &mut [V],
+    ) -> Result<(), CrossCorrelateError>;
+}
+
+impl<T: CorrelateSample> Convolution<T> for CrossCorrelateReal<T>
+where
+    CrossCorrelateReal<T>: CrossCorrelate<T>,
+{
+    fn convolve(
+        &self,
+        output: &mut [T],
+        buffer: &[T],
+        other: &[T],
+    ) -> Result<(), CrossCorrelateError> {
+        self.correlate(output, buffer, other)
+    }
+
+    fn convolve_managed(&self, buffer: &[T], other: &[T]) -> Result<Vec<T>, CrossCorrelateError> {
+        self.correlate_managed(buffer, other)
+    }
+
+    fn scratch_length(&self) -> usize {
+        CrossCorrelate::scratch_length(self)
+    }
+
+    fn convolve_with_scratch(
+        &self,
+        output: &mut [T],
+        buffer: &[T],
+        other: &[T],
+        scratch: &mut [T],
+    ) -> Result<(), CrossCorrelateError> {
+        self.correlate_with_scratch(output, buffer, other, scratch)
+    }
+}
+
+impl<T: CorrelateSample> Convolution<Complex<T>> for CrossCorrelateComplex<T>
+where
+    CrossCorrelateComplex<T>: CrossCorrelate<Complex<T>>,
+{
+    fn convolve(
+        &self,
+        output: &mut [Complex<T>],
+        buffer: &[Complex<T>],
+        other: &[Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        self.correlate(output, buffer, other)
+    }
+
+    fn convolve_managed(
+        &self,
+        buffer: &[Complex<T>],
+        other: &[Complex<T>],
+    ) -> Result<Vec<Complex<T>>, CrossCorrelateError> {
+        self.correlate_managed(buffer, other)
+    }
+
+    fn scratch_length(&self) -> usize {
+        CrossCorrelate::scratch_length(self)
+    }
+
+    fn convolve_with_scratch(
+        &self,
+        output: &mut [Complex<T>],
+        buffer: &[Complex<T>],
+        other: &[Complex<T>],
+        scratch: &mut [Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        self.correlate_with_scratch(output, buffer, other, scratch)
+    }
+}
+
+/// Factory for FFT-based linear convolution.
+///
+/// Convolvers share the FFT engines and SIMD spectrum multipliers with
+/// [`Correlate`](crate::Correlate), only the kernel spectrum is not conjugated
+/// and the result is read without the correlation lag offset.
+pub struct Convolve {}
+
+impl Convolve {
+    /// Create a real-valued convolver for any [`CorrelateSample`] from [`CorrelateOptions`].
+    ///
+    /// The mode and FFT size policy apply as they do for [`Correlate`](crate::Correlate);
+    /// the convolution always runs through the FFT and is not normalized or weighted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - Either length is zero or the mode selects no samples for them.
+    /// - The options ask for [`CorrelationMethod::Direct`](crate::CorrelationMethod::Direct),
+    ///   a normalization or a weighting.
+    /// - The FFT plan cannot be created.
+    ///
+    pub fn create_with_options<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn Convolution<T> + Sync + Send>, CrossCorrelateError> {
+        Ok(Arc::new(Convolve::make_real(
+            &ZaftPlanner,
+            buffer_length,
+            other_length,
+            options,
+        )?))
+    }
+
+    /// Create a complex-valued convolver for any [`CorrelateSample`] from [`CorrelateOptions`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Convolve::create_with_options`].
+    ///
+    pub fn create_complex_with_options<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn Convolution<Complex<T>> + Sync + Send>, CrossCorrelateError> {
+        Ok(Arc::new(Convolve::make_complex(
+            &ZaftPlanner,
+            buffer_length,
+            other_length,
+            options,
+        )?))
+    }
+
+    /// Create a real-valued convolver taking its FFT plans from an [`FftPlanCache`].
+    ///
+    /// Behaves like [`Convolve::create_with_options`], except that convolvers and
+    /// correlators sharing `cache` reuse the plans of equal FFT size.
+    ///
+    /// # Errors
+    ///
+    /// See [`Convolve::create_with_options`].
+    ///
+    pub fn create_with_cache<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        cache: &FftPlanCache<T>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn Convolution<T> + Sync + Send>, CrossCorrelateError> {
+        Ok(Arc::new(Convolve::make_real(
+            cache,
+            buffer_length,
+            other_length,
+            options,
+        )?))
+    }
+
+    /// Create a complex-valued convolver taking its FFT plans from an [`FftPlanCache`].
+    ///
+    /// Behaves like [`Convolve::create_complex_with_options`], except that convolvers and
+    /// correlators sharing `cache` reuse the plans of equal FFT size.
+    ///
+    /// # Errors
+    ///
+    /// See [`Convolve::create_with_options`].
+    ///
+    pub fn create_complex_with_cache<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        cache: &FftPlanCache<T>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn Convolution<Complex<T>> + Sync + Send>, CrossCorrelateError> {
+        Ok(Arc::new(Convolve::make_complex(
+            cache,
+            buffer_length,
+            other_length,
+            options,
+        )?))
+    }
+
+    /// Create a real-valued `f32` convolver.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_length` - Length of the first input signal.
+    /// * `other_length` - Length of the second input signal.
+    /// * `mode` - The [`CrossCorrelationMode`] selecting the output samples.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if either length is zero, the mode selects no
+    /// samples for them or the FFT plan cannot be created.
+    ///
+    pub fn create_real_f32(
+        buffer_length: usize,
+        other_length: usize,
+        mode: CrossCorrelationMode,
+    ) -> Result<Arc<dyn Convolution<f32> + Sync + Send>, CrossCorrelateError> {
+        Convolve::create_with_options::<f32>(
+            buffer_length,
+            other_length,
+            &CorrelateOptions::new().mode(mode),
+        )
+    }
+
+    /// Create a real-valued `f64` convolver.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_length` - Length of the first input signal.
+    /// * `other_length` - Length of the second input signal.
+    /// * `mode` - The [`CrossCorrelationMode`] selecting the output samples.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if either length is zero, the mode selects no
+    /// samples for them or the FFT plan cannot be created.
+    ///
+    pub fn create_real_f64(
+        buffer_length: usize,
+        other_length: usize,
+        mode: CrossCorrelationMode,
+    ) -> Result<Arc<dyn Convolution<f64> + Sync + Send>, CrossCorrelateError> {
+        Convolve::create_with_options::<f64>(
+            buffer_length,
+            other_length,
+            &CorrelateOptions::new().mode(mode),
+        )
+    }
+
+    /// Create a complex-valued `f32` convolver.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_length` - Length of the first input signal.
+    /// * `other_length` - Length of the second input signal.
+    /// * `mode` - The [`CrossCorrelationMode`] selecting the output samples.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if either length is zero, the mode selects no
+    /// samples for them or the FFT plan cannot be created.
+    ///
+    pub fn create_complex_f32(
+        buffer_length: usize,
+        other_length: usize,
+        mode: CrossCorrelationMode,
+    ) -> Result<Arc<dyn Convolution<Complex<f32>> + Sync + Send>, CrossCorrelateError> {
+        Convolve::create_complex_with_options::<f32>(
+            buffer_length,
+            other_length,
+            &CorrelateOptions::new().mode(mode),
+        )
+    }
+
+    /// Create a complex-valued `f64` convolver.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_length` - Length of the first input signal.
+    /// * `other_length` - Length of the second input signal.
+    /// * `mode` - The [`CrossCorrelationMode`] selecting the output samples.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if either length is zero, the mode selects no
+    /// samples for them or the FFT plan cannot be created.
+    ///
+    pub fn create_complex_f64(
+        buffer_length: usize,
+        other_length: usize,
+        mode: CrossCorrelationMode,
+    ) -> Result<Arc<dyn Convolution<Complex<f64>> + Sync + Send>, CrossCorrelateError> {
+        Convolve::create_complex_with_options::<f64>(
+            buffer_length,
+            other_length,
+            &CorrelateOptions::new().mode(mode),
+        )
+    }
+
+    fn make_real<T: CorrelateSample>(
+        planner: &dyn FftPlanner<T>,
+        buffer_length: usize,
+        other_length: usize,
+        options: &CorrelateOptions,
+    ) -> Result<CrossCorrelateReal<T>, CrossCorrelateError> {
+        options.check_lengths(buffer_length, other_length)?;
+        Convolve::check_options(options)?;
+        let fft_size = options
+            .fft_size_policy
+            .fft_size(options.mode, buffer_length, other_length);
+        Ok(CrossCorrelateReal {
+            fft_forward: planner.plan_real_to_complex(fft_size)?,
+            fft_inverse: planner.plan_complex_to_real(fft_size)?,
+            multiplier: T::make_spectrum_multiplier(),
+            mode: options.mode,
+            normalization: CrossCorrelationNormalization::None,
+            weighting: SpectrumWeighting::None,
+            product: SpectrumProduct::Convolution,
+            fft_size_policy: options.fft_size_policy,
+        })
+    }
+
+    fn make_complex<T: CorrelateSample>(
+        planner: &dyn FftPlanner<T>,
+        buffer_length: usize,
+        other_length: usize,
+        options: &CorrelateOptions,
+    ) -> Result<CrossCorrelateComplex<T>, CrossCorrelateError> {
+        options.check_lengths(buffer_length, other_length)?;
+        Convolve::check_options(options)?;
+        let fft_size = options
+            .fft_size_policy
+            .fft_size(options.mode, buffer_length, other_length);
+        Ok(CrossCorrelateComplex {
+            fft_forward: planner.plan_forward(fft_size)?,
+            fft_inverse: planner.plan_inverse(fft_size)?,
+            multiplier: T::make_spectrum_multiplier(),
+            mode: options.mode,
+            normalization: CrossCorrelationNormalization::None,
+            weighting: SpectrumWeighting::None,
+            product: SpectrumProduct::Convolution,
+            fft_size_policy: options.fft_size_policy,
+        })
+    }
+
+    fn check_options(options: &CorrelateOptions) -> Result<(), CrossCorrelateError> {
+        options.check_fft_method()?;
+        options.check_no_normalization()?;
+        options.check_no_weighting()
+    }
+}
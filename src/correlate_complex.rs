@@ -26,28 +26,77 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
+use crate::convolve::SpectrumProduct;
 use crate::error::try_vec;
-use crate::fast_divider::DividerUsize;
+use crate::extract::{extract_convolution, extract_correlation};
+use crate::fft::ComplexFft;
+use crate::normalization::{NormalizeSample, signal_energy};
 use crate::pad::pad_signal;
+use crate::parallel::{PARALLEL_FFT_SIZE, join};
 use crate::spectrum::SpectrumMultiplier;
-use crate::{CorrelateSample, CrossCorrelate, CrossCorrelateError, CrossCorrelationMode};
+use crate::weighting::SpectrumWeighting;
+use crate::{
+    CorrelateSample, CrossCorrelate, CrossCorrelateError, CrossCorrelationMode,
+    CrossCorrelationNormalization, FftSizePolicy,
+};
 use num_complex::Complex;
 use std::sync::Arc;
-use zaft::FftExecutor;
 
 pub(crate) struct CrossCorrelateComplex<T: CorrelateSample> {
-    pub(crate) fft_forward: Arc<dyn FftExecutor<T> + Send + Sync>,
-    pub(crate) fft_inverse: Arc<dyn FftExecutor<T> + Send + Sync>,
+    pub(crate) fft_forward: Arc<dyn ComplexFft<T> + Send + Sync>,
+    pub(crate) fft_inverse: Arc<dyn ComplexFft<T> + Send + Sync>,
     pub(crate) multiplier: Arc<dyn SpectrumMultiplier<T> + Send + Sync>,
     pub(crate) mode: CrossCorrelationMode,
+    pub(crate) normalization: CrossCorrelationNormalization,
+    pub(crate) weighting: SpectrumWeighting<T>,
+    pub(crate) product: SpectrumProduct,
+    pub(crate) fft_size_policy: FftSizePolicy,
 }
 
-impl<T: CorrelateSample> CrossCorrelate<Complex<T>> for CrossCorrelateComplex<T> {
+impl<T: CorrelateSample> CrossCorrelateComplex<T> {
+    /// Values of work space handed to one transform at a time.
+    fn executor_scratch_length(&self) -> usize {
+        self.fft_forward
+            .scratch_length()
+            .max(self.fft_inverse.scratch_length())
+    }
+}
+
+impl<T: CorrelateSample> CrossCorrelate<Complex<T>> for CrossCorrelateComplex<T>
+where
+    Complex<T>: NormalizeSample,
+{
     fn correlate(
         &self,
         output: &mut [Complex<T>],
         buffer: &[Complex<T>],
         other: &[Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        let mut scratch = try_vec![Complex::<T>::default(); self.scratch_length()];
+        self.correlate_with_scratch(output, buffer, other, &mut scratch)
+    }
+
+    fn correlate_managed(
+        &self,
+        buffer: &[Complex<T>],
+        other: &[Complex<T>],
+    ) -> Result<Vec<Complex<T>>, CrossCorrelateError> {
+        let data_length = self.mode.get_size(buffer.len(), other.len());
+        let mut output = try_vec![Complex::<T>::default(); data_length];
+        self.correlate(&mut output, buffer, other).map(|_| output)
+    }
+
+    fn scratch_length(&self) -> usize {
+        // Both padded signals, each followed by the work space of its transform
+        (self.fft_forward.length() + self.executor_scratch_length()) * 2
+    }
+
+    fn correlate_with_scratch(
+        &self,
+        output: &mut [Complex<T>],
+        buffer: &[Complex<T>],
+        other: &[Complex<T>],
+        scratch: &mut [Complex<T>],
     ) -> Result<(), CrossCorrelateError> {
         if buffer.is_empty() || other.is_empty() || output.is_empty() {
             return Err(CrossCorrelateError::BuffersMustNotHaveZeroSize);
@@ -59,7 +108,9 @@ impl<T: CorrelateSample> CrossCorrelate<Complex<T>> for CrossCorrelateComplex<T>
             ));
         }
         let data_length = self.mode.get_size(buffer.len(), other.len());
-        let fft_size = self.mode.fft_size(buffer.len(), other.len());
+        let fft_size = self
+            .fft_size_policy
+            .fft_size(self.mode, buffer.len(), other.len());
 
         if fft_size != self.fft_forward.length() {
             return Err(CrossCorrelateError::FftAndBuffersSizeDoNotMatch(
@@ -75,65 +126,66 @@ impl<T: CorrelateSample> CrossCorrelate<Complex<T>> for CrossCorrelateComplex<T>
             ));
         }
 
-        let mut padded_src = pad_signal(buffer, fft_size)?;
-        let mut padded_other = pad_signal(other, fft_size)?;
+        let scratch_length = self.scratch_length();
+        if scratch.len() < scratch_length {
+            return Err(CrossCorrelateError::ScratchSizeIsTooSmall(
+                scratch_length,
+                scratch.len(),
+            ));
+        }
 
-        self.fft_forward
-            .execute(&mut padded_src)
-            .map_err(|x| CrossCorrelateError::FftError(x.to_string()))?;
-        self.fft_forward
-            .execute(&mut padded_other)
-            .map_err(|x| CrossCorrelateError::FftError(x.to_string()))?;
-        self.multiplier
-            .mul_spectrum(&mut padded_src, &padded_other, fft_size);
-        self.fft_inverse
-            .execute(&mut padded_src)
-            .map_err(|x| CrossCorrelateError::FftError(x.to_string()))?;
+        let (src_scratch, other_scratch) =
+            scratch[..scratch_length].split_at_mut(scratch_length / 2);
+        let (padded_src, work_src) = src_scratch.split_at_mut(fft_size);
+        let (padded_other, work_other) = other_scratch.split_at_mut(fft_size);
 
-        let lag = other.len() - 1;
-        let offset = fft_size - lag;
-        match self.mode {
-            CrossCorrelationMode::Full => {
-                if fft_size == 1 {
-                    for dst in output.iter_mut() {
-                        *dst = unsafe { *padded_src.get_unchecked(0) }
-                    }
-                } else {
-                    let divisor = DividerUsize::new(fft_size);
-                    for (i, dst) in output.iter_mut().enumerate() {
-                        *dst = unsafe { *padded_src.get_unchecked((i + offset) % divisor) }
-                    }
-                }
-            }
-            CrossCorrelationMode::Valid | CrossCorrelationMode::Same => {
-                let start = match self.mode {
-                    CrossCorrelationMode::Valid => other.len() - 1,
-                    CrossCorrelationMode::Same => (other.len() - 1) / 2,
-                    CrossCorrelationMode::Full => unreachable!(),
-                };
-                if fft_size == 1 {
-                    for dst in output.iter_mut() {
-                        *dst = unsafe { *padded_src.get_unchecked(0) }
-                    }
-                } else {
-                    let divisor = DividerUsize::new(fft_size);
-                    for (i, dst) in output.iter_mut().enumerate() {
-                        *dst = unsafe { *padded_src.get_unchecked((start + i + offset) % divisor) };
-                    }
-                }
+        let (src_result, other_result) = join(
+            fft_size >= PARALLEL_FFT_SIZE,
+            || {
+                pad_signal(padded_src, buffer);
+                self.fft_forward.execute_with_scratch(padded_src, work_src)
+            },
+            || {
+                pad_signal(padded_other, other);
+                self.fft_forward
+                    .execute_with_scratch(padded_other, work_other)
+            },
+        );
+        src_result.and(other_result)?;
+        match self.product {
+            SpectrumProduct::Correlation => self.weighting.multiply(
+                self.multiplier.as_ref(),
+                padded_src,
+                padded_other,
+                fft_size,
+            ),
+            SpectrumProduct::Convolution => {
+                self.multiplier
+                    .mul_spectrum_convolve(padded_src, padded_other, fft_size)
             }
         }
+        self.fft_inverse
+            .execute_with_scratch(padded_src, work_src)?;
 
-        Ok(())
-    }
+        if self.product == SpectrumProduct::Convolution {
+            extract_convolution(output, padded_src, self.mode, buffer.len(), other.len());
+            return Ok(());
+        }
 
-    fn correlate_managed(
-        &self,
-        buffer: &[Complex<T>],
-        other: &[Complex<T>],
-    ) -> Result<Vec<Complex<T>>, CrossCorrelateError> {
-        let data_length = self.mode.get_size(buffer.len(), other.len());
-        let mut output = try_vec![Complex::<T>::default(); data_length];
-        self.correlate(&mut output, buffer, other).map(|_| output)
+        let scale = self
+            .normalization
+            .lag_scale(self.mode, buffer.len(), other.len(), || {
+                signal_energy(buffer) * signal_energy(other)
+            });
+        extract_correlation(
+            output,
+            padded_src,
+            self.mode,
+            buffer.len(),
+            other.len(),
+            &scale,
+        );
+
+        Ok(())
     }
 }
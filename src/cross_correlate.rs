@@ -26,13 +26,35 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
+use crate::autocorrelation::{AutocorrelateComplex, AutocorrelateReal};
+use crate::batch;
+use crate::complex_template::CrossCorrelateComplexTemplate;
+use crate::convolve::SpectrumProduct;
 use crate::correlate_complex::CrossCorrelateComplex;
+use crate::direct::CrossCorrelateDirect;
+use crate::fft::{
+    ComplexFft, ComplexToRealFft, FftPlanner, RealToComplexFft, ZaftPlanner, zaft_complex_to_real,
+    zaft_forward, zaft_inverse, zaft_real_to_complex,
+};
+use crate::mixed::CrossCorrelateRealComplex;
+use crate::pairwise::{CorrelationMatrixComplex, CorrelationMatrixReal};
+use crate::parallel::parallel_tasks;
+use crate::phase::{PhaseCorrelation, PhaseCorrelation2d};
 use crate::real::CrossCorrelateReal;
-use crate::{CrossCorrelateError, CrossCorrelationMode};
+use crate::real_2d::CrossCorrelateReal2d;
+use crate::real_template::{CrossCorrelateRealTemplate, template_fft_size};
+use crate::streaming::CrossCorrelateStreaming;
+use crate::zncc::CrossCorrelateZncc;
+use crate::{
+    Autocorrelate, AutocorrelationLags, ChannelPairs, CorrelateOptions, CorrelateSample,
+    CorrelationMatrix, CorrelationMethod, CrossCorrelateError, CrossCorrelationMode,
+    CrossCorrelationNormalization, FftPlanCache, GccWeighting, PeakValue, PhaseCorrelate,
+    PhaseCorrelate2d, StreamingCorrelate,
+};
 use num_complex::Complex;
+use num_traits::AsPrimitive;
 use std::fmt::Debug;
 use std::sync::Arc;
-use zaft::Zaft;
 
 /// Trait for computing cross-correlation between two sequences.
 ///
@@ -50,6 +72,175 @@ pub trait CrossCorrelate<V: Clone + Debug + Default> {
     ) -> Result<(), CrossCorrelateError>;
     /// Compute cross-correlation and return a new `Vec<V>` with the result.
     fn correlate_managed(&self, buffer: &[V], other: &[V]) -> Result<Vec<V>, CrossCorrelateError>;
+    /// Returns the number of `V` elements [`CrossCorrelate::correlate_with_scratch`]
+    /// requires for its scratch buffer.
+    ///
+    /// The length can differ between builds of the crate: with the `rayon` feature the
+    /// real FFT engine keeps both padded signals at once so they can be transformed in
+    /// parallel. Query it on the correlator instead of hardcoding a value.
+    ///
+    /// The default implementation needs no scratch and returns 0.
+    fn scratch_length(&self) -> usize {
+        0
+    }
+    /// Compute cross-correlation using a caller-provided scratch buffer.
+    ///
+    /// `scratch` must hold at least [`CrossCorrelate::scratch_length`] elements.
+    /// Its contents on entry are ignored and on exit are unspecified, so the same
+    /// buffer may be reused across calls to avoid any allocations.
+    ///
+    /// The default implementation ignores `scratch` and forwards to [`CrossCorrelate::correlate`].
+    fn correlate_with_scratch(
+        &self,
+        output: &mut [V],
+        buffer: &[V],
+        other: &[V],
+        scratch: &mut [V],
+    ) -> Result<(), CrossCorrelateError> {
+        let _ = scratch;
+        self.correlate(output, buffer, other)
+    }
+}
+
+/// Trait for cross-correlating a real signal with a complex one.
+///
+/// `B` and `O` are the sample types of `buffer` and `other`, one of them real `T`
+/// and the other `Complex<T>`, and the correlation is always complex. The real side
+/// goes through a real-to-complex FFT instead of being promoted to `Complex<T>`.
+pub trait CrossCorrelateMixed<B, O, V: Clone + Debug + Default> {
+    /// Compute cross-correlation and store in pre-allocated output slice.
+    fn correlate(
+        &self,
+        output: &mut [V],
+        buffer: &[B],
+        other: &[O],
+    ) -> Result<(), CrossCorrelateError>;
+    /// Compute cross-correlation and return a new `Vec<V>` with the result.
+    fn correlate_managed(&self, buffer: &[B], other: &[O]) -> Result<Vec<V>, CrossCorrelateError>;
+    /// Returns the number of `V` elements [`CrossCorrelateMixed::correlate_with_scratch`]
+    /// requires for its scratch buffer.
+    fn scratch_length(&self) -> usize;
+    /// Compute cross-correlation using a caller-provided scratch buffer.
+    ///
+    /// `scratch` must hold at least [`CrossCorrelateMixed::scratch_length`] elements.
+    fn correlate_with_scratch(
+        &self,
+        output: &mut [V],
+        buffer: &[B],
+        other: &[O],
+        scratch: &mut [V],
+    ) -> Result<(), CrossCorrelateError>;
+}
+
+/// Trait for correlating signals against a template fixed at construction time.
+///
+/// The template spectrum is computed once when the correlator is created, so
+/// every call only transforms the incoming `buffer`. This is the matched-filter
+/// counterpart of [`CrossCorrelate`], where `buffer` plays the same role and the
+/// template replaces `other`.
+pub trait CrossCorrelateTemplate<V: Clone + Debug + Default> {
+    /// Compute cross-correlation against the template and store in pre-allocated output slice.
+    fn correlate(&self, output: &mut [V], buffer: &[V]) -> Result<(), CrossCorrelateError>;
+    /// Compute cross-correlation against the template and return a new `Vec<V>` with the result.
+    fn correlate_managed(&self, buffer: &[V]) -> Result<Vec<V>, CrossCorrelateError>;
+    /// Returns the number of `V` elements [`CrossCorrelateTemplate::correlate_with_scratch`]
+    /// requires for its scratch buffer.
+    fn scratch_length(&self) -> usize;
+    /// Returns the length of the template this correlator was created with.
+    fn template_length(&self) -> usize;
+    /// Returns the output length for a buffer of `buffer_length` samples.
+    fn output_length(&self, buffer_length: usize) -> usize;
+    /// Compute cross-correlation against the template using a caller-provided scratch buffer.
+    ///
+    /// `scratch` must hold at least [`CrossCorrelateTemplate::scratch_length`] elements.
+    fn correlate_with_scratch(
+        &self,
+        output: &mut [V],
+        buffer: &[V],
+        scratch: &mut [V],
+    ) -> Result<(), CrossCorrelateError>;
+    /// Correlates `channel_count` equally long channels against the template.
+    ///
+    /// Channel `i` is read from `channels[i * channel_stride..][..channel_length]` and its
+    /// result is written to `output[i * output_stride..][..output_length]`, where
+    /// `output_length` is [`CrossCorrelateTemplate::output_length`] of `channel_length`.
+    /// The template spectrum and a single scratch buffer are shared by all channels.
+    fn correlate_channels(
+        &self,
+        output: &mut [V],
+        output_stride: usize,
+        channels: &[V],
+        channel_stride: usize,
+        channel_length: usize,
+        channel_count: usize,
+    ) -> Result<(), CrossCorrelateError> {
+        batch::correlate_channels_serial(
+            self,
+            output,
+            output_stride,
+            channels,
+            (channel_stride, channel_length, channel_count),
+        )
+    }
+    /// Correlates every channel against the template and returns one output per channel.
+    ///
+    /// Every channel must have the buffer length the correlator was created for. The
+    /// template spectrum and a single scratch buffer are shared by all channels.
+    fn correlate_channels_managed(
+        &self,
+        channels: &[&[V]],
+    ) -> Result<Vec<Vec<V>>, CrossCorrelateError> {
+        batch::correlate_channels_managed_serial(self, channels)
+    }
+}
+
+/// Trait for computing cross-correlation between two row-major images.
+///
+/// Image dimensions are fixed when the correlator is created, and the
+/// [`CrossCorrelationMode`] is applied to each axis independently. Every image is
+/// passed as a slice together with its stride, the distance in elements between the
+/// starts of two consecutive rows, which must be at least the image width.
+pub trait CrossCorrelate2d<V: Clone + Debug + Default> {
+    /// Compute 2D cross-correlation and store in pre-allocated output image.
+    fn correlate(
+        &self,
+        output: &mut [V],
+        output_stride: usize,
+        buffer: &[V],
+        buffer_stride: usize,
+        other: &[V],
+        other_stride: usize,
+    ) -> Result<(), CrossCorrelateError>;
+    /// Compute 2D cross-correlation and return a new densely packed image, whose stride
+    /// is [`CrossCorrelate2d::output_width`].
+    fn correlate_managed(
+        &self,
+        buffer: &[V],
+        buffer_stride: usize,
+        other: &[V],
+        other_stride: usize,
+    ) -> Result<Vec<V>, CrossCorrelateError>;
+    /// Returns the width of the output image.
+    fn output_width(&self) -> usize;
+    /// Returns the height of the output image.
+    fn output_height(&self) -> usize;
+    /// Returns the number of `V` elements [`CrossCorrelate2d::correlate_with_scratch`]
+    /// requires for its scratch buffer.
+    fn scratch_length(&self) -> usize;
+    /// Compute 2D cross-correlation using a caller-provided scratch buffer.
+    ///
+    /// `scratch` must hold at least [`CrossCorrelate2d::scratch_length`] elements.
+    #[allow(clippy::too_many_arguments)]
+    fn correlate_with_scratch(
+        &self,
+        output: &mut [V],
+        output_stride: usize,
+        buffer: &[V],
+        buffer_stride: usize,
+        other: &[V],
+        other_stride: usize,
+        scratch: &mut [V],
+    ) -> Result<(), CrossCorrelateError>;
 }
 
 /// A cross-correlation engine for signals.
@@ -61,10 +252,476 @@ pub trait CrossCorrelate<V: Clone + Debug + Default> {
 pub struct Correlate {}
 
 impl Correlate {
+    /// Create a real-valued cross-correlator for any [`CorrelateSample`] using FFT.
+    ///
+    /// Selects the real-to-complex FFT plans and the SIMD spectrum multiplier
+    /// for `T`, so generic code does not have to dispatch on `f32` and `f64`.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The [`CrossCorrelationMode`] that determines the shape of the output
+    ///   (e.g. `Full`, `Same`, `Valid`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if the FFT plan cannot be created.
+    ///
+    pub fn create<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        mode: CrossCorrelationMode,
+    ) -> Result<Arc<dyn CrossCorrelate<T> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_with_options::<T>(
+            buffer_length,
+            other_length,
+            &CorrelateOptions::new().mode(mode),
+        )
+    }
+
+    /// Create a complex-valued cross-correlator for any [`CorrelateSample`] using FFT.
+    ///
+    /// Selects the complex FFT plans and the SIMD spectrum multiplier for `T`,
+    /// so generic code does not have to dispatch on `f32` and `f64`.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The [`CrossCorrelationMode`] that determines the shape of the output
+    ///   (e.g. `Full`, `Same`, `Valid`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if the FFT plan cannot be created.
+    ///
+    pub fn create_complex<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        mode: CrossCorrelationMode,
+    ) -> Result<Arc<dyn CrossCorrelate<Complex<T>> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_complex_with_options::<T>(
+            buffer_length,
+            other_length,
+            &CorrelateOptions::new().mode(mode),
+        )
+    }
+
+    /// Create a real-valued cross-correlator for any [`CorrelateSample`] from [`CorrelateOptions`].
+    ///
+    /// Every other real factory of two signals is a shorthand for this one with some
+    /// options left at their defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - Either length is zero or the mode selects no lags for them.
+    /// - A [`GccWeighting`] is combined with [`CorrelationMethod::Direct`].
+    /// - The coherence of [`GccWeighting::Ml`] does not have `fft_size / 2 + 1` bins.
+    /// - The FFT plan cannot be created.
+    ///
+    pub fn create_with_options<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelate<T> + Sync + Send>, CrossCorrelateError> {
+        match options.resolve_method(buffer_length, other_length, false)? {
+            CorrelationMethod::Direct => Ok(Arc::new(CrossCorrelateDirect {
+                accumulator: T::make_accumulate(),
+                mode: options.mode,
+                normalization: options.normalization,
+            })),
+            _ => Correlate::make_real::<T>(buffer_length, other_length, options),
+        }
+    }
+
+    /// Create a complex-valued cross-correlator for any [`CorrelateSample`] from [`CorrelateOptions`].
+    ///
+    /// Every other complex factory of two signals is a shorthand for this one with some
+    /// options left at their defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - Either length is zero or the mode selects no lags for them.
+    /// - A [`GccWeighting`] is combined with [`CorrelationMethod::Direct`].
+    /// - The coherence of [`GccWeighting::Ml`] does not have `fft_size` bins.
+    /// - The FFT plan cannot be created.
+    ///
+    pub fn create_complex_with_options<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelate<Complex<T>> + Sync + Send>, CrossCorrelateError> {
+        match options.resolve_method(buffer_length, other_length, true)? {
+            CorrelationMethod::Direct => Ok(Arc::new(CrossCorrelateDirect {
+                accumulator: T::make_complex_accumulate(),
+                mode: options.mode,
+                normalization: options.normalization,
+            })),
+            _ => Correlate::make_complex::<T>(buffer_length, other_length, options),
+        }
+    }
+
+    /// Create a real-valued cross-correlator running on user supplied FFT executors.
+    ///
+    /// Lets the transforms come from a backend other than the default one, or be shared
+    /// between several correlators. The executors must both be planned for
+    /// `options.fft_size_policy.fft_size(mode, buffer_length, other_length)` samples.
+    /// The method set in `options` is not consulted, the correlation always runs through
+    /// the executors.
+    ///
+    /// # Arguments
+    ///
+    /// * `fft_forward` - The [`RealToComplexFft`] transforming both padded signals.
+    /// * `fft_inverse` - The [`ComplexToRealFft`] transforming the cross spectrum back.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - Either length is zero or the mode selects no lags for them.
+    /// - The forward and inverse FFT executors have mismatched lengths.
+    /// - The FFT size selected by `options` does not match the executors.
+    /// - The coherence of [`GccWeighting::Ml`] does not have `fft_size / 2 + 1` bins.
+    ///
+    pub fn create_with_executors<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        fft_forward: Arc<dyn RealToComplexFft<T> + Send + Sync>,
+        fft_inverse: Arc<dyn ComplexToRealFft<T> + Send + Sync>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelate<T> + Sync + Send>, CrossCorrelateError> {
+        options.check_lengths(buffer_length, other_length)?;
+        if fft_forward.real_length() != fft_inverse.real_length() {
+            return Err(CrossCorrelateError::FftSizesDoNotMatch(
+                fft_forward.real_length(),
+                fft_inverse.real_length(),
+            ));
+        }
+        let fft_size = options
+            .fft_size_policy
+            .fft_size(options.mode, buffer_length, other_length);
+        if fft_size != fft_forward.real_length() {
+            return Err(CrossCorrelateError::FftAndBuffersSizeDoNotMatch(
+                fft_forward.real_length(),
+                fft_size,
+            ));
+        }
+        Correlate::assemble_real(fft_forward, fft_inverse, options)
+    }
+
+    /// Create a complex-valued cross-correlator running on user supplied FFT executors.
+    ///
+    /// The complex counterpart of [`Correlate::create_with_executors`], both executors
+    /// must be planned for `options.fft_size_policy.fft_size(mode, buffer_length, other_length)`
+    /// samples and `options.method` is not consulted.
+    ///
+    /// # Arguments
+    ///
+    /// * `fft_forward` - The forward [`ComplexFft`] transforming both padded signals.
+    /// * `fft_inverse` - The inverse [`ComplexFft`] transforming the cross spectrum back.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - Either length is zero or the mode selects no lags for them.
+    /// - The forward and inverse FFT executors have mismatched lengths.
+    /// - The FFT size selected by `options` does not match the executors.
+    /// - The coherence of [`GccWeighting::Ml`] does not have `fft_size` bins.
+    ///
+    pub fn create_complex_with_executors<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        fft_forward: Arc<dyn ComplexFft<T> + Send + Sync>,
+        fft_inverse: Arc<dyn ComplexFft<T> + Send + Sync>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelate<Complex<T>> + Sync + Send>, CrossCorrelateError> {
+        options.check_lengths(buffer_length, other_length)?;
+        if fft_forward.length() != fft_inverse.length() {
+            return Err(CrossCorrelateError::FftSizesDoNotMatch(
+                fft_forward.length(),
+                fft_inverse.length(),
+            ));
+        }
+        let fft_size = options
+            .fft_size_policy
+            .fft_size(options.mode, buffer_length, other_length);
+        if fft_size != fft_forward.length() {
+            return Err(CrossCorrelateError::FftAndBuffersSizeDoNotMatch(
+                fft_forward.length(),
+                fft_size,
+            ));
+        }
+        Correlate::assemble_complex(fft_forward, fft_inverse, options)
+    }
+
+    /// Create a cross-correlator of a real `buffer` with a complex `other`.
+    ///
+    /// The real signal is transformed with a real-to-complex FFT, so it is neither
+    /// promoted to `Complex<T>` nor costs a full complex transform. The correlation
+    /// always runs through the FFT.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - Either length is zero or the mode selects no lags for them.
+    /// - The options ask for [`CorrelationMethod::Direct`].
+    /// - The coherence of [`GccWeighting::Ml`] does not have `fft_size` bins.
+    /// - The FFT plan cannot be created.
+    ///
+    #[allow(clippy::type_complexity)]
+    pub fn create_real_complex<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        options: &CorrelateOptions,
+    ) -> Result<
+        Arc<dyn CrossCorrelateMixed<T, Complex<T>, Complex<T>> + Sync + Send>,
+        CrossCorrelateError,
+    > {
+        Ok(Arc::new(Correlate::make_mixed::<T>(
+            &ZaftPlanner,
+            buffer_length,
+            other_length,
+            options,
+        )?))
+    }
+
+    /// Create a cross-correlator of a complex `buffer` with a real `other`.
+    ///
+    /// The counterpart of [`Correlate::create_real_complex`] with the roles of the
+    /// signals swapped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - Either length is zero or the mode selects no lags for them.
+    /// - The options ask for [`CorrelationMethod::Direct`].
+    /// - The coherence of [`GccWeighting::Ml`] does not have `fft_size` bins.
+    /// - The FFT plan cannot be created.
+    ///
+    #[allow(clippy::type_complexity)]
+    pub fn create_complex_real<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        options: &CorrelateOptions,
+    ) -> Result<
+        Arc<dyn CrossCorrelateMixed<Complex<T>, T, Complex<T>> + Sync + Send>,
+        CrossCorrelateError,
+    > {
+        Ok(Arc::new(Correlate::make_mixed::<T>(
+            &ZaftPlanner,
+            buffer_length,
+            other_length,
+            options,
+        )?))
+    }
+
+    /// Create a cross-correlator of a real `buffer` with a complex `other` taking its FFT
+    /// plans from an [`FftPlanCache`].
+    ///
+    /// Behaves like [`Correlate::create_real_complex`], except that correlators sharing
+    /// `cache` reuse the plans of equal FFT size instead of planning them again.
+    ///
+    /// # Errors
+    ///
+    /// See [`Correlate::create_real_complex`].
+    ///
+    #[allow(clippy::type_complexity)]
+    pub fn create_real_complex_with_cache<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        cache: &FftPlanCache<T>,
+        options: &CorrelateOptions,
+    ) -> Result<
+        Arc<dyn CrossCorrelateMixed<T, Complex<T>, Complex<T>> + Sync + Send>,
+        CrossCorrelateError,
+    > {
+        Ok(Arc::new(Correlate::make_mixed::<T>(
+            cache,
+            buffer_length,
+            other_length,
+            options,
+        )?))
+    }
+
+    /// Create a cross-correlator of a complex `buffer` with a real `other` taking its FFT
+    /// plans from an [`FftPlanCache`].
+    ///
+    /// Behaves like [`Correlate::create_complex_real`], except that correlators sharing
+    /// `cache` reuse the plans of equal FFT size instead of planning them again.
+    ///
+    /// # Errors
+    ///
+    /// See [`Correlate::create_complex_real`].
+    ///
+    #[allow(clippy::type_complexity)]
+    pub fn create_complex_real_with_cache<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        cache: &FftPlanCache<T>,
+        options: &CorrelateOptions,
+    ) -> Result<
+        Arc<dyn CrossCorrelateMixed<Complex<T>, T, Complex<T>> + Sync + Send>,
+        CrossCorrelateError,
+    > {
+        Ok(Arc::new(Correlate::make_mixed::<T>(
+            cache,
+            buffer_length,
+            other_length,
+            options,
+        )?))
+    }
+
+    /// Create a cross-correlator of a real `buffer` with a complex `other` running on
+    /// user supplied FFT executors.
+    ///
+    /// All three executors must be planned for
+    /// `options.fft_size_policy.fft_size(mode, buffer_length, other_length)` samples.
+    /// The method set in `options` is not consulted.
+    ///
+    /// # Arguments
+    ///
+    /// * `fft_real` - The [`RealToComplexFft`] transforming the padded real signal.
+    /// * `fft_forward` - The forward [`ComplexFft`] transforming the padded complex signal.
+    /// * `fft_inverse` - The inverse [`ComplexFft`] transforming the cross spectrum back.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - Either length is zero or the mode selects no lags for them.
+    /// - The executors have mismatched lengths.
+    /// - The FFT size selected by `options` does not match the executors.
+    /// - The coherence of [`GccWeighting::Ml`] does not have `fft_size` bins.
+    ///
+    #[allow(clippy::type_complexity)]
+    pub fn create_real_complex_with_executors<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        fft_real: Arc<dyn RealToComplexFft<T> + Send + Sync>,
+        fft_forward: Arc<dyn ComplexFft<T> + Send + Sync>,
+        fft_inverse: Arc<dyn ComplexFft<T> + Send + Sync>,
+        options: &CorrelateOptions,
+    ) -> Result<
+        Arc<dyn CrossCorrelateMixed<T, Complex<T>, Complex<T>> + Sync + Send>,
+        CrossCorrelateError,
+    > {
+        Ok(Arc::new(Correlate::assemble_mixed(
+            buffer_length,
+            other_length,
+            (fft_real, fft_forward, fft_inverse),
+            options,
+        )?))
+    }
+
+    /// Create a cross-correlator of a complex `buffer` with a real `other` running on
+    /// user supplied FFT executors.
+    ///
+    /// The counterpart of [`Correlate::create_real_complex_with_executors`] with the
+    /// roles of the signals swapped, `fft_real` transforms the padded real `other`.
+    ///
+    /// # Errors
+    ///
+    /// See [`Correlate::create_real_complex_with_executors`].
+    ///
+    #[allow(clippy::type_complexity)]
+    pub fn create_complex_real_with_executors<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        fft_real: Arc<dyn RealToComplexFft<T> + Send + Sync>,
+        fft_forward: Arc<dyn ComplexFft<T> + Send + Sync>,
+        fft_inverse: Arc<dyn ComplexFft<T> + Send + Sync>,
+        options: &CorrelateOptions,
+    ) -> Result<
+        Arc<dyn CrossCorrelateMixed<Complex<T>, T, Complex<T>> + Sync + Send>,
+        CrossCorrelateError,
+    > {
+        Ok(Arc::new(Correlate::assemble_mixed(
+            buffer_length,
+            other_length,
+            (fft_real, fft_forward, fft_inverse),
+            options,
+        )?))
+    }
+
+    /// Create a real-valued cross-correlator taking its FFT plans from an [`FftPlanCache`].
+    ///
+    /// Behaves like [`Correlate::create_with_options`], except that correlators sharing
+    /// `cache` reuse the plans of equal FFT size instead of planning them again.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - Either length is zero or the mode selects no lags for them.
+    /// - A [`GccWeighting`] is combined with [`CorrelationMethod::Direct`].
+    /// - The coherence of [`GccWeighting::Ml`] does not have `fft_size / 2 + 1` bins.
+    /// - The FFT plan cannot be created.
+    ///
+    pub fn create_with_cache<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        cache: &FftPlanCache<T>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelate<T> + Sync + Send>, CrossCorrelateError> {
+        match options.resolve_method(buffer_length, other_length, false)? {
+            CorrelationMethod::Direct => Ok(Arc::new(CrossCorrelateDirect {
+                accumulator: T::make_accumulate(),
+                mode: options.mode,
+                normalization: options.normalization,
+            })),
+            _ => {
+                let fft_size =
+                    options
+                        .fft_size_policy
+                        .fft_size(options.mode, buffer_length, other_length);
+                Correlate::assemble_real(
+                    cache.real_to_complex(fft_size)?,
+                    cache.complex_to_real(fft_size)?,
+                    options,
+                )
+            }
+        }
+    }
+
+    /// Create a complex-valued cross-correlator taking its FFT plans from an [`FftPlanCache`].
+    ///
+    /// Behaves like [`Correlate::create_complex_with_options`], except that correlators
+    /// sharing `cache` reuse the plans of equal FFT size instead of planning them again.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - Either length is zero or the mode selects no lags for them.
+    /// - A [`GccWeighting`] is combined with [`CorrelationMethod::Direct`].
+    /// - The coherence of [`GccWeighting::Ml`] does not have `fft_size` bins.
+    /// - The FFT plan cannot be created.
+    ///
+    pub fn create_complex_with_cache<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        cache: &FftPlanCache<T>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelate<Complex<T>> + Sync + Send>, CrossCorrelateError> {
+        match options.resolve_method(buffer_length, other_length, true)? {
+            CorrelationMethod::Direct => Ok(Arc::new(CrossCorrelateDirect {
+                accumulator: T::make_complex_accumulate(),
+                mode: options.mode,
+                normalization: options.normalization,
+            })),
+            _ => {
+                let fft_size =
+                    options
+                        .fft_size_policy
+                        .fft_size(options.mode, buffer_length, other_length);
+                Correlate::assemble_complex(
+                    cache.forward(fft_size)?,
+                    cache.inverse(fft_size)?,
+                    options,
+                )
+            }
+        }
+    }
+
     /// Create a real-valued cross-correlator using FFT.
     ///
     /// This function constructs a cross-correlator for `f32` signals, based on the
-    /// provided correlation mode and FFT executors. It uses the forward FFT to
+    /// provided correlation mode. It uses the forward FFT to
     /// transform both input signals, multiplies one by the conjugate spectrum of the other,
     /// and then applies the inverse FFT to obtain the cross-correlation result.
     ///
@@ -76,88 +733,24 @@ impl Correlate {
     /// # Returns
     ///
     /// A boxed [`CrossCorrelate`] instance implementing cross-correlation on `f32` signals,
-    /// or an error if the FFT sizes do not match.
+    /// or an error if the FFT plans cannot be created.
     ///
     /// # Errors
     ///
-    /// Returns [`CrossCorrelateError`] if:
-    /// - The forward and inverse FFT executors have mismatched lengths.
-    /// - The FFT size computed from the input buffers does not match the provided executors.
+    /// Returns [`CrossCorrelateError`] if the FFT plan cannot be created.
     ///
     pub fn create_real_f32(
         buffer_length: usize,
         other_length: usize,
         mode: CrossCorrelationMode,
     ) -> Result<Arc<dyn CrossCorrelate<f32> + Sync + Send>, CrossCorrelateError> {
-        let fft_size = mode.fft_size(buffer_length, other_length);
-        let forward = Zaft::make_r2c_fft_f32(fft_size)
-            .map_err(|x| CrossCorrelateError::FftError(x.to_string()))?;
-        let inverse = Zaft::make_c2r_fft_f32(fft_size)
-            .map_err(|x| CrossCorrelateError::FftError(x.to_string()))?;
-        #[cfg(all(target_arch = "x86_64", feature = "avx"))]
-        {
-            if std::arch::is_x86_feature_detected!("avx2")
-                && std::arch::is_x86_feature_detected!("fma")
-            {
-                use crate::avx::MulSpectrumSingleAvxFma;
-                return Ok(Arc::new(CrossCorrelateReal {
-                    fft_forward: forward,
-                    fft_inverse: inverse,
-                    multiplier: Arc::new(MulSpectrumSingleAvxFma::default()),
-                    mode,
-                }));
-            }
-        }
-        #[cfg(all(target_arch = "x86_64", feature = "sse"))]
-        {
-            if std::arch::is_x86_feature_detected!("sse4.2") {
-                use crate::sse::MulSpectrumSingleSse4_2;
-                return Ok(Arc::new(CrossCorrelateReal {
-                    fft_forward: forward,
-                    fft_inverse: inverse,
-                    multiplier: Arc::new(MulSpectrumSingleSse4_2::default()),
-                    mode,
-                }));
-            }
-        }
-        #[cfg(all(target_arch = "aarch64", feature = "fcma"))]
-        {
-            if std::arch::is_aarch64_feature_detected!("fcma") {
-                use crate::neon::SpectrumMulSingleFcma;
-                return Ok(Arc::new(CrossCorrelateReal {
-                    fft_forward: forward,
-                    fft_inverse: inverse,
-                    multiplier: Arc::new(SpectrumMulSingleFcma::default()),
-                    mode,
-                }));
-            }
-        }
-        #[cfg(all(target_arch = "aarch64", feature = "neon"))]
-        {
-            use crate::neon::SpectrumMulSingleNeon;
-            Ok(Arc::new(CrossCorrelateReal {
-                fft_forward: forward,
-                fft_inverse: inverse,
-                multiplier: Arc::new(SpectrumMulSingleNeon::default()),
-                mode,
-            }))
-        }
-        #[cfg(not(all(target_arch = "aarch64", feature = "neon")))]
-        {
-            use crate::spectrum::SpectrumMultiplierSingle;
-            Ok(Arc::new(CrossCorrelateReal {
-                fft_forward: forward,
-                fft_inverse: inverse,
-                multiplier: Arc::new(SpectrumMultiplierSingle::default()),
-                mode,
-            }))
-        }
+        Correlate::create::<f32>(buffer_length, other_length, mode)
     }
 
     /// Creates a cross-correlation engine for complex `f32` sequences.
     ///
     /// This function constructs a `CrossCorrelate` implementation that operates
-    /// on `Complex<f32>` data, using the provided FFT executors for forward and
+    /// on `Complex<f32>` data, using the default FFT backend for forward and
     /// inverse transforms. It supports different correlation modes (`Full`, `Same`, `Valid`)
     /// as specified by `mode`.
     ///
@@ -165,96 +758,28 @@ impl Correlate {
     ///
     /// * `mode` - The [`CrossCorrelationMode`] that determines the shape of the output
     ///   (e.g. `Full`, `Same`, `Valid`).
-    /// * `fft_forward` - A boxed [`FftExecutor`] used for the forward transform.
-    ///   Typically, created using `rustfft::FftPlanner::plan_fft_forward`.
-    /// * `fft_inverse` - A boxed [`FftExecutor`] used for the inverse transform.
-    ///   Typically, created using `rustfft::FftPlanner::plan_fft_inverse`.
     ///
     /// # Returns
     ///
     /// A boxed [`CrossCorrelate`] instance implementing cross-correlation on complex `f32` signals,
-    /// or an error if the FFT sizes do not match.
+    /// or an error if the FFT plans cannot be created.
     ///
     /// # Errors
     ///
-    /// Returns [`CrossCorrelateError`] if:
-    /// - The forward and inverse FFT executors have mismatched lengths.
-    /// - The FFT size computed from the input buffers does not match the provided executors.
+    /// Returns [`CrossCorrelateError`] if the FFT plan cannot be created.
     ///
     pub fn create_complex_f32(
         buffer_length: usize,
         other_length: usize,
         mode: CrossCorrelationMode,
     ) -> Result<Arc<dyn CrossCorrelate<Complex<f32>> + Sync + Send>, CrossCorrelateError> {
-        let fft_size = mode.fft_size(buffer_length, other_length);
-        let forward = Zaft::make_forward_fft_f32(fft_size)
-            .map_err(|x| CrossCorrelateError::FftError(x.to_string()))?;
-        let inverse = Zaft::make_inverse_fft_f32(fft_size)
-            .map_err(|x| CrossCorrelateError::FftError(x.to_string()))?;
-        #[cfg(all(target_arch = "x86_64", feature = "avx"))]
-        {
-            if std::arch::is_x86_feature_detected!("avx2")
-                && std::arch::is_x86_feature_detected!("fma")
-            {
-                use crate::avx::MulSpectrumSingleAvxFma;
-                return Ok(Arc::new(CrossCorrelateComplex {
-                    fft_forward: forward,
-                    fft_inverse: inverse,
-                    multiplier: Arc::new(MulSpectrumSingleAvxFma::default()),
-                    mode,
-                }));
-            }
-        }
-        #[cfg(all(target_arch = "x86_64", feature = "sse"))]
-        {
-            if std::arch::is_x86_feature_detected!("sse4.2") {
-                use crate::sse::MulSpectrumSingleSse4_2;
-                return Ok(Arc::new(CrossCorrelateComplex {
-                    fft_forward: forward,
-                    fft_inverse: inverse,
-                    multiplier: Arc::new(MulSpectrumSingleSse4_2::default()),
-                    mode,
-                }));
-            }
-        }
-        #[cfg(all(target_arch = "aarch64", feature = "fcma"))]
-        {
-            if std::arch::is_aarch64_feature_detected!("fcma") {
-                use crate::neon::SpectrumMulSingleFcma;
-                return Ok(Arc::new(CrossCorrelateComplex {
-                    fft_forward: forward,
-                    fft_inverse: inverse,
-                    multiplier: Arc::new(SpectrumMulSingleFcma::default()),
-                    mode,
-                }));
-            }
-        }
-        #[cfg(all(target_arch = "aarch64", feature = "neon"))]
-        {
-            use crate::neon::SpectrumMulSingleNeon;
-            Ok(Arc::new(CrossCorrelateComplex {
-                fft_forward: forward,
-                fft_inverse: inverse,
-                multiplier: Arc::new(SpectrumMulSingleNeon::default()),
-                mode,
-            }))
-        }
-        #[cfg(not(all(target_arch = "aarch64", feature = "neon")))]
-        {
-            use crate::spectrum::SpectrumMultiplierSingle;
-            Ok(Arc::new(CrossCorrelateComplex {
-                fft_forward: forward,
-                fft_inverse: inverse,
-                multiplier: Arc::new(SpectrumMultiplierSingle::default()),
-                mode,
-            }))
-        }
+        Correlate::create_complex::<f32>(buffer_length, other_length, mode)
     }
 
     /// Create a real-valued cross-correlator using FFT.
     ///
     /// This function constructs a cross-correlator for `f64` signals, based on the
-    /// provided correlation mode and FFT executors. It uses the forward FFT to
+    /// provided correlation mode. It uses the forward FFT to
     /// transform both input signals, multiplies one by the conjugate spectrum of the other,
     /// and then applies the inverse FFT to obtain the cross-correlation result.
     ///
@@ -262,96 +787,28 @@ impl Correlate {
     ///
     /// * `mode` - The [`CrossCorrelationMode`] that determines the shape of the output
     ///   (e.g. `Full`, `Same`, `Valid`).
-    /// * `fft_forward` - A boxed [`FftExecutor`] used for the forward transform.
-    ///   Typically, created using `rustfft::FftPlanner::plan_fft_forward`.
-    /// * `fft_inverse` - A boxed [`FftExecutor`] used for the inverse transform.
-    ///   Typically, created using `rustfft::FftPlanner::plan_fft_inverse`.
     ///
     /// # Returns
     ///
     /// A boxed [`CrossCorrelate`] instance implementing cross-correlation on `f64` signals,
-    /// or an error if the FFT sizes do not match.
+    /// or an error if the FFT plans cannot be created.
     ///
     /// # Errors
     ///
-    /// Returns [`CrossCorrelateError`] if:
-    /// - The forward and inverse FFT executors have mismatched lengths.
-    /// - The FFT size computed from the input buffers does not match the provided executors.
+    /// Returns [`CrossCorrelateError`] if the FFT plan cannot be created.
     ///
     pub fn create_real_f64(
         buffer_length: usize,
         other_length: usize,
         mode: CrossCorrelationMode,
     ) -> Result<Arc<dyn CrossCorrelate<f64> + Sync + Send>, CrossCorrelateError> {
-        let fft_size = mode.fft_size(buffer_length, other_length);
-        let forward = Zaft::make_r2c_fft_f64(fft_size)
-            .map_err(|x| CrossCorrelateError::FftError(x.to_string()))?;
-        let inverse = Zaft::make_c2r_fft_f64(fft_size)
-            .map_err(|x| CrossCorrelateError::FftError(x.to_string()))?;
-        #[cfg(all(target_arch = "x86_64", feature = "avx"))]
-        {
-            if std::arch::is_x86_feature_detected!("avx2")
-                && std::arch::is_x86_feature_detected!("fma")
-            {
-                use crate::avx::MulSpectrumDoubleAvxFma;
-                return Ok(Arc::new(CrossCorrelateReal {
-                    fft_forward: forward,
-                    fft_inverse: inverse,
-                    multiplier: Arc::new(MulSpectrumDoubleAvxFma::default()),
-                    mode,
-                }));
-            }
-        }
-        #[cfg(all(target_arch = "x86_64", feature = "sse"))]
-        {
-            if std::arch::is_x86_feature_detected!("sse4.2") {
-                use crate::sse::MulSpectrumDoubleSse4_2;
-                return Ok(Arc::new(CrossCorrelateReal {
-                    fft_forward: forward,
-                    fft_inverse: inverse,
-                    multiplier: Arc::new(MulSpectrumDoubleSse4_2::default()),
-                    mode,
-                }));
-            }
-        }
-        #[cfg(all(target_arch = "aarch64", feature = "fcma"))]
-        {
-            if std::arch::is_aarch64_feature_detected!("fcma") {
-                use crate::neon::SpectrumMulDoubleFcma;
-                return Ok(Arc::new(CrossCorrelateReal {
-                    fft_forward: forward,
-                    fft_inverse: inverse,
-                    multiplier: Arc::new(SpectrumMulDoubleFcma::default()),
-                    mode,
-                }));
-            }
-        }
-        #[cfg(all(target_arch = "aarch64", feature = "neon"))]
-        {
-            use crate::neon::SpectrumMulDoubleNeon;
-            Ok(Arc::new(CrossCorrelateReal {
-                fft_forward: forward,
-                fft_inverse: inverse,
-                multiplier: Arc::new(SpectrumMulDoubleNeon::default()),
-                mode,
-            }))
-        }
-        #[cfg(not(all(target_arch = "aarch64", feature = "neon")))]
-        {
-            use crate::spectrum::SpectrumMultiplierDouble;
-            Ok(Arc::new(CrossCorrelateReal {
-                fft_forward: forward,
-                fft_inverse: inverse,
-                multiplier: Arc::new(SpectrumMultiplierDouble::default()),
-                mode,
-            }))
-        }
+        Correlate::create::<f64>(buffer_length, other_length, mode)
     }
 
     /// Create a real-valued cross-correlator using FFT.
     ///
     /// This function constructs a cross-correlator for complex `f64` signals, based on the
-    /// provided correlation mode and FFT executors. It uses the forward FFT to
+    /// provided correlation mode. It uses the forward FFT to
     /// transform both input signals, multiplies one by the conjugate spectrum of the other,
     /// and then applies the inverse FFT to obtain the cross-correlation result.
     ///
@@ -363,81 +820,1631 @@ impl Correlate {
     /// # Returns
     ///
     /// A boxed [`CrossCorrelate`] instance implementing cross-correlation on complex `f64` signals,
-    /// or an error if the FFT sizes do not match.
+    /// or an error if the FFT plans cannot be created.
     ///
     /// # Errors
     ///
-    /// Returns [`CrossCorrelateError`] if:
-    /// - The forward and inverse FFT executors have mismatched lengths.
-    /// - The FFT size computed from the input buffers does not match the provided executors.
+    /// Returns [`CrossCorrelateError`] if the FFT plan cannot be created.
     ///
     pub fn create_complex_f64(
         buffer_length: usize,
         other_length: usize,
         mode: CrossCorrelationMode,
     ) -> Result<Arc<dyn CrossCorrelate<Complex<f64>> + Sync + Send>, CrossCorrelateError> {
-        let fft_size = mode.fft_size(buffer_length, other_length);
-        let forward = Zaft::make_forward_fft_f64(fft_size)
-            .map_err(|x| CrossCorrelateError::FftError(x.to_string()))?;
-        let inverse = Zaft::make_inverse_fft_f64(fft_size)
-            .map_err(|x| CrossCorrelateError::FftError(x.to_string()))?;
-        #[cfg(all(target_arch = "x86_64", feature = "avx"))]
-        {
-            if std::arch::is_x86_feature_detected!("avx2")
-                && std::arch::is_x86_feature_detected!("fma")
-            {
-                use crate::avx::MulSpectrumDoubleAvxFma;
-                return Ok(Arc::new(CrossCorrelateComplex {
-                    fft_forward: forward,
-                    fft_inverse: inverse,
-                    multiplier: Arc::new(MulSpectrumDoubleAvxFma::default()),
-                    mode,
-                }));
-            }
+        Correlate::create_complex::<f64>(buffer_length, other_length, mode)
+    }
+
+    /// Create a cross-correlator for any [`CorrelateSample`] against a fixed template
+    /// from [`CorrelateOptions`].
+    ///
+    /// The forward FFT of `template` is computed once here, so each call to
+    /// [`CrossCorrelateTemplate::correlate`] only transforms the incoming buffer.
+    /// Unweighted `Valid` correlations of buffers at least as long as the template run
+    /// overlap-save blocks of [`streaming_block_size`](crate::streaming_block_size)
+    /// whatever the FFT size policy, every other correlation transforms the whole buffer.
+    /// [`CorrelationMethod::Auto`] always selects the FFT.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_length` - Length of the buffers that will be correlated against the template.
+    /// * `template` - The fixed signal, playing the role of `other` in [`CrossCorrelate::correlate`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - The template is empty or the mode selects no lags for the lengths.
+    /// - The options ask for [`CorrelationMethod::Direct`].
+    /// - The coherence of [`GccWeighting::Ml`] does not have `fft_size / 2 + 1` bins.
+    /// - The FFT plan cannot be created.
+    ///
+    pub fn create_template_with_options<T: CorrelateSample>(
+        buffer_length: usize,
+        template: &[T],
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelateTemplate<T> + Sync + Send>, CrossCorrelateError> {
+        Ok(Arc::new(Correlate::make_real_template(
+            &ZaftPlanner,
+            buffer_length,
+            template,
+            options,
+        )?))
+    }
+
+    /// Create a complex-valued cross-correlator for any [`CorrelateSample`] against a
+    /// fixed template from [`CorrelateOptions`].
+    ///
+    /// The complex counterpart of [`Correlate::create_template_with_options`], which
+    /// always transforms the whole buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - The template is empty or the mode selects no lags for the lengths.
+    /// - The options ask for [`CorrelationMethod::Direct`].
+    /// - The coherence of [`GccWeighting::Ml`] does not have `fft_size` bins.
+    /// - The FFT plan cannot be created.
+    ///
+    pub fn create_complex_template_with_options<T: CorrelateSample>(
+        buffer_length: usize,
+        template: &[Complex<T>],
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelateTemplate<Complex<T>> + Sync + Send>, CrossCorrelateError>
+    {
+        Ok(Arc::new(Correlate::make_complex_template(
+            &ZaftPlanner,
+            buffer_length,
+            template,
+            options,
+        )?))
+    }
+
+    /// Create a cross-correlator against a fixed template taking its FFT plans from an
+    /// [`FftPlanCache`].
+    ///
+    /// Behaves like [`Correlate::create_template_with_options`], except that correlators
+    /// sharing `cache` reuse the plans of equal FFT size instead of planning them again.
+    ///
+    /// # Errors
+    ///
+    /// See [`Correlate::create_template_with_options`].
+    ///
+    pub fn create_template_with_cache<T: CorrelateSample>(
+        buffer_length: usize,
+        template: &[T],
+        cache: &FftPlanCache<T>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelateTemplate<T> + Sync + Send>, CrossCorrelateError> {
+        Ok(Arc::new(Correlate::make_real_template(
+            cache,
+            buffer_length,
+            template,
+            options,
+        )?))
+    }
+
+    /// Create a complex-valued cross-correlator against a fixed template taking its FFT
+    /// plans from an [`FftPlanCache`].
+    ///
+    /// Behaves like [`Correlate::create_complex_template_with_options`], except that
+    /// correlators sharing `cache` reuse the plans of equal FFT size.
+    ///
+    /// # Errors
+    ///
+    /// See [`Correlate::create_complex_template_with_options`].
+    ///
+    pub fn create_complex_template_with_cache<T: CorrelateSample>(
+        buffer_length: usize,
+        template: &[Complex<T>],
+        cache: &FftPlanCache<T>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelateTemplate<Complex<T>> + Sync + Send>, CrossCorrelateError>
+    {
+        Ok(Arc::new(Correlate::make_complex_template(
+            cache,
+            buffer_length,
+            template,
+            options,
+        )?))
+    }
+
+    /// Create a cross-correlator for `f32` signals against a fixed template.
+    ///
+    /// The forward FFT of `template` is computed once here, so each call to
+    /// [`CrossCorrelateTemplate::correlate`] only transforms the incoming buffer.
+    /// `Valid` correlations of buffers at least as long as the template run overlap-save
+    /// blocks of [`streaming_block_size`](crate::streaming_block_size), bit-identical to the
+    /// streaming correlator with that block size.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_length` - Length of the buffers that will be correlated against the template.
+    /// * `template` - The fixed signal, playing the role of `other` in [`CrossCorrelate::correlate`].
+    /// * `mode` - The [`CrossCorrelationMode`] that determines the shape of the output
+    ///   (e.g. `Full`, `Same`, `Valid`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if the template is empty or the FFT plan cannot be created.
+    ///
+    pub fn create_real_f32_with_template(
+        buffer_length: usize,
+        template: &[f32],
+        mode: CrossCorrelationMode,
+    ) -> Result<Arc<dyn CrossCorrelateTemplate<f32> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_template_with_options::<f32>(
+            buffer_length,
+            template,
+            &CorrelateOptions::new().mode(mode),
+        )
+    }
+
+    /// Create a cross-correlator for `f64` signals against a fixed template.
+    ///
+    /// The forward FFT of `template` is computed once here, so each call to
+    /// [`CrossCorrelateTemplate::correlate`] only transforms the incoming buffer.
+    /// `Valid` correlations of buffers at least as long as the template run overlap-save
+    /// blocks of [`streaming_block_size`](crate::streaming_block_size), bit-identical to the
+    /// streaming correlator with that block size.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_length` - Length of the buffers that will be correlated against the template.
+    /// * `template` - The fixed signal, playing the role of `other` in [`CrossCorrelate::correlate`].
+    /// * `mode` - The [`CrossCorrelationMode`] that determines the shape of the output
+    ///   (e.g. `Full`, `Same`, `Valid`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if the template is empty or the FFT plan cannot be created.
+    ///
+    pub fn create_real_f64_with_template(
+        buffer_length: usize,
+        template: &[f64],
+        mode: CrossCorrelationMode,
+    ) -> Result<Arc<dyn CrossCorrelateTemplate<f64> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_template_with_options::<f64>(
+            buffer_length,
+            template,
+            &CorrelateOptions::new().mode(mode),
+        )
+    }
+
+    /// Create a cross-correlator for complex `f32` signals against a fixed template.
+    ///
+    /// The forward FFT of `template` is computed once here, so each call to
+    /// [`CrossCorrelateTemplate::correlate`] only transforms the incoming buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_length` - Length of the buffers that will be correlated against the template.
+    /// * `template` - The fixed signal, playing the role of `other` in [`CrossCorrelate::correlate`].
+    /// * `mode` - The [`CrossCorrelationMode`] that determines the shape of the output
+    ///   (e.g. `Full`, `Same`, `Valid`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if the template is empty or the FFT plan cannot be created.
+    ///
+    pub fn create_complex_f32_with_template(
+        buffer_length: usize,
+        template: &[Complex<f32>],
+        mode: CrossCorrelationMode,
+    ) -> Result<Arc<dyn CrossCorrelateTemplate<Complex<f32>> + Sync + Send>, CrossCorrelateError>
+    {
+        Correlate::create_complex_template_with_options::<f32>(
+            buffer_length,
+            template,
+            &CorrelateOptions::new().mode(mode),
+        )
+    }
+
+    /// Create a cross-correlator for complex `f64` signals against a fixed template.
+    ///
+    /// The forward FFT of `template` is computed once here, so each call to
+    /// [`CrossCorrelateTemplate::correlate`] only transforms the incoming buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_length` - Length of the buffers that will be correlated against the template.
+    /// * `template` - The fixed signal, playing the role of `other` in [`CrossCorrelate::correlate`].
+    /// * `mode` - The [`CrossCorrelationMode`] that determines the shape of the output
+    ///   (e.g. `Full`, `Same`, `Valid`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if the template is empty or the FFT plan cannot be created.
+    ///
+    pub fn create_complex_f64_with_template(
+        buffer_length: usize,
+        template: &[Complex<f64>],
+        mode: CrossCorrelationMode,
+    ) -> Result<Arc<dyn CrossCorrelateTemplate<Complex<f64>> + Sync + Send>, CrossCorrelateError>
+    {
+        Correlate::create_complex_template_with_options::<f64>(
+            buffer_length,
+            template,
+            &CorrelateOptions::new().mode(mode),
+        )
+    }
+
+    /// Create a zero-normalized cross-correlator (ZNCC) for any [`CorrelateSample`] from
+    /// [`CorrelateOptions`].
+    ///
+    /// ZNCC always returns the `Valid` lags, so the mode set in `options` is not consulted,
+    /// and the output is already normalized.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_length` - Length of the buffers that will be searched, at least `template.len()`.
+    /// * `template` - The pattern to search for.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - The template is empty or longer than the buffer.
+    /// - The options ask for [`CorrelationMethod::Direct`], a normalization or a weighting.
+    /// - The FFT plan cannot be created.
+    ///
+    pub fn create_zncc_with_options<T: CorrelateSample + AsPrimitive<f64>>(
+        buffer_length: usize,
+        template: &[T],
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelateTemplate<T> + Sync + Send>, CrossCorrelateError>
+    where
+        f64: AsPrimitive<T>,
+    {
+        Ok(Arc::new(Correlate::make_zncc(
+            &ZaftPlanner,
+            buffer_length,
+            template,
+            options,
+        )?))
+    }
+
+    /// Create a zero-normalized cross-correlator (ZNCC) taking its FFT plans from an
+    /// [`FftPlanCache`].
+    ///
+    /// Behaves like [`Correlate::create_zncc_with_options`], except that correlators
+    /// sharing `cache` reuse the plans of equal FFT size.
+    ///
+    /// # Errors
+    ///
+    /// See [`Correlate::create_zncc_with_options`].
+    ///
+    pub fn create_zncc_with_cache<T: CorrelateSample + AsPrimitive<f64>>(
+        buffer_length: usize,
+        template: &[T],
+        cache: &FftPlanCache<T>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelateTemplate<T> + Sync + Send>, CrossCorrelateError>
+    where
+        f64: AsPrimitive<T>,
+    {
+        Ok(Arc::new(Correlate::make_zncc(
+            cache,
+            buffer_length,
+            template,
+            options,
+        )?))
+    }
+
+    /// Create a zero-normalized cross-correlator (ZNCC) for `f32` signals against a fixed template.
+    ///
+    /// At every `Valid` lag both the template and the window of the buffer it overlaps are
+    /// made zero-mean and scaled to unit energy, so the output is the Pearson correlation
+    /// coefficient of the two and lies in `[-1, 1]`. Windows or templates without any
+    /// variance produce `0`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_length` - Length of the buffers that will be searched, at least `template.len()`.
+    /// * `template` - The pattern to search for.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if the template is empty, longer than the buffer,
+    /// or the FFT plan cannot be created.
+    ///
+    pub fn create_zncc_f32(
+        buffer_length: usize,
+        template: &[f32],
+    ) -> Result<Arc<dyn CrossCorrelateTemplate<f32> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_zncc_with_options::<f32>(
+            buffer_length,
+            template,
+            &CorrelateOptions::new(),
+        )
+    }
+
+    /// Create a zero-normalized cross-correlator (ZNCC) for `f64` signals against a fixed template.
+    ///
+    /// At every `Valid` lag both the template and the window of the buffer it overlaps are
+    /// made zero-mean and scaled to unit energy, so the output is the Pearson correlation
+    /// coefficient of the two and lies in `[-1, 1]`. Windows or templates without any
+    /// variance produce `0`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_length` - Length of the buffers that will be searched, at least `template.len()`.
+    /// * `template` - The pattern to search for.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if the template is empty, longer than the buffer,
+    /// or the FFT plan cannot be created.
+    ///
+    pub fn create_zncc_f64(
+        buffer_length: usize,
+        template: &[f64],
+    ) -> Result<Arc<dyn CrossCorrelateTemplate<f64> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_zncc_with_options::<f64>(
+            buffer_length,
+            template,
+            &CorrelateOptions::new(),
+        )
+    }
+
+    /// Create a 2D cross-correlator for real images of any [`CorrelateSample`] from
+    /// [`CorrelateOptions`].
+    ///
+    /// The mode and FFT size policy apply to each axis independently. The correlation
+    /// always runs through the FFT and its output is not normalized.
+    ///
+    /// # Arguments
+    ///
+    /// * `width`, `height` - Dimensions of the `buffer` image.
+    /// * `kernel_width`, `kernel_height` - Dimensions of the `other` image.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - Any dimension is zero or the mode selects no lags along an axis.
+    /// - The options ask for [`CorrelationMethod::Direct`] or a normalization.
+    /// - The coherence of [`GccWeighting::Ml`] does not have `(fft_width / 2 + 1) * fft_height`
+    ///   bins, stored column by column.
+    /// - An FFT plan cannot be created.
+    ///
+    pub fn create_real_2d_with_options<T: CorrelateSample>(
+        width: usize,
+        height: usize,
+        kernel_width: usize,
+        kernel_height: usize,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelate2d<T> + Sync + Send>, CrossCorrelateError> {
+        Ok(Arc::new(Correlate::make_real_2d(
+            &ZaftPlanner,
+            (width, height),
+            (kernel_width, kernel_height),
+            options,
+        )?))
+    }
+
+    /// Create a 2D cross-correlator for real images taking its FFT plans from an
+    /// [`FftPlanCache`].
+    ///
+    /// Behaves like [`Correlate::create_real_2d_with_options`], except that correlators
+    /// sharing `cache` reuse the plans of equal FFT size.
+    ///
+    /// # Errors
+    ///
+    /// See [`Correlate::create_real_2d_with_options`].
+    ///
+    pub fn create_real_2d_with_cache<T: CorrelateSample>(
+        (width, height): (usize, usize),
+        (kernel_width, kernel_height): (usize, usize),
+        cache: &FftPlanCache<T>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelate2d<T> + Sync + Send>, CrossCorrelateError> {
+        Ok(Arc::new(Correlate::make_real_2d(
+            cache,
+            (width, height),
+            (kernel_width, kernel_height),
+            options,
+        )?))
+    }
+
+    /// Create a 2D cross-correlator for real `f32` images.
+    ///
+    /// Images are row-major. The correlation is computed with 1D real FFTs along the rows
+    /// followed by complex FFTs along the columns, and `mode` selects the output extent
+    /// along each axis independently.
+    ///
+    /// # Arguments
+    ///
+    /// * `width`, `height` - Dimensions of the `buffer` image.
+    /// * `kernel_width`, `kernel_height` - Dimensions of the `other` image.
+    /// * `mode` - The [`CrossCorrelationMode`] that determines the shape of the output
+    ///   (e.g. `Full`, `Same`, `Valid`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if any dimension is zero or an FFT plan cannot be created.
+    ///
+    pub fn create_real_2d_f32(
+        width: usize,
+        height: usize,
+        kernel_width: usize,
+        kernel_height: usize,
+        mode: CrossCorrelationMode,
+    ) -> Result<Arc<dyn CrossCorrelate2d<f32> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_real_2d_with_options::<f32>(
+            width,
+            height,
+            kernel_width,
+            kernel_height,
+            &CorrelateOptions::new().mode(mode),
+        )
+    }
+
+    /// Create a 2D cross-correlator for real `f64` images.
+    ///
+    /// Images are row-major. The correlation is computed with 1D real FFTs along the rows
+    /// followed by complex FFTs along the columns, and `mode` selects the output extent
+    /// along each axis independently.
+    ///
+    /// # Arguments
+    ///
+    /// * `width`, `height` - Dimensions of the `buffer` image.
+    /// * `kernel_width`, `kernel_height` - Dimensions of the `other` image.
+    /// * `mode` - The [`CrossCorrelationMode`] that determines the shape of the output
+    ///   (e.g. `Full`, `Same`, `Valid`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if any dimension is zero or an FFT plan cannot be created.
+    ///
+    pub fn create_real_2d_f64(
+        width: usize,
+        height: usize,
+        kernel_width: usize,
+        kernel_height: usize,
+        mode: CrossCorrelationMode,
+    ) -> Result<Arc<dyn CrossCorrelate2d<f64> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_real_2d_with_options::<f64>(
+            width,
+            height,
+            kernel_width,
+            kernel_height,
+            &CorrelateOptions::new().mode(mode),
+        )
+    }
+
+    /// Create a phase correlator for any [`CorrelateSample`] from [`CorrelateOptions`].
+    ///
+    /// See [`Correlate::create_phase_correlation_f32`]. The mode and the PHAT weighting
+    /// are chosen by the phase correlator, so those set in `options` are not consulted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - Either length is zero.
+    /// - The options ask for [`CorrelationMethod::Direct`] or a normalization.
+    /// - The FFT plan cannot be created.
+    ///
+    pub fn create_phase_correlation_with_options<T: CorrelateSample + PeakValue>(
+        buffer_length: usize,
+        other_length: usize,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn PhaseCorrelate<T> + Sync + Send>, CrossCorrelateError> {
+        Ok(Arc::new(Correlate::make_phase(
+            &ZaftPlanner,
+            buffer_length,
+            other_length,
+            options,
+        )?))
+    }
+
+    /// Create a phase correlator taking its FFT plans from an [`FftPlanCache`].
+    ///
+    /// Behaves like [`Correlate::create_phase_correlation_with_options`], except that
+    /// correlators sharing `cache` reuse the plans of equal FFT size.
+    ///
+    /// # Errors
+    ///
+    /// See [`Correlate::create_phase_correlation_with_options`].
+    ///
+    pub fn create_phase_correlation_with_cache<T: CorrelateSample + PeakValue>(
+        buffer_length: usize,
+        other_length: usize,
+        cache: &FftPlanCache<T>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn PhaseCorrelate<T> + Sync + Send>, CrossCorrelateError> {
+        Ok(Arc::new(Correlate::make_phase(
+            cache,
+            buffer_length,
+            other_length,
+            options,
+        )?))
+    }
+
+    /// Create a phase correlator for real images of any [`CorrelateSample`] from
+    /// [`CorrelateOptions`].
+    ///
+    /// See [`Correlate::create_phase_correlation_2d_f32`]. The mode and the PHAT weighting
+    /// are chosen by the phase correlator, so those set in `options` are not consulted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - Any dimension is zero.
+    /// - The options ask for [`CorrelationMethod::Direct`] or a normalization.
+    /// - An FFT plan cannot be created.
+    ///
+    pub fn create_phase_correlation_2d_with_options<T: CorrelateSample + PeakValue>(
+        width: usize,
+        height: usize,
+        kernel_width: usize,
+        kernel_height: usize,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn PhaseCorrelate2d<T> + Sync + Send>, CrossCorrelateError> {
+        Ok(Arc::new(Correlate::make_phase_2d(
+            &ZaftPlanner,
+            (width, height),
+            (kernel_width, kernel_height),
+            options,
+        )?))
+    }
+
+    /// Create a phase correlator for real images taking its FFT plans from an
+    /// [`FftPlanCache`].
+    ///
+    /// Behaves like [`Correlate::create_phase_correlation_2d_with_options`], except that
+    /// correlators sharing `cache` reuse the plans of equal FFT size.
+    ///
+    /// # Errors
+    ///
+    /// See [`Correlate::create_phase_correlation_2d_with_options`].
+    ///
+    pub fn create_phase_correlation_2d_with_cache<T: CorrelateSample + PeakValue>(
+        (width, height): (usize, usize),
+        (kernel_width, kernel_height): (usize, usize),
+        cache: &FftPlanCache<T>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn PhaseCorrelate2d<T> + Sync + Send>, CrossCorrelateError> {
+        Ok(Arc::new(Correlate::make_phase_2d(
+            cache,
+            (width, height),
+            (kernel_width, kernel_height),
+            options,
+        )?))
+    }
+
+    /// Create a phase correlator for `f32` signals.
+    ///
+    /// Phase correlation normalizes the cross-power spectrum to unit magnitude, which
+    /// reduces the correlation of two translated signals to a sharp peak at their shift
+    /// regardless of their gains or spectral content.
+    ///
+    /// Signals of equal length are correlated circularly, which is exact for circular
+    /// shifts and reports shifts in `-n/2..=n/2`. Signals of different lengths are
+    /// zero padded to [`CrossCorrelationMode::Full`] instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_length` - Length of the signal whose shift is estimated.
+    /// * `other_length` - Length of the reference signal.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if the FFT plan cannot be created.
+    ///
+    pub fn create_phase_correlation_f32(
+        buffer_length: usize,
+        other_length: usize,
+    ) -> Result<Arc<dyn PhaseCorrelate<f32> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_phase_correlation_with_options::<f32>(
+            buffer_length,
+            other_length,
+            &CorrelateOptions::new(),
+        )
+    }
+
+    /// Create a phase correlator for real `f32` images, used for image registration.
+    ///
+    /// See [`Correlate::create_phase_correlation_f32`] and [`Correlate::create_real_2d_f32`],
+    /// images of the same size are correlated circularly along both axes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if any dimension is zero or an FFT plan cannot be created.
+    ///
+    pub fn create_phase_correlation_2d_f32(
+        width: usize,
+        height: usize,
+        kernel_width: usize,
+        kernel_height: usize,
+    ) -> Result<Arc<dyn PhaseCorrelate2d<f32> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_phase_correlation_2d_with_options::<f32>(
+            width,
+            height,
+            kernel_width,
+            kernel_height,
+            &CorrelateOptions::new(),
+        )
+    }
+
+    /// Create a phase correlator for `f64` signals.
+    ///
+    /// Phase correlation normalizes the cross-power spectrum to unit magnitude, which
+    /// reduces the correlation of two translated signals to a sharp peak at their shift
+    /// regardless of their gains or spectral content.
+    ///
+    /// Signals of equal length are correlated circularly, which is exact for circular
+    /// shifts and reports shifts in `-n/2..=n/2`. Signals of different lengths are
+    /// zero padded to [`CrossCorrelationMode::Full`] instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer_length` - Length of the signal whose shift is estimated.
+    /// * `other_length` - Length of the reference signal.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if the FFT plan cannot be created.
+    ///
+    pub fn create_phase_correlation_f64(
+        buffer_length: usize,
+        other_length: usize,
+    ) -> Result<Arc<dyn PhaseCorrelate<f64> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_phase_correlation_with_options::<f64>(
+            buffer_length,
+            other_length,
+            &CorrelateOptions::new(),
+        )
+    }
+
+    /// Create a phase correlator for real `f64` images, used for image registration.
+    ///
+    /// See [`Correlate::create_phase_correlation_f64`] and [`Correlate::create_real_2d_f64`],
+    /// images of the same size are correlated circularly along both axes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if any dimension is zero or an FFT plan cannot be created.
+    ///
+    pub fn create_phase_correlation_2d_f64(
+        width: usize,
+        height: usize,
+        kernel_width: usize,
+        kernel_height: usize,
+    ) -> Result<Arc<dyn PhaseCorrelate2d<f64> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_phase_correlation_2d_with_options::<f64>(
+            width,
+            height,
+            kernel_width,
+            kernel_height,
+            &CorrelateOptions::new(),
+        )
+    }
+
+    /// Create a streaming correlator of `template` for any [`CorrelateSample`] from
+    /// [`CorrelateOptions`].
+    ///
+    /// The FFT size is `block_size`, so neither the mode nor the FFT size policy set in
+    /// `options` is consulted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - `template` is empty or longer than `block_size`.
+    /// - The options ask for [`CorrelationMethod::Direct`], a normalization or a weighting.
+    /// - The FFT plan cannot be created.
+    ///
+    pub fn create_streaming_with_options<T: CorrelateSample>(
+        template: &[T],
+        block_size: usize,
+        options: &CorrelateOptions,
+    ) -> Result<Box<dyn StreamingCorrelate<T> + Sync + Send>, CrossCorrelateError> {
+        Ok(Box::new(Correlate::make_streaming(
+            &ZaftPlanner,
+            template,
+            block_size,
+            options,
+        )?))
+    }
+
+    /// Create a streaming correlator of `template` taking its FFT plans from an
+    /// [`FftPlanCache`].
+    ///
+    /// Behaves like [`Correlate::create_streaming_with_options`], except that correlators
+    /// sharing `cache` reuse the plans of equal block size.
+    ///
+    /// # Errors
+    ///
+    /// See [`Correlate::create_streaming_with_options`].
+    ///
+    pub fn create_streaming_with_cache<T: CorrelateSample>(
+        template: &[T],
+        block_size: usize,
+        cache: &FftPlanCache<T>,
+        options: &CorrelateOptions,
+    ) -> Result<Box<dyn StreamingCorrelate<T> + Sync + Send>, CrossCorrelateError> {
+        Ok(Box::new(Correlate::make_streaming(
+            cache, template, block_size, options,
+        )?))
+    }
+
+    /// Create a streaming correlator of `template` for unbounded `f32` input.
+    ///
+    /// The stream is processed with overlap-save in blocks of `block_size` samples, each
+    /// of which yields `block_size - template.len() + 1` outputs. Larger blocks are more
+    /// efficient but hold outputs back for longer. [`streaming_block_size`](crate::streaming_block_size)
+    /// is a good start and emits the same bits as the `Valid` template correlator.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if `template` is empty or longer than `block_size`,
+    /// or the FFT plan cannot be created.
+    ///
+    pub fn create_streaming_f32(
+        template: &[f32],
+        block_size: usize,
+    ) -> Result<Box<dyn StreamingCorrelate<f32> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_streaming_with_options::<f32>(
+            template,
+            block_size,
+            &CorrelateOptions::new(),
+        )
+    }
+
+    /// Create a streaming correlator of `template` for unbounded `f64` input.
+    ///
+    /// The stream is processed with overlap-save in blocks of `block_size` samples, each
+    /// of which yields `block_size - template.len() + 1` outputs. Larger blocks are more
+    /// efficient but hold outputs back for longer. [`streaming_block_size`](crate::streaming_block_size)
+    /// is a good start and emits the same bits as the `Valid` template correlator.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if `template` is empty or longer than `block_size`,
+    /// or the FFT plan cannot be created.
+    ///
+    pub fn create_streaming_f64(
+        template: &[f64],
+        block_size: usize,
+    ) -> Result<Box<dyn StreamingCorrelate<f64> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_streaming_with_options::<f64>(
+            template,
+            block_size,
+            &CorrelateOptions::new(),
+        )
+    }
+
+    /// Create a correlator of every pair among real channels of any [`CorrelateSample`]
+    /// from [`CorrelateOptions`].
+    ///
+    /// Every channel is transformed once, each pair then only costs a multiply and an
+    /// inverse FFT.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel_length` - Length of every channel.
+    /// * `pairs` - The [`ChannelPairs`] to correlate.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - `channel_length` is zero or the mode selects no lags for it.
+    /// - The options ask for [`CorrelationMethod::Direct`].
+    /// - The coherence of [`GccWeighting::Ml`] does not have `fft_size / 2 + 1` bins.
+    /// - The FFT plan cannot be created.
+    ///
+    pub fn create_real_matrix_with_options<T: CorrelateSample + PeakValue>(
+        channel_length: usize,
+        pairs: ChannelPairs,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CorrelationMatrix<T> + Sync + Send>, CrossCorrelateError> {
+        Ok(Arc::new(Correlate::make_real_matrix(
+            &ZaftPlanner,
+            channel_length,
+            pairs,
+            options,
+        )?))
+    }
+
+    /// Create a correlator of every pair among complex channels of any [`CorrelateSample`]
+    /// from [`CorrelateOptions`].
+    ///
+    /// # Arguments
+    ///
+    /// * `channel_length` - Length of every channel.
+    /// * `pairs` - The [`ChannelPairs`] to correlate.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - `channel_length` is zero or the mode selects no lags for it.
+    /// - The options ask for [`CorrelationMethod::Direct`].
+    /// - The coherence of [`GccWeighting::Ml`] does not have `fft_size` bins.
+    /// - The FFT plan cannot be created.
+    ///
+    pub fn create_complex_matrix_with_options<T: CorrelateSample>(
+        channel_length: usize,
+        pairs: ChannelPairs,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CorrelationMatrix<Complex<T>> + Sync + Send>, CrossCorrelateError>
+    where
+        Complex<T>: PeakValue,
+    {
+        Ok(Arc::new(Correlate::make_complex_matrix(
+            &ZaftPlanner,
+            channel_length,
+            pairs,
+            options,
+        )?))
+    }
+
+    /// Create a correlator of every pair among real channels taking its FFT plans from an
+    /// [`FftPlanCache`].
+    ///
+    /// Behaves like [`Correlate::create_real_matrix_with_options`], except that correlators
+    /// sharing `cache` reuse the plans of equal FFT size.
+    ///
+    /// # Errors
+    ///
+    /// See [`Correlate::create_real_matrix_with_options`].
+    ///
+    pub fn create_real_matrix_with_cache<T: CorrelateSample + PeakValue>(
+        channel_length: usize,
+        pairs: ChannelPairs,
+        cache: &FftPlanCache<T>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CorrelationMatrix<T> + Sync + Send>, CrossCorrelateError> {
+        Ok(Arc::new(Correlate::make_real_matrix(
+            cache,
+            channel_length,
+            pairs,
+            options,
+        )?))
+    }
+
+    /// Create a correlator of every pair among complex channels taking its FFT plans from
+    /// an [`FftPlanCache`].
+    ///
+    /// Behaves like [`Correlate::create_complex_matrix_with_options`], except that
+    /// correlators sharing `cache` reuse the plans of equal FFT size.
+    ///
+    /// # Errors
+    ///
+    /// See [`Correlate::create_complex_matrix_with_options`].
+    ///
+    pub fn create_complex_matrix_with_cache<T: CorrelateSample>(
+        channel_length: usize,
+        pairs: ChannelPairs,
+        cache: &FftPlanCache<T>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CorrelationMatrix<Complex<T>> + Sync + Send>, CrossCorrelateError>
+    where
+        Complex<T>: PeakValue,
+    {
+        Ok(Arc::new(Correlate::make_complex_matrix(
+            cache,
+            channel_length,
+            pairs,
+            options,
+        )?))
+    }
+
+    /// Create a correlator of every pair among real `f32` channels of equal length.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel_length` - Length of every channel.
+    /// * `mode` - The [`CrossCorrelationMode`] that determines the shape of each pair output.
+    /// * `pairs` - The [`ChannelPairs`] to correlate.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if `channel_length` is zero or the FFT plan cannot be created.
+    ///
+    pub fn create_real_matrix_f32(
+        channel_length: usize,
+        mode: CrossCorrelationMode,
+        pairs: ChannelPairs,
+    ) -> Result<Arc<dyn CorrelationMatrix<f32> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_real_matrix_with_options::<f32>(
+            channel_length,
+            pairs,
+            &CorrelateOptions::new().mode(mode),
+        )
+    }
+
+    /// Create a correlator of every pair among real `f64` channels of equal length.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel_length` - Length of every channel.
+    /// * `mode` - The [`CrossCorrelationMode`] that determines the shape of each pair output.
+    /// * `pairs` - The [`ChannelPairs`] to correlate.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if `channel_length` is zero or the FFT plan cannot be created.
+    ///
+    pub fn create_real_matrix_f64(
+        channel_length: usize,
+        mode: CrossCorrelationMode,
+        pairs: ChannelPairs,
+    ) -> Result<Arc<dyn CorrelationMatrix<f64> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_real_matrix_with_options::<f64>(
+            channel_length,
+            pairs,
+            &CorrelateOptions::new().mode(mode),
+        )
+    }
+
+    /// Create a correlator of every pair among complex `f32` channels of equal length.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel_length` - Length of every channel.
+    /// * `mode` - The [`CrossCorrelationMode`] that determines the shape of each pair output.
+    /// * `pairs` - The [`ChannelPairs`] to correlate.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if `channel_length` is zero or the FFT plan cannot be created.
+    ///
+    pub fn create_complex_matrix_f32(
+        channel_length: usize,
+        mode: CrossCorrelationMode,
+        pairs: ChannelPairs,
+    ) -> Result<Arc<dyn CorrelationMatrix<Complex<f32>> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_complex_matrix_with_options::<f32>(
+            channel_length,
+            pairs,
+            &CorrelateOptions::new().mode(mode),
+        )
+    }
+
+    /// Create a correlator of every pair among complex `f64` channels of equal length.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel_length` - Length of every channel.
+    /// * `mode` - The [`CrossCorrelationMode`] that determines the shape of each pair output.
+    /// * `pairs` - The [`ChannelPairs`] to correlate.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if `channel_length` is zero or the FFT plan cannot be created.
+    ///
+    pub fn create_complex_matrix_f64(
+        channel_length: usize,
+        mode: CrossCorrelationMode,
+        pairs: ChannelPairs,
+    ) -> Result<Arc<dyn CorrelationMatrix<Complex<f64>> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_complex_matrix_with_options::<f64>(
+            channel_length,
+            pairs,
+            &CorrelateOptions::new().mode(mode),
+        )
+    }
+
+    /// Create an autocorrelator for any [`CorrelateSample`] from [`CorrelateOptions`].
+    ///
+    /// `lags` selects the output, so the mode set in `options` is not consulted.
+    /// [`CrossCorrelationNormalization::Biased`] gives the estimate used by Yule-Walker and LPC.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal_length` - Length of the signal.
+    /// * `lags` - The [`AutocorrelationLags`] to return.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if:
+    /// - `signal_length` is zero.
+    /// - The options ask for [`CorrelationMethod::Direct`] or a weighting.
+    /// - The FFT plan cannot be created.
+    ///
+    pub fn create_autocorrelation_with_options<T: CorrelateSample>(
+        signal_length: usize,
+        lags: AutocorrelationLags,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn Autocorrelate<T> + Sync + Send>, CrossCorrelateError>
+    where
+        f64: AsPrimitive<T>,
+    {
+        Ok(Arc::new(Correlate::make_autocorrelation(
+            &ZaftPlanner,
+            signal_length,
+            lags,
+            options,
+        )?))
+    }
+
+    /// Create an autocorrelator for complex signals of any [`CorrelateSample`] from
+    /// [`CorrelateOptions`].
+    ///
+    /// The complex counterpart of [`Correlate::create_autocorrelation_with_options`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Correlate::create_autocorrelation_with_options`].
+    ///
+    pub fn create_complex_autocorrelation_with_options<T: CorrelateSample>(
+        signal_length: usize,
+        lags: AutocorrelationLags,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn Autocorrelate<Complex<T>> + Sync + Send>, CrossCorrelateError>
+    where
+        f64: AsPrimitive<T>,
+    {
+        Ok(Arc::new(Correlate::make_complex_autocorrelation(
+            &ZaftPlanner,
+            signal_length,
+            lags,
+            options,
+        )?))
+    }
+
+    /// Create an autocorrelator taking its FFT plans from an [`FftPlanCache`].
+    ///
+    /// Behaves like [`Correlate::create_autocorrelation_with_options`], except that
+    /// autocorrelators sharing `cache` reuse the plans of equal FFT size.
+    ///
+    /// # Errors
+    ///
+    /// See [`Correlate::create_autocorrelation_with_options`].
+    ///
+    pub fn create_autocorrelation_with_cache<T: CorrelateSample>(
+        signal_length: usize,
+        lags: AutocorrelationLags,
+        cache: &FftPlanCache<T>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn Autocorrelate<T> + Sync + Send>, CrossCorrelateError>
+    where
+        f64: AsPrimitive<T>,
+    {
+        Ok(Arc::new(Correlate::make_autocorrelation(
+            cache,
+            signal_length,
+            lags,
+            options,
+        )?))
+    }
+
+    /// Create a complex autocorrelator taking its FFT plans from an [`FftPlanCache`].
+    ///
+    /// Behaves like [`Correlate::create_complex_autocorrelation_with_options`], except
+    /// that autocorrelators sharing `cache` reuse the plans of equal FFT size.
+    ///
+    /// # Errors
+    ///
+    /// See [`Correlate::create_autocorrelation_with_options`].
+    ///
+    pub fn create_complex_autocorrelation_with_cache<T: CorrelateSample>(
+        signal_length: usize,
+        lags: AutocorrelationLags,
+        cache: &FftPlanCache<T>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn Autocorrelate<Complex<T>> + Sync + Send>, CrossCorrelateError>
+    where
+        f64: AsPrimitive<T>,
+    {
+        Ok(Arc::new(Correlate::make_complex_autocorrelation(
+            cache,
+            signal_length,
+            lags,
+            options,
+        )?))
+    }
+
+    /// Create an autocorrelator for `f32` signals.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal_length` - Length of the signal.
+    /// * `lags` - The [`AutocorrelationLags`] to return.
+    /// * `normalization` - The [`CrossCorrelationNormalization`] applied to the output,
+    ///   [`CrossCorrelationNormalization::Biased`] gives the estimate used by Yule-Walker and LPC.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if `signal_length` is zero or the FFT plan cannot be created.
+    ///
+    pub fn create_autocorrelation_f32(
+        signal_length: usize,
+        lags: AutocorrelationLags,
+        normalization: CrossCorrelationNormalization,
+    ) -> Result<Arc<dyn Autocorrelate<f32> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_autocorrelation_with_options::<f32>(
+            signal_length,
+            lags,
+            &CorrelateOptions::new().normalization(normalization),
+        )
+    }
+
+    /// Create an autocorrelator for `f64` signals.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal_length` - Length of the signal.
+    /// * `lags` - The [`AutocorrelationLags`] to return.
+    /// * `normalization` - The [`CrossCorrelationNormalization`] applied to the output,
+    ///   [`CrossCorrelationNormalization::Biased`] gives the estimate used by Yule-Walker and LPC.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if `signal_length` is zero or the FFT plan cannot be created.
+    ///
+    pub fn create_autocorrelation_f64(
+        signal_length: usize,
+        lags: AutocorrelationLags,
+        normalization: CrossCorrelationNormalization,
+    ) -> Result<Arc<dyn Autocorrelate<f64> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_autocorrelation_with_options::<f64>(
+            signal_length,
+            lags,
+            &CorrelateOptions::new().normalization(normalization),
+        )
+    }
+
+    /// Create an autocorrelator for complex `f32` signals.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal_length` - Length of the signal.
+    /// * `lags` - The [`AutocorrelationLags`] to return.
+    /// * `normalization` - The [`CrossCorrelationNormalization`] applied to the output,
+    ///   [`CrossCorrelationNormalization::Biased`] gives the estimate used by Yule-Walker and LPC.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if `signal_length` is zero or the FFT plan cannot be created.
+    ///
+    pub fn create_complex_autocorrelation_f32(
+        signal_length: usize,
+        lags: AutocorrelationLags,
+        normalization: CrossCorrelationNormalization,
+    ) -> Result<Arc<dyn Autocorrelate<Complex<f32>> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_complex_autocorrelation_with_options::<f32>(
+            signal_length,
+            lags,
+            &CorrelateOptions::new().normalization(normalization),
+        )
+    }
+
+    /// Create an autocorrelator for complex `f64` signals.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal_length` - Length of the signal.
+    /// * `lags` - The [`AutocorrelationLags`] to return.
+    /// * `normalization` - The [`CrossCorrelationNormalization`] applied to the output,
+    ///   [`CrossCorrelationNormalization::Biased`] gives the estimate used by Yule-Walker and LPC.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if `signal_length` is zero or the FFT plan cannot be created.
+    ///
+    pub fn create_complex_autocorrelation_f64(
+        signal_length: usize,
+        lags: AutocorrelationLags,
+        normalization: CrossCorrelationNormalization,
+    ) -> Result<Arc<dyn Autocorrelate<Complex<f64>> + Sync + Send>, CrossCorrelateError> {
+        Correlate::create_complex_autocorrelation_with_options::<f64>(
+            signal_length,
+            lags,
+            &CorrelateOptions::new().normalization(normalization),
+        )
+    }
+
+    fn make_phase<T: CorrelateSample>(
+        planner: &dyn FftPlanner<T>,
+        buffer_length: usize,
+        other_length: usize,
+        options: &CorrelateOptions,
+    ) -> Result<PhaseCorrelation<T>, CrossCorrelateError> {
+        let mode = if buffer_length == other_length {
+            CrossCorrelationMode::Circular
+        } else {
+            CrossCorrelationMode::Full
+        };
+        let options = options.clone().mode(mode).weighting(GccWeighting::Phat);
+        options.check_lengths(buffer_length, other_length)?;
+        options.check_fft_method()?;
+        options.check_no_normalization()?;
+        let fft_size = options
+            .fft_size_policy
+            .fft_size(mode, buffer_length, other_length);
+        let correlator = Correlate::assemble_real(
+            planner.plan_real_to_complex(fft_size)?,
+            planner.plan_complex_to_real(fft_size)?,
+            &options,
+        )?;
+        Ok(PhaseCorrelation { correlator, mode })
+    }
+
+    fn make_phase_2d<T: CorrelateSample>(
+        planner: &dyn FftPlanner<T>,
+        (width, height): (usize, usize),
+        (kernel_width, kernel_height): (usize, usize),
+        options: &CorrelateOptions,
+    ) -> Result<PhaseCorrelation2d<T>, CrossCorrelateError> {
+        let mode = if width == kernel_width && height == kernel_height {
+            CrossCorrelationMode::Circular
+        } else {
+            CrossCorrelationMode::Full
+        };
+        let correlator = Correlate::make_real_2d(
+            planner,
+            (width, height),
+            (kernel_width, kernel_height),
+            &options.clone().mode(mode).weighting(GccWeighting::Phat),
+        )?;
+        Ok(PhaseCorrelation2d {
+            correlator: Arc::new(correlator),
+            kernel_width,
+            kernel_height,
+            mode,
+        })
+    }
+
+    fn make_real_2d<T: CorrelateSample>(
+        planner: &dyn FftPlanner<T>,
+        (width, height): (usize, usize),
+        (kernel_width, kernel_height): (usize, usize),
+        options: &CorrelateOptions,
+    ) -> Result<CrossCorrelateReal2d<T>, CrossCorrelateError> {
+        if width == 0 || height == 0 || kernel_width == 0 || kernel_height == 0 {
+            return Err(CrossCorrelateError::BuffersMustNotHaveZeroSize);
         }
-        #[cfg(all(target_arch = "x86_64", feature = "sse"))]
-        {
-            if std::arch::is_x86_feature_detected!("sse4.2") {
-                use crate::sse::MulSpectrumDoubleSse4_2;
-                return Ok(Arc::new(CrossCorrelateComplex {
-                    fft_forward: forward,
-                    fft_inverse: inverse,
-                    multiplier: Arc::new(MulSpectrumDoubleSse4_2::default()),
-                    mode,
-                }));
-            }
+        let mode = options.mode;
+        if mode.get_size(width, kernel_width) == 0 {
+            return Err(CrossCorrelateError::ModeSelectsNoLags(width, kernel_width));
         }
-        #[cfg(all(target_arch = "aarch64", feature = "fcma"))]
-        {
-            if std::arch::is_aarch64_feature_detected!("fcma") {
-                use crate::neon::SpectrumMulDoubleFcma;
-                return Ok(Arc::new(CrossCorrelateComplex {
-                    fft_forward: forward,
-                    fft_inverse: inverse,
-                    multiplier: Arc::new(SpectrumMulDoubleFcma::default()),
-                    mode,
-                }));
-            }
+        if mode.get_size(height, kernel_height) == 0 {
+            return Err(CrossCorrelateError::ModeSelectsNoLags(
+                height,
+                kernel_height,
+            ));
+        }
+        options.check_fft_method()?;
+        options.check_no_normalization()?;
+        let fft_width = options.fft_size_policy.fft_size(mode, width, kernel_width);
+        let fft_height = options
+            .fft_size_policy
+            .fft_size(mode, height, kernel_height);
+        Ok(CrossCorrelateReal2d {
+            row_forward: planner.plan_real_to_complex(fft_width)?,
+            row_inverse: planner.plan_complex_to_real(fft_width)?,
+            column_forward: planner.plan_forward(fft_height)?,
+            column_inverse: planner.plan_inverse(fft_height)?,
+            multiplier: T::make_spectrum_multiplier(),
+            mode,
+            width,
+            height,
+            kernel_width,
+            kernel_height,
+            weighting: options
+                .weighting
+                .resolve((fft_width / 2 + 1) * fft_height)?,
+            fft_size_policy: options.fft_size_policy,
+            tasks: parallel_tasks(),
+        })
+    }
+
+    fn make_real_template<T: CorrelateSample>(
+        planner: &dyn FftPlanner<T>,
+        buffer_length: usize,
+        template: &[T],
+        options: &CorrelateOptions,
+    ) -> Result<CrossCorrelateRealTemplate<T>, CrossCorrelateError> {
+        options.check_lengths(buffer_length, template.len())?;
+        options.check_fft_method()?;
+        let fft_size = template_fft_size(
+            options.mode,
+            options.fft_size_policy,
+            options.weighting != GccWeighting::None,
+            buffer_length,
+            template.len(),
+        );
+        CrossCorrelateRealTemplate::new(
+            planner.plan_real_to_complex(fft_size)?,
+            planner.plan_complex_to_real(fft_size)?,
+            T::make_spectrum_multiplier(),
+            options,
+            template,
+        )
+    }
+
+    fn make_complex_template<T: CorrelateSample>(
+        planner: &dyn FftPlanner<T>,
+        buffer_length: usize,
+        template: &[Complex<T>],
+        options: &CorrelateOptions,
+    ) -> Result<CrossCorrelateComplexTemplate<T>, CrossCorrelateError> {
+        options.check_lengths(buffer_length, template.len())?;
+        options.check_fft_method()?;
+        let fft_size =
+            options
+                .fft_size_policy
+                .fft_size(options.mode, buffer_length, template.len());
+        CrossCorrelateComplexTemplate::new(
+            planner.plan_forward(fft_size)?,
+            planner.plan_inverse(fft_size)?,
+            T::make_spectrum_multiplier(),
+            options,
+            template,
+        )
+    }
+
+    fn make_zncc<T: CorrelateSample + AsPrimitive<f64>>(
+        planner: &dyn FftPlanner<T>,
+        buffer_length: usize,
+        template: &[T],
+        options: &CorrelateOptions,
+    ) -> Result<CrossCorrelateZncc<T>, CrossCorrelateError>
+    where
+        f64: AsPrimitive<T>,
+    {
+        if buffer_length < template.len() {
+            return Err(CrossCorrelateError::TemplateIsLongerThanBuffer(
+                template.len(),
+                buffer_length,
+            ));
+        }
+        options.check_fft_method()?;
+        options.check_no_normalization()?;
+        options.check_no_weighting()?;
+        let fft_size = template_fft_size(
+            CrossCorrelationMode::Valid,
+            options.fft_size_policy,
+            false,
+            buffer_length,
+            template.len(),
+        );
+        CrossCorrelateZncc::new(
+            planner.plan_real_to_complex(fft_size)?,
+            planner.plan_complex_to_real(fft_size)?,
+            T::make_spectrum_multiplier(),
+            options.fft_size_policy,
+            template,
+        )
+    }
+
+    fn make_streaming<T: CorrelateSample>(
+        planner: &dyn FftPlanner<T>,
+        template: &[T],
+        block_size: usize,
+        options: &CorrelateOptions,
+    ) -> Result<CrossCorrelateStreaming<T>, CrossCorrelateError> {
+        options.check_fft_method()?;
+        options.check_no_normalization()?;
+        options.check_no_weighting()?;
+        CrossCorrelateStreaming::new(
+            planner.plan_real_to_complex(block_size)?,
+            planner.plan_complex_to_real(block_size)?,
+            T::make_spectrum_multiplier(),
+            template,
+        )
+    }
+
+    fn make_real_matrix<T: CorrelateSample>(
+        planner: &dyn FftPlanner<T>,
+        channel_length: usize,
+        pairs: ChannelPairs,
+        options: &CorrelateOptions,
+    ) -> Result<CorrelationMatrixReal<T>, CrossCorrelateError> {
+        options.check_lengths(channel_length, channel_length)?;
+        options.check_fft_method()?;
+        let fft_size =
+            options
+                .fft_size_policy
+                .fft_size(options.mode, channel_length, channel_length);
+        Ok(CorrelationMatrixReal {
+            fft_forward: planner.plan_real_to_complex(fft_size)?,
+            fft_inverse: planner.plan_complex_to_real(fft_size)?,
+            multiplier: T::make_spectrum_multiplier(),
+            mode: options.mode,
+            normalization: options.normalization,
+            weighting: options.weighting.resolve(fft_size / 2 + 1)?,
+            channel_length,
+            pairs,
+        })
+    }
+
+    fn make_complex_matrix<T: CorrelateSample>(
+        planner: &dyn FftPlanner<T>,
+        channel_length: usize,
+        pairs: ChannelPairs,
+        options: &CorrelateOptions,
+    ) -> Result<CorrelationMatrixComplex<T>, CrossCorrelateError> {
+        options.check_lengths(channel_length, channel_length)?;
+        options.check_fft_method()?;
+        let fft_size =
+            options
+                .fft_size_policy
+                .fft_size(options.mode, channel_length, channel_length);
+        Ok(CorrelationMatrixComplex {
+            fft_forward: planner.plan_forward(fft_size)?,
+            fft_inverse: planner.plan_inverse(fft_size)?,
+            multiplier: T::make_spectrum_multiplier(),
+            mode: options.mode,
+            normalization: options.normalization,
+            weighting: options.weighting.resolve(fft_size)?,
+            channel_length,
+            pairs,
+        })
+    }
+
+    /// FFT size of an autocorrelation, after validating the options it cannot honor.
+    fn autocorrelation_fft_size(
+        signal_length: usize,
+        options: &CorrelateOptions,
+    ) -> Result<usize, CrossCorrelateError> {
+        if signal_length == 0 {
+            return Err(CrossCorrelateError::BuffersMustNotHaveZeroSize);
         }
-        #[cfg(all(target_arch = "aarch64", feature = "neon"))]
-        {
-            use crate::neon::SpectrumMulDoubleNeon;
-            Ok(Arc::new(CrossCorrelateComplex {
-                fft_forward: forward,
-                fft_inverse: inverse,
-                multiplier: Arc::new(SpectrumMulDoubleNeon::default()),
-                mode,
-            }))
+        options.check_fft_method()?;
+        options.check_no_weighting()?;
+        Ok(options.fft_size_policy.fft_size(
+            CrossCorrelationMode::Full,
+            signal_length,
+            signal_length,
+        ))
+    }
+
+    fn make_autocorrelation<T: CorrelateSample>(
+        planner: &dyn FftPlanner<T>,
+        signal_length: usize,
+        lags: AutocorrelationLags,
+        options: &CorrelateOptions,
+    ) -> Result<AutocorrelateReal<T>, CrossCorrelateError> {
+        let fft_size = Correlate::autocorrelation_fft_size(signal_length, options)?;
+        Ok(AutocorrelateReal {
+            fft_forward: planner.plan_real_to_complex(fft_size)?,
+            fft_inverse: planner.plan_complex_to_real(fft_size)?,
+            lags,
+            normalization: options.normalization,
+            fft_size_policy: options.fft_size_policy,
+        })
+    }
+
+    fn make_complex_autocorrelation<T: CorrelateSample>(
+        planner: &dyn FftPlanner<T>,
+        signal_length: usize,
+        lags: AutocorrelationLags,
+        options: &CorrelateOptions,
+    ) -> Result<AutocorrelateComplex<T>, CrossCorrelateError> {
+        let fft_size = Correlate::autocorrelation_fft_size(signal_length, options)?;
+        Ok(AutocorrelateComplex {
+            fft_forward: planner.plan_forward(fft_size)?,
+            fft_inverse: planner.plan_inverse(fft_size)?,
+            lags,
+            normalization: options.normalization,
+            fft_size_policy: options.fft_size_policy,
+        })
+    }
+
+    fn make_real<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelate<T> + Sync + Send>, CrossCorrelateError> {
+        let fft_size = options
+            .fft_size_policy
+            .fft_size(options.mode, buffer_length, other_length);
+        Correlate::assemble_real(
+            zaft_real_to_complex::<T>(fft_size)?,
+            zaft_complex_to_real::<T>(fft_size)?,
+            options,
+        )
+    }
+
+    fn assemble_real<T: CorrelateSample>(
+        fft_forward: Arc<dyn RealToComplexFft<T> + Send + Sync>,
+        fft_inverse: Arc<dyn ComplexToRealFft<T> + Send + Sync>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelate<T> + Sync + Send>, CrossCorrelateError> {
+        let weighting = options
+            .weighting
+            .resolve(fft_forward.real_length() / 2 + 1)?;
+        Ok(Arc::new(CrossCorrelateReal {
+            fft_forward,
+            fft_inverse,
+            multiplier: T::make_spectrum_multiplier(),
+            mode: options.mode,
+            normalization: options.normalization,
+            weighting,
+            product: SpectrumProduct::Correlation,
+            fft_size_policy: options.fft_size_policy,
+        }))
+    }
+
+    fn make_complex<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelate<Complex<T>> + Sync + Send>, CrossCorrelateError> {
+        let fft_size = options
+            .fft_size_policy
+            .fft_size(options.mode, buffer_length, other_length);
+        Correlate::assemble_complex(
+            zaft_forward::<T>(fft_size)?,
+            zaft_inverse::<T>(fft_size)?,
+            options,
+        )
+    }
+
+    fn make_mixed<T: CorrelateSample>(
+        planner: &dyn FftPlanner<T>,
+        buffer_length: usize,
+        other_length: usize,
+        options: &CorrelateOptions,
+    ) -> Result<CrossCorrelateRealComplex<T>, CrossCorrelateError> {
+        options.check_lengths(buffer_length, other_length)?;
+        options.check_fft_method()?;
+        let fft_size = options
+            .fft_size_policy
+            .fft_size(options.mode, buffer_length, other_length);
+        Correlate::assemble_mixed(
+            buffer_length,
+            other_length,
+            (
+                planner.plan_real_to_complex(fft_size)?,
+                planner.plan_forward(fft_size)?,
+                planner.plan_inverse(fft_size)?,
+            ),
+            options,
+        )
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn assemble_mixed<T: CorrelateSample>(
+        buffer_length: usize,
+        other_length: usize,
+        (fft_real, fft_forward, fft_inverse): (
+            Arc<dyn RealToComplexFft<T> + Send + Sync>,
+            Arc<dyn ComplexFft<T> + Send + Sync>,
+            Arc<dyn ComplexFft<T> + Send + Sync>,
+        ),
+        options: &CorrelateOptions,
+    ) -> Result<CrossCorrelateRealComplex<T>, CrossCorrelateError> {
+        options.check_lengths(buffer_length, other_length)?;
+        let fft_length = fft_forward.length();
+        for length in [fft_real.real_length(), fft_inverse.length()] {
+            if length != fft_length {
+                return Err(CrossCorrelateError::FftSizesDoNotMatch(fft_length, length));
+            }
         }
-        #[cfg(not(all(target_arch = "aarch64", feature = "neon")))]
-        {
-            use crate::spectrum::SpectrumMultiplierDouble;
-            Ok(Arc::new(CrossCorrelateComplex {
-                fft_forward: forward,
-                fft_inverse: inverse,
-                multiplier: Arc::new(SpectrumMultiplierDouble::default()),
-                mode,
-            }))
+        let fft_size = options
+            .fft_size_policy
+            .fft_size(options.mode, buffer_length, other_length);
+        if fft_size != fft_length {
+            return Err(CrossCorrelateError::FftAndBuffersSizeDoNotMatch(
+                fft_length, fft_size,
+            ));
         }
+        Ok(CrossCorrelateRealComplex {
+            fft_real,
+            fft_forward,
+            fft_inverse,
+            multiplier: T::make_spectrum_multiplier(),
+            mode: options.mode,
+            normalization: options.normalization,
+            weighting: options.weighting.resolve(fft_size)?,
+            fft_size_policy: options.fft_size_policy,
+        })
+    }
+
+    fn assemble_complex<T: CorrelateSample>(
+        fft_forward: Arc<dyn ComplexFft<T> + Send + Sync>,
+        fft_inverse: Arc<dyn ComplexFft<T> + Send + Sync>,
+        options: &CorrelateOptions,
+    ) -> Result<Arc<dyn CrossCorrelate<Complex<T>> + Sync + Send>, CrossCorrelateError> {
+        let weighting = options.weighting.resolve(fft_forward.length())?;
+        Ok(Arc::new(CrossCorrelateComplex {
+            fft_forward,
+            fft_inverse,
+            multiplier: T::make_spectrum_multiplier(),
+            mode: options.mode,
+            normalization: options.normalization,
+            weighting,
+            product: SpectrumProduct::Correlation,
+            fft_size_policy: options.fft_size_policy,
+        }))
     }
 }
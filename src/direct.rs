@@ -0,0 +1,162 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::accumulate::ScaledAccumulate;
+use crate::error::try_vec;
+use crate::normalization::{NormalizeSample, signal_energy};
+use crate::{
+    CrossCorrelate, CrossCorrelateError, CrossCorrelationMode, CrossCorrelationNormalization,
+};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Output lags processed per pass over the kernel, sized to keep the block in L1.
+const BLOCK_SIZE: usize = 2048;
+
+/// Time-domain cross-correlation.
+///
+/// The result is accumulated tap by tap: for every kernel sample the matching
+/// shifted slice of `buffer` is scaled and added to the output, which keeps the
+/// inner loop vectorized over output lags even for very short kernels.
+pub(crate) struct CrossCorrelateDirect<V> {
+    pub(crate) accumulator: Arc<dyn ScaledAccumulate<V> + Send + Sync>,
+    pub(crate) mode: CrossCorrelationMode,
+    pub(crate) normalization: CrossCorrelationNormalization,
+}
+
+impl<V: Copy + Clone + Debug + Default + NormalizeSample> CrossCorrelate<V>
+    for CrossCorrelateDirect<V>
+{
+    fn correlate(
+        &self,
+        output: &mut [V],
+        buffer: &[V],
+        other: &[V],
+    ) -> Result<(), CrossCorrelateError> {
+        self.correlate_with_scratch(output, buffer, other, &mut [])
+    }
+
+    fn correlate_managed(&self, buffer: &[V], other: &[V]) -> Result<Vec<V>, CrossCorrelateError> {
+        let data_length = self.mode.get_size(buffer.len(), other.len());
+        let mut output = try_vec![V::default(); data_length];
+        self.correlate(&mut output, buffer, other).map(|_| output)
+    }
+
+    fn scratch_length(&self) -> usize {
+        0
+    }
+
+    fn correlate_with_scratch(
+        &self,
+        output: &mut [V],
+        buffer: &[V],
+        other: &[V],
+        _: &mut [V],
+    ) -> Result<(), CrossCorrelateError> {
+        if buffer.is_empty() || other.is_empty() || output.is_empty() {
+            return Err(CrossCorrelateError::BuffersMustNotHaveZeroSize);
+        }
+        let data_length = self.mode.get_size(buffer.len(), other.len());
+        if output.len() != data_length {
+            return Err(CrossCorrelateError::OutputSizeDoNotMatch(
+                data_length,
+                output.len(),
+            ));
+        }
+
+        for dst in output.iter_mut() {
+            *dst = V::default();
+        }
+
+        if self.mode == CrossCorrelationMode::Circular {
+            self.correlate_circular(output, buffer, other);
+        } else {
+            self.correlate_linear(output, buffer, other);
+        }
+
+        self.normalization
+            .lag_scale(self.mode, buffer.len(), other.len(), || {
+                signal_energy(buffer) * signal_energy(other)
+            })
+            .apply(output);
+
+        Ok(())
+    }
+}
+
+impl<V: Copy + NormalizeSample> CrossCorrelateDirect<V> {
+    fn correlate_linear(&self, output: &mut [V], buffer: &[V], other: &[V]) {
+        let start = self.mode.start_index(buffer.len(), other.len()) as isize;
+        let lag_origin = other.len() as isize - 1;
+        let buffer_len = buffer.len() as isize;
+
+        for (block_index, block) in output.chunks_mut(BLOCK_SIZE).enumerate() {
+            let block_start = (block_index * BLOCK_SIZE) as isize;
+            let block_len = block.len() as isize;
+            for (j, &tap) in other.iter().enumerate() {
+                // block[i] pairs with buffer[i + shift] for this tap
+                let shift = block_start + start + j as isize - lag_origin;
+                let first = (-shift).clamp(0, block_len);
+                let last = (buffer_len - shift).clamp(first, block_len);
+                if first == last {
+                    continue;
+                }
+                let src = &buffer[(first + shift) as usize..(last + shift) as usize];
+                self.accumulator
+                    .accumulate(&mut block[first as usize..last as usize], src, tap);
+            }
+        }
+    }
+
+    /// Wraps `buffer` around the period instead of clipping it, samples past
+    /// its end stand for the zero padding of a shorter buffer.
+    fn correlate_circular(&self, output: &mut [V], buffer: &[V], other: &[V]) {
+        let period = output.len();
+        for (block_index, block) in output.chunks_mut(BLOCK_SIZE).enumerate() {
+            let block_start = block_index * BLOCK_SIZE;
+            for (j, &tap) in other.iter().enumerate() {
+                // block[i] pairs with buffer[(block_start + i + j) % period]
+                let mut offset = 0;
+                let mut source = (block_start + j) % period;
+                while offset < block.len() {
+                    let count = (block.len() - offset).min(period - source);
+                    let available = count.min(buffer.len().saturating_sub(source));
+                    if available > 0 {
+                        self.accumulator.accumulate(
+                            &mut block[offset..offset + available],
+                            &buffer[source..source + available],
+                            tap,
+                        );
+                    }
+                    offset += count;
+                    source = 0;
+                }
+            }
+        }
+    }
+}
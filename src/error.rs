@@ -37,6 +37,15 @@ pub enum CrossCorrelateError {
     FftAndBuffersSizeDoNotMatch(usize, usize),
     OutputSizeDoNotMatch(usize, usize),
     BuffersMustNotHaveZeroSize,
+    ScratchSizeIsTooSmall(usize, usize),
+    TemplateIsLongerThanBuffer(usize, usize),
+    WeightingSizeDoNotMatch(usize, usize),
+    StrideIsTooSmall(usize, usize),
+    ImageSizeDoNotMatch(usize, usize),
+    ChannelLengthDoNotMatch(usize, usize),
+    ModeSelectsNoLags(usize, usize),
+    WeightingRequiresFft,
+    UnsupportedOption(&'static str),
 }
 
 impl Error for CrossCorrelateError {}
@@ -60,6 +69,33 @@ impl Display for CrossCorrelateError {
             CrossCorrelateError::BuffersMustNotHaveZeroSize => {
                 f.write_str("Buffers must have zero size")
             }
+            CrossCorrelateError::ScratchSizeIsTooSmall(s0, s1) => f.write_fmt(format_args!(
+                "Scratch size should be at least {s0} but it was {s1}"
+            )),
+            CrossCorrelateError::TemplateIsLongerThanBuffer(s0, s1) => f.write_fmt(format_args!(
+                "Template length {s0} exceeds buffer length {s1}"
+            )),
+            CrossCorrelateError::WeightingSizeDoNotMatch(s0, s1) => f.write_fmt(format_args!(
+                "Weighting expects {s0} spectrum bins but {s1} were given"
+            )),
+            CrossCorrelateError::StrideIsTooSmall(s0, s1) => {
+                f.write_fmt(format_args!("Stride {s1} is smaller than row width {s0}"))
+            }
+            CrossCorrelateError::ImageSizeDoNotMatch(s0, s1) => f.write_fmt(format_args!(
+                "Strided buffer requires at least {s0} elements but it was {s1}"
+            )),
+            CrossCorrelateError::ChannelLengthDoNotMatch(s0, s1) => f.write_fmt(format_args!(
+                "Channel length expected to be {s0} but it was {s1}"
+            )),
+            CrossCorrelateError::ModeSelectsNoLags(s0, s1) => f.write_fmt(format_args!(
+                "Correlation mode selects no lags for inputs of {s0} and {s1} samples"
+            )),
+            CrossCorrelateError::WeightingRequiresFft => {
+                f.write_str("Spectrum weighting is only available with the FFT method")
+            }
+            CrossCorrelateError::UnsupportedOption(option) => {
+                f.write_fmt(format_args!("{option} is not supported by this correlator"))
+            }
         }
     }
 }
@@ -0,0 +1,76 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::CrossCorrelationMode;
+use crate::fast_divider::DividerUsize;
+use crate::normalization::{LagScale, NormalizeSample};
+
+/// Copies the linear convolution out of a circular one, where it starts at index 0.
+pub(crate) fn extract_convolution<V: Copy>(
+    output: &mut [V],
+    circular: &[V],
+    mode: CrossCorrelationMode,
+    buffer_len: usize,
+    other_len: usize,
+) {
+    let start = match mode {
+        CrossCorrelationMode::Circular => 0,
+        _ => mode.start_index(buffer_len, other_len),
+    };
+    output.copy_from_slice(&circular[start..start + output.len()]);
+}
+
+/// Copies the lags selected by `mode` out of a circular correlation result
+/// and applies the requested normalization to them.
+///
+/// `circular` holds the inverse transform of the cross-spectrum, where lag `k`
+/// lives at index `k mod fft_size`.
+pub(crate) fn extract_correlation<V: NormalizeSample>(
+    output: &mut [V],
+    circular: &[V],
+    mode: CrossCorrelationMode,
+    buffer_len: usize,
+    other_len: usize,
+    scale: &LagScale,
+) {
+    let fft_size = circular.len();
+    let lag = other_len - 1;
+    let offset = fft_size - lag;
+    let start = mode.start_index(buffer_len, other_len);
+    if fft_size == 1 {
+        for dst in output.iter_mut() {
+            *dst = unsafe { *circular.get_unchecked(0) }
+        }
+    } else {
+        let divisor = DividerUsize::new(fft_size);
+        for (i, dst) in output.iter_mut().enumerate() {
+            *dst = unsafe { *circular.get_unchecked((start + i + offset) % divisor) };
+        }
+    }
+    scale.apply(output);
+}
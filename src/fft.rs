@@ -0,0 +1,268 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::{CorrelateSample, CrossCorrelateError};
+use num_complex::Complex;
+use std::sync::Arc;
+
+/// Forward real-to-complex FFT of a fixed length.
+///
+/// Implementations transform [`RealToComplexFft::real_length`] real samples into
+/// the `real_length / 2 + 1` non-redundant bins of their spectrum, without scaling.
+pub trait RealToComplexFft<T>: Send + Sync {
+    /// Transforms `input` into `output`.
+    fn execute(&self, input: &[T], output: &mut [Complex<T>]) -> Result<(), CrossCorrelateError>;
+    /// Returns the number of complex values [`RealToComplexFft::execute_with_scratch`]
+    /// needs as work space, none unless the backend overrides it.
+    fn scratch_length(&self) -> usize {
+        0
+    }
+    /// Transforms `input` into `output`, working in `scratch` instead of allocating.
+    ///
+    /// `scratch` must hold at least [`RealToComplexFft::scratch_length`] values.
+    /// Forwards to [`RealToComplexFft::execute`] unless the backend overrides it.
+    fn execute_with_scratch(
+        &self,
+        input: &[T],
+        output: &mut [Complex<T>],
+        scratch: &mut [Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        let _ = scratch;
+        self.execute(input, output)
+    }
+    /// Returns the number of real samples transformed.
+    fn real_length(&self) -> usize;
+}
+
+/// Inverse complex-to-real FFT of a fixed length.
+///
+/// Implementations turn the `real_length / 2 + 1` bins of a Hermitian spectrum
+/// back into [`ComplexToRealFft::real_length`] real samples, without scaling.
+pub trait ComplexToRealFft<T>: Send + Sync {
+    /// Transforms `input` into `output`.
+    ///
+    /// `input` may be used as scratch, its contents on exit are unspecified.
+    fn execute(
+        &self,
+        input: &mut [Complex<T>],
+        output: &mut [T],
+    ) -> Result<(), CrossCorrelateError>;
+    /// Returns the number of complex values [`ComplexToRealFft::execute_with_scratch`]
+    /// needs as work space, none unless the backend overrides it.
+    fn scratch_length(&self) -> usize {
+        0
+    }
+    /// Transforms `input` into `output`, working in `scratch` instead of allocating.
+    ///
+    /// `scratch` must hold at least [`ComplexToRealFft::scratch_length`] values.
+    /// Forwards to [`ComplexToRealFft::execute`] unless the backend overrides it.
+    fn execute_with_scratch(
+        &self,
+        input: &mut [Complex<T>],
+        output: &mut [T],
+        scratch: &mut [Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        let _ = scratch;
+        self.execute(input, output)
+    }
+    /// Returns the number of real samples produced.
+    fn real_length(&self) -> usize;
+}
+
+/// In-place complex FFT of a fixed length and direction, without scaling.
+pub trait ComplexFft<T>: Send + Sync {
+    /// Transforms `buffer` in place.
+    fn execute(&self, buffer: &mut [Complex<T>]) -> Result<(), CrossCorrelateError>;
+    /// Returns the number of values [`ComplexFft::execute_with_scratch`] needs as work
+    /// space, none unless the backend overrides it.
+    fn scratch_length(&self) -> usize {
+        0
+    }
+    /// Transforms `buffer` in place, working in `scratch` instead of allocating.
+    ///
+    /// `scratch` must hold at least [`ComplexFft::scratch_length`] values.
+    /// Forwards to [`ComplexFft::execute`] unless the backend overrides it.
+    fn execute_with_scratch(
+        &self,
+        buffer: &mut [Complex<T>],
+        scratch: &mut [Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        let _ = scratch;
+        self.execute(buffer)
+    }
+    /// Returns the number of samples transformed.
+    fn length(&self) -> usize;
+}
+
+/// Source of the FFT plans correlators run on.
+///
+/// Every factory plans its transforms through one of these. The default is zaft,
+/// [`FftPlanCache`](crate::FftPlanCache) shares plans between correlators and can
+/// front any other planner.
+pub trait FftPlanner<T>: Send + Sync {
+    /// Plans a real-to-complex transform of `size` samples.
+    fn plan_real_to_complex(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn RealToComplexFft<T> + Send + Sync>, CrossCorrelateError>;
+    /// Plans a complex-to-real transform of `size` samples.
+    fn plan_complex_to_real(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn ComplexToRealFft<T> + Send + Sync>, CrossCorrelateError>;
+    /// Plans a forward complex transform of `size` samples.
+    fn plan_forward(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn ComplexFft<T> + Send + Sync>, CrossCorrelateError>;
+    /// Plans an inverse complex transform of `size` samples.
+    fn plan_inverse(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn ComplexFft<T> + Send + Sync>, CrossCorrelateError>;
+}
+
+/// Plans every transform with zaft, without caching.
+pub(crate) struct ZaftPlanner;
+
+impl<T: CorrelateSample> FftPlanner<T> for ZaftPlanner {
+    fn plan_real_to_complex(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn RealToComplexFft<T> + Send + Sync>, CrossCorrelateError> {
+        zaft_real_to_complex(size)
+    }
+
+    fn plan_complex_to_real(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn ComplexToRealFft<T> + Send + Sync>, CrossCorrelateError> {
+        zaft_complex_to_real(size)
+    }
+
+    fn plan_forward(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn ComplexFft<T> + Send + Sync>, CrossCorrelateError> {
+        zaft_forward(size)
+    }
+
+    fn plan_inverse(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn ComplexFft<T> + Send + Sync>, CrossCorrelateError> {
+        zaft_inverse(size)
+    }
+}
+
+/// Adapts a zaft real-to-complex plan, the default backend.
+pub(crate) struct ZaftRealToComplex<T> {
+    pub(crate) executor: Arc<dyn zaft::R2CFftExecutor<T> + Send + Sync>,
+}
+
+impl<T> RealToComplexFft<T> for ZaftRealToComplex<T> {
+    fn execute(&self, input: &[T], output: &mut [Complex<T>]) -> Result<(), CrossCorrelateError> {
+        self.executor
+            .execute(input, output)
+            .map_err(|x| CrossCorrelateError::FftError(x.to_string()))
+    }
+
+    fn real_length(&self) -> usize {
+        self.executor.real_length()
+    }
+}
+
+/// Adapts a zaft complex-to-real plan, the default backend.
+pub(crate) struct ZaftComplexToReal<T> {
+    pub(crate) executor: Arc<dyn zaft::C2RFftExecutor<T> + Send + Sync>,
+}
+
+impl<T> ComplexToRealFft<T> for ZaftComplexToReal<T> {
+    fn execute(
+        &self,
+        input: &mut [Complex<T>],
+        output: &mut [T],
+    ) -> Result<(), CrossCorrelateError> {
+        self.executor
+            .execute(input, output)
+            .map_err(|x| CrossCorrelateError::FftError(x.to_string()))
+    }
+
+    fn real_length(&self) -> usize {
+        self.executor.real_length()
+    }
+}
+
+/// Adapts a zaft complex plan, the default backend.
+pub(crate) struct ZaftComplex<T> {
+    pub(crate) executor: Arc<dyn zaft::FftExecutor<T> + Send + Sync>,
+}
+
+impl<T> ComplexFft<T> for ZaftComplex<T> {
+    fn execute(&self, buffer: &mut [Complex<T>]) -> Result<(), CrossCorrelateError> {
+        self.executor
+            .execute(buffer)
+            .map_err(|x| CrossCorrelateError::FftError(x.to_string()))
+    }
+
+    fn length(&self) -> usize {
+        self.executor.length()
+    }
+}
+
+pub(crate) fn zaft_real_to_complex<T: CorrelateSample>(
+    size: usize,
+) -> Result<Arc<dyn RealToComplexFft<T> + Send + Sync>, CrossCorrelateError> {
+    Ok(Arc::new(ZaftRealToComplex {
+        executor: T::make_r2c_fft(size)?,
+    }))
+}
+
+pub(crate) fn zaft_complex_to_real<T: CorrelateSample>(
+    size: usize,
+) -> Result<Arc<dyn ComplexToRealFft<T> + Send + Sync>, CrossCorrelateError> {
+    Ok(Arc::new(ZaftComplexToReal {
+        executor: T::make_c2r_fft(size)?,
+    }))
+}
+
+pub(crate) fn zaft_forward<T: CorrelateSample>(
+    size: usize,
+) -> Result<Arc<dyn ComplexFft<T> + Send + Sync>, CrossCorrelateError> {
+    Ok(Arc::new(ZaftComplex {
+        executor: T::make_forward_fft(size)?,
+    }))
+}
+
+pub(crate) fn zaft_inverse<T: CorrelateSample>(
+    size: usize,
+) -> Result<Arc<dyn ComplexFft<T> + Send + Sync>, CrossCorrelateError> {
+    Ok(Arc::new(ZaftComplex {
+        executor: T::make_inverse_fft(size)?,
+    }))
+}
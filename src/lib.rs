@@ -39,31 +39,73 @@
     feature(stdarch_neon_fcma)
 )]
 
-use std::fmt::Debug;
-
-pub(crate) trait CorrelateSample: Copy + 'static + Clone + Default + Debug {}
-
-impl CorrelateSample for f32 {}
-impl CorrelateSample for f64 {}
-
+mod accumulate;
+mod autocorrelation;
 #[cfg(all(target_arch = "x86_64", feature = "avx"))]
 mod avx;
+mod batch;
+mod complex_template;
+mod convolve;
 mod correlate_complex;
 mod cross_correlate;
+mod direct;
 mod error;
+mod extract;
 mod fast_divider;
+mod fft;
+mod method;
+mod mixed;
 mod mode;
 #[cfg(all(target_arch = "aarch64", feature = "neon"))]
 mod neon;
+mod normalization;
+mod options;
 mod pad;
+mod pairwise;
+mod parallel;
+mod peak;
+mod phase;
+mod plan_cache;
 mod real;
+mod real_2d;
+mod real_template;
+#[cfg(feature = "rustfft")]
+mod rustfft_backend;
+mod sample;
+mod scratch;
 mod spectrum;
 #[cfg(all(target_arch = "x86_64", feature = "sse"))]
 mod sse;
+mod streaming;
+mod weighting;
+mod zncc;
 
-pub use cross_correlate::{Correlate, CrossCorrelate};
+pub use autocorrelation::{Autocorrelate, AutocorrelationLags};
+pub use convolve::{Convolution, Convolve};
+pub use cross_correlate::{
+    Correlate, CrossCorrelate, CrossCorrelate2d, CrossCorrelateMixed, CrossCorrelateTemplate,
+};
 pub use error::CrossCorrelateError;
-pub use mode::CrossCorrelationMode;
+pub use fft::{ComplexFft, ComplexToRealFft, FftPlanner, RealToComplexFft};
+pub use method::CorrelationMethod;
+pub use mode::{CrossCorrelationMode, fft_next_good_size, lags};
+pub use normalization::CrossCorrelationNormalization;
+pub use options::{CorrelateOptions, FftSizePolicy};
+pub use pairwise::{ChannelPairs, CorrelationMatrix, PairCorrelation, PairPeak};
+pub use peak::{
+    CorrelationPeak, PeakInterpolation, PeakValue, SubSamplePeak, find_peak, find_peaks,
+    refine_peak,
+};
+pub use phase::{PhaseCorrelate, PhaseCorrelate2d, PhaseShift, PhaseShift2d};
+pub use plan_cache::FftPlanCache;
+#[cfg(feature = "rustfft")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rustfft")))]
+pub use rustfft_backend::{
+    RustFftComplex, RustFftComplexToReal, RustFftPlanner, RustFftRealToComplex,
+};
+pub use sample::CorrelateSample;
+pub use streaming::{StreamingCorrelate, streaming_block_size};
+pub use weighting::GccWeighting;
 
 #[cfg(test)]
 mod tests {
@@ -135,6 +177,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::excessive_precision)]
     fn test_f32() {
         static VALID: [f32; 5] = [
             9.13584942,
@@ -197,4 +240,2685 @@ mod tests {
             .zip(VALID.iter())
             .for_each(|(a, b)| assert!((a - b).abs() < 1e-3));
     }
+
+    #[test]
+    fn test_with_scratch() {
+        let src = vec![
+            5.12, 6.2136, 7.2387, 1.52312, 2.52313, 3.52313, 4.52313, 5.23871,
+        ];
+        let dst = vec![0.31421, 0.421, 0.653, 0.121];
+
+        let correlation =
+            Correlate::create_real_f64(src.len(), dst.len(), CrossCorrelationMode::Full).unwrap();
+        let reference = correlation.correlate_managed(&src, &dst).unwrap();
+
+        let mut scratch = vec![0.; correlation.scratch_length()];
+        let mut output = vec![0.; reference.len()];
+        for _ in 0..2 {
+            correlation
+                .correlate_with_scratch(&mut output, &src, &dst, &mut scratch)
+                .unwrap();
+            assert_eq!(output, reference);
+        }
+
+        let mut small_scratch = vec![0.; correlation.scratch_length() - 1];
+        assert!(matches!(
+            correlation.correlate_with_scratch(&mut output, &src, &dst, &mut small_scratch),
+            Err(CrossCorrelateError::ScratchSizeIsTooSmall(_, _))
+        ));
+
+        let src_complex = src
+            .iter()
+            .map(|&x| num_complex::Complex::new(x, -x))
+            .collect::<Vec<_>>();
+        let dst_complex = dst
+            .iter()
+            .map(|&x| num_complex::Complex::new(x, x))
+            .collect::<Vec<_>>();
+        let correlation =
+            Correlate::create_complex_f64(src.len(), dst.len(), CrossCorrelationMode::Same)
+                .unwrap();
+        let reference = correlation
+            .correlate_managed(&src_complex, &dst_complex)
+            .unwrap();
+        let mut scratch = vec![num_complex::Complex::default(); correlation.scratch_length()];
+        let mut output = vec![num_complex::Complex::default(); reference.len()];
+        correlation
+            .correlate_with_scratch(&mut output, &src_complex, &dst_complex, &mut scratch)
+            .unwrap();
+        assert_eq!(output, reference);
+    }
+
+    struct DelegatingCorrelate(std::sync::Arc<dyn CrossCorrelate<f64> + Send + Sync>);
+
+    impl CrossCorrelate<f64> for DelegatingCorrelate {
+        fn correlate(
+            &self,
+            output: &mut [f64],
+            buffer: &[f64],
+            other: &[f64],
+        ) -> Result<(), CrossCorrelateError> {
+            self.0.correlate(output, buffer, other)
+        }
+
+        fn correlate_managed(
+            &self,
+            buffer: &[f64],
+            other: &[f64],
+        ) -> Result<Vec<f64>, CrossCorrelateError> {
+            self.0.correlate_managed(buffer, other)
+        }
+    }
+
+    #[test]
+    fn test_default_scratch() {
+        let src = vec![5.12, 6.2136, 7.2387, 1.52312, 2.52313];
+        let dst = vec![0.31421, 0.421, 0.653];
+        let correlation = DelegatingCorrelate(
+            Correlate::create_real_f64(src.len(), dst.len(), CrossCorrelationMode::Full).unwrap(),
+        );
+        assert_eq!(correlation.scratch_length(), 0);
+        let reference = correlation.correlate_managed(&src, &dst).unwrap();
+        let mut output = vec![0.; reference.len()];
+        correlation
+            .correlate_with_scratch(&mut output, &src, &dst, &mut [])
+            .unwrap();
+        assert_eq!(output, reference);
+    }
+
+    #[test]
+    fn test_template() {
+        let src = vec![
+            5.12, 6.2136, 7.2387, 1.52312, 2.52313, 3.52313, 4.52313, 5.23871,
+        ];
+        let dst = vec![0.31421, 0.421, 0.653, 0.121];
+
+        for mode in [
+            CrossCorrelationMode::Full,
+            CrossCorrelationMode::Same,
+            CrossCorrelationMode::Valid,
+        ] {
+            let correlation = Correlate::create_real_f64(src.len(), dst.len(), mode).unwrap();
+            let reference = correlation.correlate_managed(&src, &dst).unwrap();
+            let template = Correlate::create_real_f64_with_template(src.len(), &dst, mode).unwrap();
+            let result = template.correlate_managed(&src).unwrap();
+            assert_eq!(result.len(), reference.len());
+            result
+                .iter()
+                .zip(reference.iter())
+                .for_each(|(a, b)| assert!((a - b).abs() < 1e-9));
+
+            let src_complex = src
+                .iter()
+                .map(|&x| num_complex::Complex::new(x, 1. - x))
+                .collect::<Vec<_>>();
+            let dst_complex = dst
+                .iter()
+                .map(|&x| num_complex::Complex::new(-x, x))
+                .collect::<Vec<_>>();
+            let correlation = Correlate::create_complex_f64(src.len(), dst.len(), mode).unwrap();
+            let reference = correlation
+                .correlate_managed(&src_complex, &dst_complex)
+                .unwrap();
+            let template =
+                Correlate::create_complex_f64_with_template(src.len(), &dst_complex, mode).unwrap();
+            let result = template.correlate_managed(&src_complex).unwrap();
+            assert_eq!(result.len(), reference.len());
+            result
+                .iter()
+                .zip(reference.iter())
+                .for_each(|(a, b)| assert!((a - b).norm() < 1e-9));
+        }
+
+        let cache = FftPlanCache::<f64>::new();
+        for options in [
+            CorrelateOptions::new()
+                .mode(CrossCorrelationMode::Valid)
+                .normalization(CrossCorrelationNormalization::Coeff),
+            CorrelateOptions::new()
+                .mode(CrossCorrelationMode::Same)
+                .weighting(GccWeighting::Phat),
+            CorrelateOptions::new()
+                .normalization(CrossCorrelationNormalization::Unbiased)
+                .weighting(GccWeighting::Roth)
+                .fft_size_policy(FftSizePolicy::Exact),
+        ] {
+            let reference = Correlate::create_with_options::<f64>(src.len(), dst.len(), &options)
+                .unwrap()
+                .correlate_managed(&src, &dst)
+                .unwrap();
+            let template =
+                Correlate::create_template_with_options::<f64>(src.len(), &dst, &options).unwrap();
+            let cached =
+                Correlate::create_template_with_cache::<f64>(src.len(), &dst, &cache, &options)
+                    .unwrap();
+            for result in [
+                template.correlate_managed(&src).unwrap(),
+                cached.correlate_managed(&src).unwrap(),
+            ] {
+                assert_eq!(result.len(), reference.len());
+                result
+                    .iter()
+                    .zip(reference.iter())
+                    .for_each(|(a, b)| assert!((a - b).abs() < 1e-9, "{a} != {b}"));
+            }
+        }
+        // Exact sizes 11 rather than rounding up to 12
+        assert!(cache.real_to_complex(11).is_ok());
+
+        let src_complex = src
+            .iter()
+            .map(|&x| num_complex::Complex::new(x, 0.5 * x))
+            .collect::<Vec<_>>();
+        let dst_complex = dst
+            .iter()
+            .map(|&x| num_complex::Complex::new(x, -x))
+            .collect::<Vec<_>>();
+        let options = CorrelateOptions::new()
+            .normalization(CrossCorrelationNormalization::Coeff)
+            .weighting(GccWeighting::Phat);
+        let reference =
+            Correlate::create_complex_with_options::<f64>(src.len(), dst.len(), &options)
+                .unwrap()
+                .correlate_managed(&src_complex, &dst_complex)
+                .unwrap();
+        let result = Correlate::create_complex_template_with_options::<f64>(
+            src.len(),
+            &dst_complex,
+            &options,
+        )
+        .unwrap()
+        .correlate_managed(&src_complex)
+        .unwrap();
+        result
+            .iter()
+            .zip(reference.iter())
+            .for_each(|(a, b)| assert!((a - b).norm() < 1e-9));
+
+        assert!(matches!(
+            Correlate::create_template_with_options::<f64>(
+                src.len(),
+                &dst,
+                &CorrelateOptions::new().method(CorrelationMethod::Direct),
+            ),
+            Err(CrossCorrelateError::UnsupportedOption(_))
+        ));
+        assert!(matches!(
+            Correlate::create_template_with_options::<f64>(
+                src.len(),
+                &dst,
+                &CorrelateOptions::new().weighting(GccWeighting::Ml(vec![0.5; 3])),
+            ),
+            Err(CrossCorrelateError::WeightingSizeDoNotMatch(_, 3))
+        ));
+    }
+
+    #[test]
+    fn test_padded_fft_size() {
+        let src = (0..1000)
+            .map(|x| ((x as f64) * 0.37).sin())
+            .collect::<Vec<_>>();
+        let dst = (0..1018)
+            .map(|x| ((x as f64) * 0.11).cos())
+            .collect::<Vec<_>>();
+
+        for mode in [
+            CrossCorrelationMode::Full,
+            CrossCorrelationMode::Same,
+            CrossCorrelationMode::Valid,
+        ] {
+            assert_ne!(
+                mode.fft_size(src.len(), dst.len()),
+                src.len() + dst.len() - 1
+            );
+            let correlation = Correlate::create_real_f64(src.len(), dst.len(), mode).unwrap();
+            let result = correlation.correlate_managed(&src, &dst).unwrap();
+            assert_eq!(result.len(), mode.get_size(src.len(), dst.len()));
+
+            let start = match mode {
+                CrossCorrelationMode::Full => 0,
+                CrossCorrelationMode::Same => (src.len() - 1) / 2,
+                CrossCorrelationMode::Valid => src.len() - 1,
+                CrossCorrelationMode::MaxLag { .. }
+                | CrossCorrelationMode::Range { .. }
+                | CrossCorrelationMode::Circular => {
+                    unreachable!()
+                }
+            };
+            for (i, &value) in result.iter().enumerate() {
+                let lag = (start + i) as isize - (dst.len() as isize - 1);
+                let mut expected = 0.;
+                for (j, &y) in dst.iter().enumerate() {
+                    let k = j as isize + lag;
+                    if k >= 0 && (k as usize) < src.len() {
+                        expected += src[k as usize] * y;
+                    }
+                }
+                assert!((value - expected).abs() < 1e-9, "{mode:?} {i}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_shorter_buffer() {
+        // Valid and Same anchor on the shorter input even when it is `buffer`,
+        // before they read `other_len - 1` into the wrapped full correlation
+        let buffer = [1f64, 2., 3.];
+        let other = [1f64, 0., 0., 0., 0., 1.];
+        for (mode, expected) in [
+            (CrossCorrelationMode::Valid, vec![3., 0., 0., 1.]),
+            (CrossCorrelationMode::Same, vec![2., 3., 0., 0., 1., 2.]),
+        ] {
+            for method in [CorrelationMethod::Fft, CorrelationMethod::Direct] {
+                let result = Correlate::create_with_options::<f64>(
+                    3,
+                    6,
+                    &CorrelateOptions::new().mode(mode).method(method),
+                )
+                .unwrap()
+                .correlate_managed(&buffer, &other)
+                .unwrap();
+                assert_eq!(result.len(), expected.len());
+                for (a, b) in result.iter().zip(expected.iter()) {
+                    assert!((a - b).abs() < 1e-12, "{mode:?} {method:?} {result:?}");
+                }
+            }
+            let to_complex = |x: &[f64]| {
+                x.iter()
+                    .map(|&v| num_complex::Complex::new(v, 0.))
+                    .collect::<Vec<_>>()
+            };
+            let result = Correlate::create_complex_f64(3, 6, mode)
+                .unwrap()
+                .correlate_managed(&to_complex(&buffer), &to_complex(&other))
+                .unwrap();
+            for (a, b) in result.iter().zip(expected.iter()) {
+                assert!((a.re - b).abs() < 1e-12 && a.im.abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_direct() {
+        let src = (0..2500)
+            .map(|x| ((x as f64) * 0.37).sin())
+            .collect::<Vec<_>>();
+        let dst = (0..19)
+            .map(|x| ((x as f64) * 0.11).cos())
+            .collect::<Vec<_>>();
+        let src_complex = src
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| num_complex::Complex::new(x, (i as f64 * 0.2).cos()))
+            .collect::<Vec<_>>();
+        let dst_complex = dst
+            .iter()
+            .map(|&x| num_complex::Complex::new(x, 0.5 - x))
+            .collect::<Vec<_>>();
+
+        for mode in [
+            CrossCorrelationMode::Full,
+            CrossCorrelationMode::Same,
+            CrossCorrelationMode::Valid,
+        ] {
+            for (a, b) in [(&src, &dst), (&dst, &src)] {
+                let fft = Correlate::create_real_f64(a.len(), b.len(), mode).unwrap();
+                let reference = fft.correlate_managed(a, b).unwrap();
+                let direct = Correlate::create_with_options::<f64>(
+                    a.len(),
+                    b.len(),
+                    &CorrelateOptions::new()
+                        .mode(mode)
+                        .method(CorrelationMethod::Direct),
+                )
+                .unwrap();
+                let result = direct.correlate_managed(a, b).unwrap();
+                assert_eq!(result.len(), reference.len());
+                result
+                    .iter()
+                    .zip(reference.iter())
+                    .enumerate()
+                    .for_each(|(i, (a, b))| assert!((a - b).abs() < 1e-9, "{mode:?} {i} {a} {b}"));
+
+                let a32 = a.iter().map(|&x| x as f32).collect::<Vec<_>>();
+                let b32 = b.iter().map(|&x| x as f32).collect::<Vec<_>>();
+                let direct = Correlate::create_with_options::<f32>(
+                    a.len(),
+                    b.len(),
+                    &CorrelateOptions::new()
+                        .mode(mode)
+                        .method(CorrelationMethod::Direct),
+                )
+                .unwrap();
+                let result = direct.correlate_managed(&a32, &b32).unwrap();
+                result
+                    .iter()
+                    .zip(reference.iter())
+                    .for_each(|(&a, &b)| assert!((a as f64 - b).abs() < 1e-4));
+            }
+
+            for (a, b) in [(&src_complex, &dst_complex), (&dst_complex, &src_complex)] {
+                let fft = Correlate::create_complex_f64(a.len(), b.len(), mode).unwrap();
+                let reference = fft.correlate_managed(a, b).unwrap();
+                let direct = Correlate::create_complex_with_options::<f64>(
+                    a.len(),
+                    b.len(),
+                    &CorrelateOptions::new()
+                        .mode(mode)
+                        .method(CorrelationMethod::Direct),
+                )
+                .unwrap();
+                let result = direct.correlate_managed(a, b).unwrap();
+                assert_eq!(result.len(), reference.len());
+                result
+                    .iter()
+                    .zip(reference.iter())
+                    .for_each(|(a, b)| assert!((a - b).norm() < 1e-9));
+
+                let a32 = a
+                    .iter()
+                    .map(|x| num_complex::Complex::new(x.re as f32, x.im as f32))
+                    .collect::<Vec<_>>();
+                let b32 = b
+                    .iter()
+                    .map(|x| num_complex::Complex::new(x.re as f32, x.im as f32))
+                    .collect::<Vec<_>>();
+                let direct = Correlate::create_complex_with_options::<f32>(
+                    a.len(),
+                    b.len(),
+                    &CorrelateOptions::new()
+                        .mode(mode)
+                        .method(CorrelationMethod::Direct),
+                )
+                .unwrap();
+                let result = direct.correlate_managed(&a32, &b32).unwrap();
+                result.iter().zip(reference.iter()).for_each(|(a, b)| {
+                    assert!((a.re as f64 - b.re).abs() < 1e-4);
+                    assert!((a.im as f64 - b.im).abs() < 1e-4);
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn test_normalization() {
+        let src = (0..300)
+            .map(|x| ((x as f64) * 0.37).sin() + 0.2)
+            .collect::<Vec<_>>();
+        let dst = (0..41)
+            .map(|x| ((x as f64) * 0.11).cos())
+            .collect::<Vec<_>>();
+        let energy =
+            src.iter().map(|x| x * x).sum::<f64>() * dst.iter().map(|x| x * x).sum::<f64>();
+
+        for mode in [
+            CrossCorrelationMode::Full,
+            CrossCorrelationMode::Same,
+            CrossCorrelationMode::Valid,
+        ] {
+            for (a, b) in [(&src, &dst), (&dst, &src)] {
+                let raw = Correlate::create_real_f64(a.len(), b.len(), mode)
+                    .unwrap()
+                    .correlate_managed(a, b)
+                    .unwrap();
+                let start = match mode {
+                    CrossCorrelationMode::Full => 0,
+                    CrossCorrelationMode::Same => (a.len().min(b.len()) - 1) / 2,
+                    CrossCorrelationMode::Valid => a.len().min(b.len()) - 1,
+                    CrossCorrelationMode::MaxLag { .. }
+                    | CrossCorrelationMode::Range { .. }
+                    | CrossCorrelationMode::Circular => {
+                        unreachable!()
+                    }
+                };
+                for normalization in [
+                    CrossCorrelationNormalization::None,
+                    CrossCorrelationNormalization::Biased,
+                    CrossCorrelationNormalization::Unbiased,
+                    CrossCorrelationNormalization::Coeff,
+                ] {
+                    let result = Correlate::create_with_options::<f64>(
+                        a.len(),
+                        b.len(),
+                        &CorrelateOptions::new()
+                            .mode(mode)
+                            .normalization(normalization),
+                    )
+                    .unwrap()
+                    .correlate_managed(a, b)
+                    .unwrap();
+                    for (i, (&value, &raw)) in result.iter().zip(raw.iter()).enumerate() {
+                        let lag = (start + i) as isize - (b.len() as isize - 1);
+                        let overlap = (0..b.len() as isize)
+                            .filter(|&j| j + lag >= 0 && j + lag < a.len() as isize)
+                            .count();
+                        let expected = match normalization {
+                            CrossCorrelationNormalization::None => raw,
+                            CrossCorrelationNormalization::Biased => raw / src.len() as f64,
+                            CrossCorrelationNormalization::Unbiased => raw / overlap as f64,
+                            CrossCorrelationNormalization::Coeff => raw / energy.sqrt(),
+                        };
+                        assert!((value - expected).abs() < 1e-9, "{normalization:?}");
+                    }
+                }
+            }
+        }
+
+        let auto = Correlate::create_complex_with_options::<f32>(
+            20,
+            20,
+            &CorrelateOptions::new()
+                .mode(CrossCorrelationMode::Full)
+                .normalization(CrossCorrelationNormalization::Coeff),
+        )
+        .unwrap();
+        let signal = (0..20)
+            .map(|x| num_complex::Complex::new((x as f32 * 0.7).sin(), (x as f32 * 0.3).cos()))
+            .collect::<Vec<_>>();
+        let result = auto.correlate_managed(&signal, &signal).unwrap();
+        assert!(result.iter().all(|x| x.norm() <= 1.));
+        assert!((result[19].re - 1.).abs() < 1e-6);
+        assert!(result[19].im.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_zncc() {
+        let template = (0..37)
+            .map(|x| ((x as f64) * 0.41).sin() * 3.)
+            .collect::<Vec<_>>();
+        let mut src = (0..500)
+            .map(|x| ((x as f64) * 0.07).cos() + 1000.)
+            .collect::<Vec<_>>();
+        src[200..237]
+            .iter_mut()
+            .zip(template.iter())
+            .for_each(|(dst, &t)| *dst = 2. * t + 1000.5);
+        src[400..450].iter_mut().for_each(|x| *x = 1000.);
+
+        let zncc = Correlate::create_zncc_f64(src.len(), &template).unwrap();
+        let result = zncc.correlate_managed(&src).unwrap();
+        assert_eq!(result.len(), src.len() - template.len() + 1);
+
+        let template_mean = template.iter().sum::<f64>() / template.len() as f64;
+        for (k, &value) in result.iter().enumerate() {
+            let window = &src[k..k + template.len()];
+            let window_mean = window.iter().sum::<f64>() / window.len() as f64;
+            let mut numerator = 0.;
+            let mut window_energy = 0.;
+            let mut template_energy = 0.;
+            for (&x, &t) in window.iter().zip(template.iter()) {
+                numerator += (x - window_mean) * (t - template_mean);
+                window_energy += (x - window_mean) * (x - window_mean);
+                template_energy += (t - template_mean) * (t - template_mean);
+            }
+            let expected = if window_energy > 0. {
+                numerator / (window_energy * template_energy).sqrt()
+            } else {
+                0.
+            };
+            assert!((value - expected).abs() < 1e-6, "{k} {value} {expected}");
+            assert!((-1. ..=1.).contains(&value));
+        }
+        assert!((result[200] - 1.).abs() < 1e-9);
+        assert_eq!(result[410], 0.);
+
+        let src32 = src.iter().map(|&x| x as f32).collect::<Vec<_>>();
+        let template32 = template.iter().map(|&x| x as f32).collect::<Vec<_>>();
+        let zncc = Correlate::create_zncc_f32(src32.len(), &template32).unwrap();
+        let result32 = zncc.correlate_managed(&src32).unwrap();
+        let peak = result32
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        assert_eq!(peak.0, 200);
+        assert!((peak.1 - 1.).abs() < 1e-3);
+
+        assert!(Correlate::create_zncc_f64(10, &template).is_err());
+        assert!(zncc.correlate_managed(&src32[..20]).is_err());
+    }
+
+    #[test]
+    fn test_peaks() {
+        let pulse = (0..32)
+            .map(|x| ((x as f64) * 0.9).sin() * (-(x as f64 - 16.).powi(2) / 40.).exp())
+            .collect::<Vec<_>>();
+        let mut src = vec![0f64; 400];
+        src[57..89].copy_from_slice(&pulse);
+        src[250..282]
+            .iter_mut()
+            .zip(pulse.iter())
+            .for_each(|(dst, &x)| *dst = x * 0.5);
+
+        for mode in [
+            CrossCorrelationMode::Full,
+            CrossCorrelationMode::Same,
+            CrossCorrelationMode::Valid,
+        ] {
+            let correlation = Correlate::create_real_f64(src.len(), pulse.len(), mode).unwrap();
+            let result = correlation.correlate_managed(&src, &pulse).unwrap();
+            let lag_range = lags(mode, src.len(), pulse.len());
+            assert_eq!(lag_range.len(), result.len());
+
+            let peak = find_peak(&result, mode, src.len(), pulse.len()).unwrap();
+            assert_eq!(peak.lag, 57);
+            assert_eq!(peak.lag, lag_range.start + peak.index as isize);
+            assert_eq!(peak.value, result[peak.index]);
+
+            let peaks = find_peaks(&result, mode, src.len(), pulse.len(), 2, 32);
+            assert_eq!(peaks.len(), 2);
+            assert_eq!(peaks[0].lag, 57);
+            assert_eq!(peaks[1].lag, 250);
+            assert!(peaks[0].value > peaks[1].value);
+        }
+
+        let complex_src = src
+            .iter()
+            .map(|&x| num_complex::Complex::new(0., -x))
+            .collect::<Vec<_>>();
+        let complex_pulse = pulse
+            .iter()
+            .map(|&x| num_complex::Complex::new(x, 0.))
+            .collect::<Vec<_>>();
+        let mode = CrossCorrelationMode::Full;
+        let correlation =
+            Correlate::create_complex_f64(complex_src.len(), complex_pulse.len(), mode).unwrap();
+        let result = correlation
+            .correlate_managed(&complex_src, &complex_pulse)
+            .unwrap();
+        let peak = find_peak(&result, mode, complex_src.len(), complex_pulse.len()).unwrap();
+        assert_eq!(peak.lag, 57);
+        assert!(peak.value.im < 0.);
+
+        let peaks = find_peaks(&[1f32, 3., 2., 5., 4., 4., 6.], mode, 4, 4, 10, 2);
+        assert_eq!(
+            peaks.iter().map(|x| x.index).collect::<Vec<_>>(),
+            vec![6, 3, 1]
+        );
+        assert!(find_peak::<f32>(&[], mode, 1, 1).is_none());
+    }
+
+    #[test]
+    fn test_refine_peak() {
+        let gaussian = |t: f64| (-t * t / 32.).exp();
+        let delay = 57.3;
+        let src = (0..200)
+            .map(|x| gaussian(x as f64 - 20. - delay))
+            .collect::<Vec<_>>();
+        let template = (0..41)
+            .map(|x| gaussian(x as f64 - 20.))
+            .collect::<Vec<_>>();
+        let mode = CrossCorrelationMode::Full;
+        let correlation = Correlate::create_real_f64(src.len(), template.len(), mode).unwrap();
+        let result = correlation.correlate_managed(&src, &template).unwrap();
+        let peak = find_peak(&result, mode, src.len(), template.len()).unwrap();
+        assert_eq!(peak.lag, 57);
+
+        for (interpolation, tolerance) in [
+            (PeakInterpolation::Parabolic, 0.05),
+            (PeakInterpolation::Gaussian, 1e-6),
+            (PeakInterpolation::Centroid(20), 5e-3),
+            (PeakInterpolation::Sinc(24), 1e-3),
+            (PeakInterpolation::PhaseSlope(24), 1e-3),
+        ] {
+            let refined = refine_peak(&result, &peak, interpolation);
+            assert!(
+                (refined.delay - delay).abs() < tolerance,
+                "{interpolation:?} {}",
+                refined.delay
+            );
+            if !matches!(interpolation, PeakInterpolation::Centroid(_)) {
+                assert!(refined.height >= peak.value - 1e-9, "{interpolation:?}");
+            }
+        }
+
+        let complex = result
+            .iter()
+            .map(|&x| num_complex::Complex::new(x * 0.6, -x * 0.8))
+            .collect::<Vec<_>>();
+        let peak = find_peak(&complex, mode, src.len(), template.len()).unwrap();
+        for interpolation in [
+            PeakInterpolation::Sinc(24),
+            PeakInterpolation::PhaseSlope(24),
+        ] {
+            let refined = refine_peak(&complex, &peak, interpolation);
+            assert!((refined.delay - delay).abs() < 1e-3, "{interpolation:?}");
+            assert!(
+                (refined.height - peak.value.norm()).abs() < 1e-2 * peak.value.norm(),
+                "{interpolation:?}"
+            );
+        }
+
+        let edge = find_peak(&[3f64, 2., 1.], mode, 2, 2).unwrap();
+        let refined = refine_peak(&[3f64, 2., 1.], &edge, PeakInterpolation::Parabolic);
+        assert_eq!(refined.delay, -1.);
+        assert_eq!(refined.height, 3.);
+    }
+
+    #[test]
+    fn test_gcc_weighting() {
+        // Low-passed noise gives a broad plain correlation peak that PHAT sharpens
+        let mut state = 0x2545f491u32;
+        let mut noise = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as f64 / u32::MAX as f64 - 0.5
+        };
+        let mut smoothed = 0.;
+        let source = (0..600)
+            .map(|_| {
+                smoothed = smoothed * 0.9 + noise();
+                smoothed
+            })
+            .collect::<Vec<_>>();
+        let delay = 23;
+        let src = source[..512].to_vec();
+        let other = source[delay..delay + 512]
+            .iter()
+            .map(|&x| x * 0.7 + noise() * 0.1)
+            .collect::<Vec<_>>();
+
+        let mode = CrossCorrelationMode::Full;
+        let fft_size = mode.fft_size(src.len(), other.len());
+        let plain = Correlate::create_real_f64(src.len(), other.len(), mode)
+            .unwrap()
+            .correlate_managed(&src, &other)
+            .unwrap();
+        let plain_peak = find_peak(&plain, mode, src.len(), other.len()).unwrap();
+        let plain_width = plain
+            .iter()
+            .filter(|&&x| x > plain_peak.value * 0.5)
+            .count();
+
+        // Both inputs follow the spectrum of the first-order smoothing above
+        let power = (0..fft_size / 2 + 1)
+            .map(|k| {
+                let w = std::f64::consts::TAU * k as f64 / fft_size as f64;
+                let spectrum = 1. / (1. - 1.8 * w.cos() + 0.81);
+                spectrum * spectrum
+            })
+            .collect::<Vec<_>>();
+        for weighting in [
+            GccWeighting::Phat,
+            GccWeighting::Scot(power),
+            GccWeighting::Roth,
+            GccWeighting::Ml(vec![0.5; fft_size / 2 + 1]),
+        ] {
+            let result = Correlate::create_with_options::<f64>(
+                src.len(),
+                other.len(),
+                &CorrelateOptions::new()
+                    .mode(mode)
+                    .weighting(weighting.clone()),
+            )
+            .unwrap()
+            .correlate_managed(&src, &other)
+            .unwrap();
+            let peak = find_peak(&result, mode, src.len(), other.len()).unwrap();
+            assert_eq!(peak.lag, delay as isize, "{weighting:?}");
+            let width = result.iter().filter(|&&x| x > peak.value * 0.5).count();
+            assert!(width < plain_width, "{weighting:?}");
+
+            let src32 = src.iter().map(|&x| x as f32).collect::<Vec<_>>();
+            let other32 = other.iter().map(|&x| x as f32).collect::<Vec<_>>();
+            let result32 = Correlate::create_with_options::<f32>(
+                src.len(),
+                other.len(),
+                &CorrelateOptions::new()
+                    .mode(mode)
+                    .weighting(weighting.clone()),
+            )
+            .unwrap()
+            .correlate_managed(&src32, &other32)
+            .unwrap();
+            for (&a, &b) in result32.iter().zip(result.iter()) {
+                assert!((a as f64 - b).abs() < 1e-4 * peak.value.abs().max(1.));
+            }
+        }
+
+        let complex_src = src
+            .iter()
+            .map(|&x| num_complex::Complex::new(x, -x))
+            .collect::<Vec<_>>();
+        let complex_other = other
+            .iter()
+            .map(|&x| num_complex::Complex::new(x, -x))
+            .collect::<Vec<_>>();
+        let result = Correlate::create_complex_with_options::<f64>(
+            src.len(),
+            other.len(),
+            &CorrelateOptions::new()
+                .mode(mode)
+                .weighting(GccWeighting::Phat),
+        )
+        .unwrap()
+        .correlate_managed(&complex_src, &complex_other)
+        .unwrap();
+        let peak = find_peak(&result, mode, src.len(), other.len()).unwrap();
+        assert_eq!(peak.lag, delay as isize);
+
+        assert!(
+            Correlate::create_with_options::<f32>(
+                src.len(),
+                other.len(),
+                &CorrelateOptions::new()
+                    .mode(mode)
+                    .weighting(GccWeighting::Ml(vec![0.5; fft_size]))
+            )
+            .is_err()
+        );
+        assert!(matches!(
+            Correlate::create_with_options::<f64>(
+                src.len(),
+                other.len(),
+                &CorrelateOptions::new()
+                    .mode(mode)
+                    .weighting(GccWeighting::Scot(vec![1.; fft_size]))
+            ),
+            Err(CrossCorrelateError::WeightingSizeDoNotMatch(..))
+        ));
+    }
+
+    #[test]
+    fn test_real_2d() {
+        fn correlate_2d_brute(
+            buffer: &[f64],
+            (w, h): (usize, usize),
+            other: &[f64],
+            (kw, kh): (usize, usize),
+        ) -> Vec<f64> {
+            let (fw, fh) = (w + kw - 1, h + kh - 1);
+            let mut result = vec![0.; fw * fh];
+            for ly in 0..fh {
+                for lx in 0..fw {
+                    let mut sum = 0.;
+                    for y in 0..kh {
+                        for x in 0..kw {
+                            let sy = (ly + y) as isize - (kh - 1) as isize;
+                            let sx = (lx + x) as isize - (kw - 1) as isize;
+                            if sy >= 0 && sx >= 0 && (sy as usize) < h && (sx as usize) < w {
+                                sum += buffer[sy as usize * w + sx as usize] * other[y * kw + x];
+                            }
+                        }
+                    }
+                    result[ly * fw + lx] = sum;
+                }
+            }
+            result
+        }
+
+        // The larger image pads past `PARALLEL_FFT_SIZE`, so with `rayon` the row and
+        // column passes are split over the thread pool.
+        for (w, h) in [(13usize, 9usize), (70, 61)] {
+            let buffer = (0..w * h)
+                .map(|i| ((i * 37 % 101) as f64 / 50.) - 1.)
+                .collect::<Vec<_>>();
+            for (kw, kh) in [(4usize, 3usize), (13, 9), (16, 5)] {
+                let other = (0..kw * kh)
+                    .map(|i| ((i * 53 % 89) as f64 / 44.) - 1.)
+                    .collect::<Vec<_>>();
+                let full = correlate_2d_brute(&buffer, (w, h), &other, (kw, kh));
+                let full_width = w + kw - 1;
+
+                // Pad rows to exercise strides larger than the image width.
+                let buffer_stride = w + 3;
+                let mut strided_buffer = vec![0f64; buffer_stride * h];
+                for (dst, src) in strided_buffer
+                    .chunks_exact_mut(buffer_stride)
+                    .zip(buffer.chunks_exact(w))
+                {
+                    dst[..w].copy_from_slice(src);
+                }
+                let other_stride = kw + 1;
+                let mut strided_other = vec![0f64; other_stride * kh];
+                for (dst, src) in strided_other
+                    .chunks_exact_mut(other_stride)
+                    .zip(other.chunks_exact(kw))
+                {
+                    dst[..kw].copy_from_slice(src);
+                }
+
+                for mode in [
+                    CrossCorrelationMode::Full,
+                    CrossCorrelationMode::Same,
+                    CrossCorrelationMode::Valid,
+                ] {
+                    let correlator = Correlate::create_real_2d_f64(w, h, kw, kh, mode).unwrap();
+                    let (ow, oh) = (correlator.output_width(), correlator.output_height());
+                    assert_eq!(ow, mode.get_size(w, kw));
+                    assert_eq!(oh, mode.get_size(h, kh));
+                    let result = correlator
+                        .correlate_managed(
+                            &strided_buffer,
+                            buffer_stride,
+                            &strided_other,
+                            other_stride,
+                        )
+                        .unwrap();
+                    let (sx, sy) = (mode.start_index(w, kw), mode.start_index(h, kh));
+                    for y in 0..oh {
+                        for x in 0..ow {
+                            let expected = full[(sy + y) * full_width + sx + x];
+                            assert!(
+                                (result[y * ow + x] - expected).abs() < 1e-9,
+                                "{mode:?} {kw}x{kh} at ({x}, {y}): expected {expected}, got {}",
+                                result[y * ow + x]
+                            );
+                        }
+                    }
+
+                    let output_stride = ow + 2;
+                    let buffer32 = strided_buffer.iter().map(|&x| x as f32).collect::<Vec<_>>();
+                    let other32 = strided_other.iter().map(|&x| x as f32).collect::<Vec<_>>();
+                    let correlator32 = Correlate::create_real_2d_f32(w, h, kw, kh, mode).unwrap();
+                    let mut output32 = vec![0f32; output_stride * oh];
+                    let mut scratch = vec![0f32; correlator32.scratch_length()];
+                    correlator32
+                        .correlate_with_scratch(
+                            &mut output32,
+                            output_stride,
+                            &buffer32,
+                            buffer_stride,
+                            &other32,
+                            other_stride,
+                            &mut scratch,
+                        )
+                        .unwrap();
+                    for y in 0..oh {
+                        for x in 0..ow {
+                            assert!(
+                                (output32[y * output_stride + x] as f64 - result[y * ow + x]).abs()
+                                    < 1e-3
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        let correlator =
+            Correlate::create_real_2d_f32(4, 4, 2, 2, CrossCorrelationMode::Full).unwrap();
+        assert!(matches!(
+            correlator.correlate_managed(&[0.; 16], 3, &[0.; 4], 2),
+            Err(CrossCorrelateError::StrideIsTooSmall(4, 3))
+        ));
+        assert!(matches!(
+            correlator.correlate_managed(&[0.; 15], 4, &[0.; 4], 2),
+            Err(CrossCorrelateError::ImageSizeDoNotMatch(16, 15))
+        ));
+        let no_lags = CrossCorrelationMode::Range {
+            start_lag: 100,
+            end_lag: 200,
+        };
+        assert!(matches!(
+            Correlate::create_real_2d_f64(8, 8, 4, 4, no_lags),
+            Err(CrossCorrelateError::ModeSelectsNoLags(8, 4))
+        ));
+        assert!(crate::real_2d::check_image::<f64>(&[], 0, 8, 4).is_ok());
+        assert!(crate::real_2d::check_image::<f64>(&[], 4, 0, 4).is_ok());
+    }
+
+    #[test]
+    fn test_phase_correlation() {
+        let mut state = 0x2545f491u32;
+        let mut noise = move || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as f64 / u32::MAX as f64 - 0.5
+        };
+        let signal = (0..300).map(|_| noise()).collect::<Vec<_>>();
+        let unrelated = (0..240).map(|_| noise()).collect::<Vec<_>>();
+
+        let delay = 17usize;
+        let other = &signal[delay..delay + 240];
+        let buffer = signal[..240].iter().map(|&x| 3. * x).collect::<Vec<_>>();
+        let phase = Correlate::create_phase_correlation_f64(buffer.len(), other.len()).unwrap();
+        let estimate = phase.estimate(&buffer, other).unwrap();
+        assert_eq!(estimate.shift, delay as isize);
+        assert!((estimate.sub_sample_shift - delay as f64).abs() < 0.5);
+        assert!(estimate.confidence > 0.5, "{estimate:?}");
+        let unrelated_estimate = phase.estimate(&unrelated, other).unwrap();
+        assert!(
+            unrelated_estimate.confidence < 0.2,
+            "{unrelated_estimate:?}"
+        );
+
+        let buffer32 = buffer.iter().map(|&x| x as f32).collect::<Vec<_>>();
+        let other32 = other.iter().map(|&x| x as f32).collect::<Vec<_>>();
+        let estimate32 = Correlate::create_phase_correlation_f32(buffer.len(), other.len())
+            .unwrap()
+            .estimate(&buffer32, &other32)
+            .unwrap();
+        assert_eq!(estimate32.shift, delay as isize);
+
+        // Band-limited signal shifted by a fraction of a sample
+        let fractional = 0.3;
+        let tone = |t: f64| {
+            (0..12)
+                .map(|k| ((k as f64 * 0.15 + 0.05) * t + k as f64).sin())
+                .sum::<f64>()
+        };
+        let buffer = (0..256).map(|i| tone(i as f64)).collect::<Vec<_>>();
+        let other = (0..256)
+            .map(|i| tone(i as f64 + fractional))
+            .collect::<Vec<_>>();
+        let estimate = Correlate::create_phase_correlation_f64(256, 256)
+            .unwrap()
+            .estimate(&buffer, &other)
+            .unwrap();
+        assert_eq!(estimate.shift, 0);
+        assert!(
+            estimate.sub_sample_shift > 0. && estimate.sub_sample_shift < 0.5,
+            "{estimate:?}"
+        );
+
+        // Exact circular shifts peak at full height, shifts past half a period wrap
+        let period = (0..64).map(|_| noise()).collect::<Vec<_>>();
+        let phase = Correlate::create_phase_correlation_f64(64, 64).unwrap();
+        for shift in [5isize, -5, 32] {
+            let buffer = (0..64)
+                .map(|i| period[(i as isize - shift).rem_euclid(64) as usize])
+                .collect::<Vec<_>>();
+            let estimate = phase.estimate(&buffer, &period).unwrap();
+            assert_eq!(estimate.shift, shift);
+            assert!((estimate.sub_sample_shift - shift as f64).abs() < 1e-9);
+            assert!((estimate.confidence - 1.).abs() < 1e-9, "{estimate:?}");
+        }
+
+        // Images, the second one is cut out of the first with an offset
+        let (w, h) = (40usize, 32usize);
+        let image = (0..w * h).map(|_| noise()).collect::<Vec<_>>();
+        let (dx, dy) = (5usize, 3usize);
+        let (kw, kh) = (24usize, 20usize);
+        let mut template = vec![0.; kw * kh];
+        for y in 0..kh {
+            for x in 0..kw {
+                template[y * kw + x] = image[(y + dy) * w + x + dx] * 0.5;
+            }
+        }
+        let phase_2d = Correlate::create_phase_correlation_2d_f64(w, h, kw, kh).unwrap();
+        let estimate = phase_2d.estimate(&image, w, &template, kw).unwrap();
+        assert_eq!(
+            (estimate.shift_x, estimate.shift_y),
+            (dx as isize, dy as isize)
+        );
+        assert!((estimate.sub_pixel_x - dx as f64).abs() < 0.5);
+        assert!((estimate.sub_pixel_y - dy as f64).abs() < 0.5);
+        assert!(estimate.confidence > 0.1, "{estimate:?}");
+
+        let image32 = image.iter().map(|&x| x as f32).collect::<Vec<_>>();
+        let template32 = template.iter().map(|&x| x as f32).collect::<Vec<_>>();
+        let estimate32 = Correlate::create_phase_correlation_2d_f32(w, h, kw, kh)
+            .unwrap()
+            .estimate(&image32, w, &template32, kw)
+            .unwrap();
+        assert_eq!(
+            (estimate32.shift_x, estimate32.shift_y),
+            (dx as isize, dy as isize)
+        );
+
+        let (sx, sy) = (-3isize, 7isize);
+        let shifted = (0..w * h)
+            .map(|i| {
+                let x = (i % w) as isize - sx;
+                let y = (i / w) as isize - sy;
+                image[y.rem_euclid(h as isize) as usize * w + x.rem_euclid(w as isize) as usize]
+            })
+            .collect::<Vec<_>>();
+        let estimate = Correlate::create_phase_correlation_2d_f64(w, h, w, h)
+            .unwrap()
+            .estimate(&shifted, w, &image, w)
+            .unwrap();
+        assert_eq!((estimate.shift_x, estimate.shift_y), (sx, sy));
+        assert!((estimate.sub_pixel_x - sx as f64).abs() < 1e-9);
+        assert!((estimate.sub_pixel_y - sy as f64).abs() < 1e-9);
+        assert!((estimate.confidence - 1.).abs() < 1e-9, "{estimate:?}");
+
+        // The mode and weighting come from the phase correlator, the FFT size from `options`
+        let cache = FftPlanCache::<f64>::new();
+        let exact = CorrelateOptions::new()
+            .mode(CrossCorrelationMode::Valid)
+            .fft_size_policy(FftSizePolicy::Exact);
+        let estimate = Correlate::create_phase_correlation_2d_with_cache::<f64>(
+            (w, h),
+            (w, h),
+            &cache,
+            &exact,
+        )
+        .unwrap()
+        .estimate(&shifted, w, &image, w)
+        .unwrap();
+        assert_eq!((estimate.shift_x, estimate.shift_y), (sx, sy));
+        assert_eq!(cache.len(), 4);
+        let estimate =
+            Correlate::create_phase_correlation_with_cache::<f64>(64, 64, &cache, &exact)
+                .unwrap()
+                .estimate(&period, &period)
+                .unwrap();
+        assert_eq!(estimate.shift, 0);
+        assert_eq!(cache.len(), 6);
+        let estimate =
+            Correlate::create_phase_correlation_2d_with_options::<f32>(w, h, kw, kh, &exact)
+                .unwrap()
+                .estimate(&image32, w, &template32, kw)
+                .unwrap();
+        assert_eq!(
+            (estimate.shift_x, estimate.shift_y),
+            (dx as isize, dy as isize)
+        );
+        assert!(matches!(
+            Correlate::create_phase_correlation_with_options::<f64>(
+                64,
+                64,
+                &exact.clone().method(CorrelationMethod::Direct)
+            ),
+            Err(CrossCorrelateError::UnsupportedOption(_))
+        ));
+        assert!(matches!(
+            Correlate::create_phase_correlation_with_options::<f64>(
+                64,
+                64,
+                &exact.normalization(CrossCorrelationNormalization::Coeff)
+            ),
+            Err(CrossCorrelateError::UnsupportedOption(_))
+        ));
+    }
+
+    #[test]
+    fn test_streaming() {
+        let stream = (0..1000)
+            .map(|i| ((i * 29 % 97) as f64 / 48.) - 1.)
+            .collect::<Vec<_>>();
+        let template = (0..37)
+            .map(|i| ((i * 11 % 23) as f64 / 11.) - 1.)
+            .collect::<Vec<_>>();
+        let expected = Correlate::create_real_f64_with_template(
+            stream.len(),
+            &template,
+            CrossCorrelationMode::Valid,
+        )
+        .unwrap()
+        .correlate_managed(&stream)
+        .unwrap();
+        let direct = Correlate::create_with_options::<f64>(
+            stream.len(),
+            template.len(),
+            &CorrelateOptions::new()
+                .mode(CrossCorrelationMode::Valid)
+                .method(CorrelationMethod::Direct),
+        )
+        .unwrap()
+        .correlate_managed(&stream, &template)
+        .unwrap();
+        for (i, (&a, &b)) in expected.iter().zip(direct.iter()).enumerate() {
+            assert!((a - b).abs() < 1e-9, "at {i}: expected {b}, got {a}");
+        }
+
+        let block_size = streaming_block_size(template.len());
+        let mut streaming = Correlate::create_streaming_f64(&template, block_size).unwrap();
+        assert_eq!(streaming.block_size(), block_size);
+        assert_eq!(streaming.template_length(), template.len());
+        let mut whole = streaming.push(&stream).unwrap();
+        whole.extend(streaming.flush().unwrap());
+        assert_eq!(whole, expected);
+
+        // Streams ending on and around block boundaries
+        let step = block_size - (template.len() - 1);
+        for length in [
+            template.len(),
+            block_size,
+            block_size + 1,
+            block_size + step,
+        ] {
+            let batch = Correlate::create_real_f64_with_template(
+                length,
+                &template,
+                CrossCorrelationMode::Valid,
+            )
+            .unwrap()
+            .correlate_managed(&stream[..length])
+            .unwrap();
+            let mut streamed = streaming.push(&stream[..length]).unwrap();
+            streamed.extend(streaming.flush().unwrap());
+            assert_eq!(streamed, batch, "{length}");
+        }
+
+        // Chunking must not change a single bit
+        for chunk_sizes in [[1usize, 2, 3], [7, 91, 200], [128, 1, 64]] {
+            let mut chunked = Vec::new();
+            let mut remaining = stream.as_slice();
+            for &size in chunk_sizes.iter().cycle() {
+                if remaining.is_empty() {
+                    break;
+                }
+                let (chunk, rest) = remaining.split_at(size.min(remaining.len()));
+                streaming.push_into(chunk, &mut chunked).unwrap();
+                remaining = rest;
+            }
+            chunked.extend(streaming.flush().unwrap());
+            assert_eq!(chunked, whole, "{chunk_sizes:?}");
+        }
+
+        // A stream shorter than the template produces nothing
+        assert!(streaming.push(&stream[..20]).unwrap().is_empty());
+        assert!(streaming.flush().unwrap().is_empty());
+
+        let stream32 = stream.iter().map(|&x| x as f32).collect::<Vec<_>>();
+        let template32 = template.iter().map(|&x| x as f32).collect::<Vec<_>>();
+        let mut streaming32 = Correlate::create_streaming_f32(&template32, 100).unwrap();
+        let mut result32 = Vec::new();
+        for chunk in stream32.chunks(33) {
+            result32.extend(streaming32.push(chunk).unwrap());
+        }
+        result32.extend(streaming32.flush().unwrap());
+        assert_eq!(result32.len(), expected.len());
+        for (&a, &b) in result32.iter().zip(expected.iter()) {
+            assert!((a as f64 - b).abs() < 1e-3);
+        }
+        let mut streaming32 =
+            Correlate::create_streaming_f32(&template32, streaming_block_size(37)).unwrap();
+        let mut whole32 = streaming32.push(&stream32).unwrap();
+        whole32.extend(streaming32.flush().unwrap());
+        let expected32 = Correlate::create_real_f32_with_template(
+            stream32.len(),
+            &template32,
+            CrossCorrelationMode::Valid,
+        )
+        .unwrap()
+        .correlate_managed(&stream32)
+        .unwrap();
+        assert_eq!(whole32, expected32);
+
+        assert!(matches!(
+            Correlate::create_streaming_f32(&template32, 20),
+            Err(CrossCorrelateError::TemplateIsLongerThanBuffer(37, 20))
+        ));
+    }
+
+    #[test]
+    fn test_correlate_channels() {
+        let (channel_length, channel_stride, channel_count) = (200usize, 212usize, 6usize);
+        let channels = (0..channel_stride * channel_count)
+            .map(|i| ((i * 41 % 103) as f32 / 51.) - 1.)
+            .collect::<Vec<_>>();
+        let reference = (0..31)
+            .map(|i| ((i * 7 % 19) as f32 / 9.) - 1.)
+            .collect::<Vec<_>>();
+        for mode in [CrossCorrelationMode::Full, CrossCorrelationMode::Valid] {
+            let correlator =
+                Correlate::create_real_f32_with_template(channel_length, &reference, mode).unwrap();
+            let output_length = correlator.output_length(channel_length);
+            assert_eq!(
+                output_length,
+                mode.get_size(channel_length, reference.len())
+            );
+            let output_stride = output_length + 5;
+            let mut output = vec![0f32; output_stride * channel_count];
+            correlator
+                .correlate_channels(
+                    &mut output,
+                    output_stride,
+                    &channels,
+                    channel_stride,
+                    channel_length,
+                    channel_count,
+                )
+                .unwrap();
+            let slices = channels
+                .chunks(channel_stride)
+                .map(|x| &x[..channel_length])
+                .collect::<Vec<_>>();
+            let managed = correlator.correlate_channels_managed(&slices).unwrap();
+            assert_eq!(managed.len(), channel_count);
+            for (i, channel) in slices.iter().enumerate() {
+                let expected = correlator.correlate_managed(channel).unwrap();
+                assert_eq!(
+                    &output[i * output_stride..i * output_stride + output_length],
+                    expected.as_slice()
+                );
+                assert_eq!(managed[i], expected);
+            }
+
+            assert!(matches!(
+                correlator.correlate_channels(
+                    &mut output,
+                    output_stride,
+                    &channels,
+                    channel_length - 1,
+                    channel_length,
+                    channel_count,
+                ),
+                Err(CrossCorrelateError::StrideIsTooSmall(..))
+            ));
+            assert!(matches!(
+                correlator.correlate_channels(
+                    &mut output,
+                    output_stride,
+                    &channels,
+                    channel_stride,
+                    channel_length,
+                    channel_count + 1,
+                ),
+                Err(CrossCorrelateError::ImageSizeDoNotMatch(..))
+            ));
+        }
+
+        let complex_channels = (0..3)
+            .map(|c| {
+                (0..64)
+                    .map(|i| num_complex::Complex::new((i * c) as f64 % 5., (i + c) as f64 % 3.))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let complex_reference = (0..9)
+            .map(|i| num_complex::Complex::new(i as f64 % 4., 1. - i as f64 % 2.))
+            .collect::<Vec<_>>();
+        let correlator = Correlate::create_complex_f64_with_template(
+            64,
+            &complex_reference,
+            CrossCorrelationMode::Same,
+        )
+        .unwrap();
+        let slices = complex_channels
+            .iter()
+            .map(|x| x.as_slice())
+            .collect::<Vec<_>>();
+        let managed = correlator.correlate_channels_managed(&slices).unwrap();
+        for (channel, result) in slices.iter().zip(managed.iter()) {
+            assert_eq!(result, &correlator.correlate_managed(channel).unwrap());
+        }
+    }
+
+    struct DelegatingTemplate(std::sync::Arc<dyn CrossCorrelateTemplate<f32> + Send + Sync>);
+
+    impl CrossCorrelateTemplate<f32> for DelegatingTemplate {
+        fn correlate(&self, output: &mut [f32], buffer: &[f32]) -> Result<(), CrossCorrelateError> {
+            self.0.correlate(output, buffer)
+        }
+
+        fn correlate_managed(&self, buffer: &[f32]) -> Result<Vec<f32>, CrossCorrelateError> {
+            self.0.correlate_managed(buffer)
+        }
+
+        fn scratch_length(&self) -> usize {
+            self.0.scratch_length()
+        }
+
+        fn template_length(&self) -> usize {
+            self.0.template_length()
+        }
+
+        fn output_length(&self, buffer_length: usize) -> usize {
+            self.0.output_length(buffer_length)
+        }
+
+        fn correlate_with_scratch(
+            &self,
+            output: &mut [f32],
+            buffer: &[f32],
+            scratch: &mut [f32],
+        ) -> Result<(), CrossCorrelateError> {
+            self.0.correlate_with_scratch(output, buffer, scratch)
+        }
+    }
+
+    #[test]
+    fn test_default_correlate_channels() {
+        let channels = (0..3 * 50)
+            .map(|i| ((i * 13 % 29) as f32 / 14.) - 1.)
+            .collect::<Vec<_>>();
+        let reference = [0.5f32, -1., 0.25, 2.];
+        let correlator = DelegatingTemplate(
+            Correlate::create_real_f32_with_template(50, &reference, CrossCorrelationMode::Same)
+                .unwrap(),
+        );
+        let mut output = vec![0f32; 3 * 50];
+        correlator
+            .correlate_channels(&mut output, 50, &channels, 50, 50, 3)
+            .unwrap();
+        let slices = channels.chunks(50).collect::<Vec<_>>();
+        let managed = correlator.correlate_channels_managed(&slices).unwrap();
+        for (i, channel) in slices.iter().enumerate() {
+            let expected = correlator.correlate_managed(channel).unwrap();
+            assert_eq!(&output[i * 50..(i + 1) * 50], expected.as_slice());
+            assert_eq!(managed[i], expected);
+        }
+    }
+
+    #[test]
+    fn test_large_fft_matches_direct() {
+        // Large enough for the forward transforms to run concurrently with `rayon`
+        let buffer = (0..6000)
+            .map(|i| ((i * 31 % 97) as f64 / 48.) - 1.)
+            .collect::<Vec<_>>();
+        let other = (0..40).map(|i| (i as f64 * 0.3).sin()).collect::<Vec<_>>();
+        let mode = CrossCorrelationMode::Same;
+        let direct = Correlate::create_with_options::<f64>(
+            6000,
+            40,
+            &CorrelateOptions::new()
+                .mode(mode)
+                .method(CorrelationMethod::Direct),
+        )
+        .unwrap()
+        .correlate_managed(&buffer, &other)
+        .unwrap();
+        let real = Correlate::create_real_f64(6000, 40, mode)
+            .unwrap()
+            .correlate_managed(&buffer, &other)
+            .unwrap();
+        let to_complex = |x: &[f64]| {
+            x.iter()
+                .map(|&v| num_complex::Complex::new(v, 0.))
+                .collect::<Vec<_>>()
+        };
+        let complex = Correlate::create_complex_f64(6000, 40, mode)
+            .unwrap()
+            .correlate_managed(&to_complex(&buffer), &to_complex(&other))
+            .unwrap();
+        let mixed =
+            Correlate::create_real_complex::<f64>(6000, 40, &CorrelateOptions::new().mode(mode))
+                .unwrap()
+                .correlate_managed(&buffer, &to_complex(&other))
+                .unwrap();
+        for i in 0..direct.len() {
+            assert!((real[i] - direct[i]).abs() < 1e-9);
+            assert!((complex[i].re - direct[i]).abs() < 1e-9 && complex[i].im.abs() < 1e-9);
+            assert!((mixed[i].re - direct[i]).abs() < 1e-9 && mixed[i].im.abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_correlation_matrix() {
+        let source = (0..160)
+            .map(|i| ((i * 37 % 113) as f64 / 56.) - 1.)
+            .collect::<Vec<_>>();
+        let delays = [0usize, 4, 9, 21];
+        let channels = delays
+            .iter()
+            .map(|&d| source[d..d + 120].to_vec())
+            .collect::<Vec<_>>();
+        let slices = channels.iter().map(|x| x.as_slice()).collect::<Vec<_>>();
+
+        for mode in [CrossCorrelationMode::Full, CrossCorrelationMode::Same] {
+            let reference = Correlate::create_real_f64(120, 120, mode).unwrap();
+            for (pairs, expected_pairs) in
+                [(ChannelPairs::UpperTriangle, 6), (ChannelPairs::All, 16)]
+            {
+                let matrix = Correlate::create_real_matrix_f64(120, mode, pairs).unwrap();
+                assert_eq!(matrix.output_length(), mode.get_size(120, 120));
+                assert_eq!(matrix.lags(), lags(mode, 120, 120));
+                let result = matrix.correlate(&slices).unwrap();
+                assert_eq!(result.len(), expected_pairs);
+                for pair in result.iter() {
+                    if pairs == ChannelPairs::UpperTriangle {
+                        assert!(pair.first < pair.second);
+                    }
+                    let expected = reference
+                        .correlate_managed(&channels[pair.first], &channels[pair.second])
+                        .unwrap();
+                    for (&a, &b) in pair.output.iter().zip(expected.iter()) {
+                        assert!((a - b).abs() < 1e-9);
+                    }
+                }
+                let peaks = matrix.peaks(&slices).unwrap();
+                assert_eq!(peaks.len(), expected_pairs);
+                for (peak, pair) in peaks.iter().zip(result.iter()) {
+                    assert_eq!((peak.first, peak.second), (pair.first, pair.second));
+                    assert_eq!(
+                        peak.peak.lag,
+                        delays[pair.second] as isize - delays[pair.first] as isize
+                    );
+                }
+            }
+        }
+
+        let complex_channels = channels
+            .iter()
+            .map(|x| {
+                x.iter()
+                    .map(|&v| num_complex::Complex::new(v as f32, (v * 0.5) as f32))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let complex_slices = complex_channels
+            .iter()
+            .map(|x| x.as_slice())
+            .collect::<Vec<_>>();
+        let mode = CrossCorrelationMode::Full;
+        let reference = Correlate::create_complex_f32(120, 120, mode).unwrap();
+        let matrix =
+            Correlate::create_complex_matrix_f32(120, mode, ChannelPairs::UpperTriangle).unwrap();
+        for pair in matrix.correlate(&complex_slices).unwrap() {
+            let expected = reference
+                .correlate_managed(
+                    &complex_channels[pair.first],
+                    &complex_channels[pair.second],
+                )
+                .unwrap();
+            for (a, b) in pair.output.iter().zip(expected.iter()) {
+                assert!((a - b).norm() < 1e-2);
+            }
+        }
+
+        assert!(matches!(
+            matrix.peaks(&[&complex_channels[0], &complex_channels[1][..100]]),
+            Err(CrossCorrelateError::ChannelLengthDoNotMatch(120, 100))
+        ));
+    }
+
+    #[test]
+    fn test_autocorrelation() {
+        let signal = (0..157)
+            .map(|i| ((i * 31 % 89) as f64 / 44.) - 1.)
+            .collect::<Vec<_>>();
+        let n = signal.len();
+        for normalization in [
+            CrossCorrelationNormalization::None,
+            CrossCorrelationNormalization::Biased,
+            CrossCorrelationNormalization::Unbiased,
+            CrossCorrelationNormalization::Coeff,
+        ] {
+            let expected = Correlate::create_with_options::<f64>(
+                n,
+                n,
+                &CorrelateOptions::new()
+                    .mode(CrossCorrelationMode::Full)
+                    .normalization(normalization),
+            )
+            .unwrap()
+            .correlate_managed(&signal, &signal)
+            .unwrap();
+
+            let two_sided = Correlate::create_autocorrelation_f64(
+                n,
+                AutocorrelationLags::TwoSided,
+                normalization,
+            )
+            .unwrap()
+            .autocorrelate_managed(&signal)
+            .unwrap();
+            assert_eq!(two_sided.len(), 2 * n - 1);
+            for (i, (&a, &b)) in two_sided.iter().zip(expected.iter()).enumerate() {
+                assert!((a - b).abs() < 1e-9, "{normalization:?} at {i}: {a} vs {b}");
+            }
+
+            let one_sided = Correlate::create_autocorrelation_f64(
+                n,
+                AutocorrelationLags::OneSided,
+                normalization,
+            )
+            .unwrap()
+            .autocorrelate_managed(&signal)
+            .unwrap();
+            assert_eq!(one_sided.len(), n);
+            for (&a, &b) in one_sided.iter().zip(expected[n - 1..].iter()) {
+                assert!((a - b).abs() < 1e-9, "{normalization:?}");
+            }
+            if normalization == CrossCorrelationNormalization::Coeff {
+                assert!((one_sided[0] - 1.).abs() < 1e-12);
+            }
+
+            let signal32 = signal.iter().map(|&x| x as f32).collect::<Vec<_>>();
+            let one_sided32 = Correlate::create_autocorrelation_f32(
+                n,
+                AutocorrelationLags::OneSided,
+                normalization,
+            )
+            .unwrap()
+            .autocorrelate_managed(&signal32)
+            .unwrap();
+            for (&a, &b) in one_sided32.iter().zip(one_sided.iter()) {
+                assert!((a as f64 - b).abs() < 1e-3 * b.abs().max(1.));
+            }
+        }
+
+        let complex_signal = signal
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| num_complex::Complex::new(x, (i % 7) as f64 / 7.))
+            .collect::<Vec<_>>();
+        let expected = Correlate::create_complex_with_options::<f64>(
+            n,
+            n,
+            &CorrelateOptions::new()
+                .mode(CrossCorrelationMode::Full)
+                .normalization(CrossCorrelationNormalization::Unbiased),
+        )
+        .unwrap()
+        .correlate_managed(&complex_signal, &complex_signal)
+        .unwrap();
+        let autocorrelation = Correlate::create_complex_autocorrelation_f64(
+            n,
+            AutocorrelationLags::OneSided,
+            CrossCorrelationNormalization::Unbiased,
+        )
+        .unwrap();
+        let mut output = vec![num_complex::Complex::default(); autocorrelation.output_length(n)];
+        let mut scratch = vec![num_complex::Complex::default(); autocorrelation.scratch_length()];
+        autocorrelation
+            .autocorrelate_with_scratch(&mut output, &complex_signal, &mut scratch)
+            .unwrap();
+        for (a, b) in output.iter().zip(expected[n - 1..].iter()) {
+            assert!((a - b).norm() < 1e-9);
+        }
+        assert_eq!(AutocorrelationLags::OneSided.lags(n), 0..n as isize);
+        assert_eq!(
+            AutocorrelationLags::TwoSided.lags(n),
+            lags(CrossCorrelationMode::Full, n, n)
+        );
+    }
+
+    #[test]
+    fn test_convolve() {
+        // Odd lengths leave SIMD tails in the spectrum multipliers
+        for (n, m) in [(37usize, 11usize), (11, 37), (64, 5), (1, 9)] {
+            let buffer = (0..n)
+                .map(|i| ((i * 13 % 29) as f64 / 14.) - 1.)
+                .collect::<Vec<_>>();
+            let other = (0..m)
+                .map(|i| ((i * 7 % 17) as f64 / 8.) - 1.)
+                .collect::<Vec<_>>();
+            let mut full = vec![0f64; n + m - 1];
+            for (i, &a) in buffer.iter().enumerate() {
+                for (j, &b) in other.iter().enumerate() {
+                    full[i + j] += a * b;
+                }
+            }
+            for mode in [
+                CrossCorrelationMode::Full,
+                CrossCorrelationMode::Same,
+                CrossCorrelationMode::Valid,
+            ] {
+                let start = match mode {
+                    CrossCorrelationMode::Full => 0,
+                    CrossCorrelationMode::Same => (n.min(m) - 1) / 2,
+                    CrossCorrelationMode::Valid => n.min(m) - 1,
+                    CrossCorrelationMode::MaxLag { .. }
+                    | CrossCorrelationMode::Range { .. }
+                    | CrossCorrelationMode::Circular => {
+                        unreachable!()
+                    }
+                };
+                let expected = &full[start..start + mode.get_size(n, m)];
+
+                let real = Convolve::create_real_f64(n, m, mode)
+                    .unwrap()
+                    .convolve_managed(&buffer, &other)
+                    .unwrap();
+                assert_eq!(real.len(), expected.len());
+                for (i, (&a, &b)) in real.iter().zip(expected.iter()).enumerate() {
+                    assert!((a - b).abs() < 1e-9, "{mode:?} {n}x{m} at {i}: {a} vs {b}");
+                }
+
+                let buffer32 = buffer.iter().map(|&x| x as f32).collect::<Vec<_>>();
+                let other32 = other.iter().map(|&x| x as f32).collect::<Vec<_>>();
+                let real32 = Convolve::create_real_f32(n, m, mode)
+                    .unwrap()
+                    .convolve_managed(&buffer32, &other32)
+                    .unwrap();
+                for (&a, &b) in real32.iter().zip(expected.iter()) {
+                    assert!((a as f64 - b).abs() < 1e-4, "{mode:?} {n}x{m}");
+                }
+
+                // Imaginary parts only, so the product is real and negated
+                let complex_buffer = buffer
+                    .iter()
+                    .map(|&x| num_complex::Complex::new(0., x))
+                    .collect::<Vec<_>>();
+                let complex_other = other
+                    .iter()
+                    .map(|&x| num_complex::Complex::new(0., x))
+                    .collect::<Vec<_>>();
+                let complex = Convolve::create_complex_f64(n, m, mode)
+                    .unwrap()
+                    .convolve_managed(&complex_buffer, &complex_other)
+                    .unwrap();
+                for (&a, &b) in complex.iter().zip(expected.iter()) {
+                    assert!((a.re + b).abs() < 1e-9 && a.im.abs() < 1e-9, "{mode:?}");
+                }
+
+                let complex_buffer32 = complex_buffer
+                    .iter()
+                    .map(|x| num_complex::Complex::new(x.re as f32, x.im as f32))
+                    .collect::<Vec<_>>();
+                let complex_other32 = complex_other
+                    .iter()
+                    .map(|x| num_complex::Complex::new(x.re as f32, x.im as f32))
+                    .collect::<Vec<_>>();
+                let complex32 = Convolve::create_complex_f32(n, m, mode)
+                    .unwrap()
+                    .convolve_managed(&complex_buffer32, &complex_other32)
+                    .unwrap();
+                for (&a, &b) in complex32.iter().zip(expected.iter()) {
+                    assert!((a.re as f64 + b).abs() < 1e-4 && a.im.abs() < 1e-4);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_lag() {
+        let buffer = (0..301)
+            .map(|i| ((i * 17 % 53) as f64 / 26.) - 1.)
+            .collect::<Vec<_>>();
+        let other = (0..97)
+            .map(|i| ((i * 11 % 41) as f64 / 20.) - 1.)
+            .collect::<Vec<_>>();
+        for (a, b) in [(&buffer, &other), (&other, &buffer)] {
+            let (n, m) = (a.len(), b.len());
+            for (negative, positive) in [(8, 8), (0, 20), (15, 3), (500, 2), (4, 500)] {
+                let mode = CrossCorrelationMode::MaxLag { negative, positive };
+                assert!(mode.fft_size(n, m) <= CrossCorrelationMode::Full.fft_size(n, m));
+                let first = lags(mode, n, m).start;
+                assert_eq!(first, -(negative.min(m - 1) as isize));
+                let start = (first + m as isize - 1) as usize;
+                for normalization in [
+                    CrossCorrelationNormalization::None,
+                    CrossCorrelationNormalization::Unbiased,
+                    CrossCorrelationNormalization::Coeff,
+                ] {
+                    let full = Correlate::create_with_options::<f64>(
+                        n,
+                        m,
+                        &CorrelateOptions::new()
+                            .mode(CrossCorrelationMode::Full)
+                            .normalization(normalization),
+                    )
+                    .unwrap()
+                    .correlate_managed(a, b)
+                    .unwrap();
+                    let expected = &full[start..start + mode.get_size(n, m)];
+                    let windowed = Correlate::create_with_options::<f64>(
+                        n,
+                        m,
+                        &CorrelateOptions::new()
+                            .mode(mode)
+                            .normalization(normalization),
+                    )
+                    .unwrap()
+                    .correlate_managed(a, b)
+                    .unwrap();
+                    assert_eq!(windowed.len(), expected.len());
+                    for (i, (&x, &y)) in windowed.iter().zip(expected.iter()).enumerate() {
+                        assert!((x - y).abs() < 1e-9, "{mode:?} {normalization:?} at {i}");
+                    }
+                }
+
+                let expected = Correlate::create_real_f64(n, m, CrossCorrelationMode::Full)
+                    .unwrap()
+                    .correlate_managed(a, b)
+                    .unwrap();
+                let expected = &expected[start..start + mode.get_size(n, m)];
+                let direct = Correlate::create_with_options::<f64>(
+                    n,
+                    m,
+                    &CorrelateOptions::new()
+                        .mode(mode)
+                        .method(CorrelationMethod::Direct),
+                )
+                .unwrap()
+                .correlate_managed(a, b)
+                .unwrap();
+                for (&x, &y) in direct.iter().zip(expected.iter()) {
+                    assert!((x - y).abs() < 1e-9, "{mode:?}");
+                }
+
+                let complex_a = a
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &x)| num_complex::Complex::new(x, (i % 5) as f64 / 5.))
+                    .collect::<Vec<_>>();
+                let complex_b = b
+                    .iter()
+                    .map(|&x| num_complex::Complex::new(-x, 0.5))
+                    .collect::<Vec<_>>();
+                let full = Correlate::create_complex_f64(n, m, CrossCorrelationMode::Full)
+                    .unwrap()
+                    .correlate_managed(&complex_a, &complex_b)
+                    .unwrap();
+                let windowed = Correlate::create_complex_f64(n, m, mode)
+                    .unwrap()
+                    .correlate_managed(&complex_a, &complex_b)
+                    .unwrap();
+                for (&x, &y) in windowed.iter().zip(full[start..].iter()) {
+                    assert!((x - y).norm() < 1e-9, "{mode:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lag_range() {
+        let buffer = (0..523)
+            .map(|i| ((i * 19 % 61) as f64 / 30.) - 1.)
+            .collect::<Vec<_>>();
+        let other = (0..64)
+            .map(|i| ((i * 5 % 23) as f64 / 11.) - 1.)
+            .collect::<Vec<_>>();
+        for (a, b) in [(&buffer, &other), (&other, &buffer)] {
+            let (n, m) = (a.len(), b.len());
+            let full = Correlate::create_with_options::<f64>(
+                n,
+                m,
+                &CorrelateOptions::new()
+                    .mode(CrossCorrelationMode::Full)
+                    .normalization(CrossCorrelationNormalization::Unbiased),
+            )
+            .unwrap()
+            .correlate_managed(a, b)
+            .unwrap();
+            for (start_lag, end_lag) in [(20, 50), (-40, -3), (-7, 12), (-1000, 1000)] {
+                let mode = CrossCorrelationMode::Range { start_lag, end_lag };
+                let window = lags(mode, n, m);
+                assert_eq!(window.start, start_lag.max(1 - m as isize));
+                assert_eq!(window.end, end_lag.min(n as isize));
+                let start = (window.start + m as isize - 1) as usize;
+                let expected = &full[start..start + window.len()];
+
+                let gated = Correlate::create_with_options::<f64>(
+                    n,
+                    m,
+                    &CorrelateOptions::new()
+                        .mode(mode)
+                        .normalization(CrossCorrelationNormalization::Unbiased),
+                )
+                .unwrap()
+                .correlate_managed(a, b)
+                .unwrap();
+                assert_eq!(gated.len(), window.len());
+                for (i, (&x, &y)) in gated.iter().zip(expected.iter()).enumerate() {
+                    assert!((x - y).abs() < 1e-9, "{mode:?} at {i}: {x} vs {y}");
+                }
+
+                let a32 = a.iter().map(|&x| x as f32).collect::<Vec<_>>();
+                let b32 = b.iter().map(|&x| x as f32).collect::<Vec<_>>();
+                let template = Correlate::create_real_f32_with_template(n, &b32, mode)
+                    .unwrap()
+                    .correlate_managed(&a32)
+                    .unwrap();
+                let expected = Correlate::create_real_f64(n, m, CrossCorrelationMode::Full)
+                    .unwrap()
+                    .correlate_managed(a, b)
+                    .unwrap();
+                for (&x, &y) in template.iter().zip(expected[start..].iter()) {
+                    assert!((x as f64 - y).abs() < 1e-3 * y.abs().max(1.), "{mode:?}");
+                }
+
+                let complex_a = a
+                    .iter()
+                    .map(|&x| num_complex::Complex::new(x, 1. - x))
+                    .collect::<Vec<_>>();
+                let complex_b = b
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &x)| num_complex::Complex::new(x, (i % 3) as f64))
+                    .collect::<Vec<_>>();
+                let expected = Correlate::create_complex_f64(n, m, CrossCorrelationMode::Full)
+                    .unwrap()
+                    .correlate_managed(&complex_a, &complex_b)
+                    .unwrap();
+                let gated = Correlate::create_complex_f64(n, m, mode)
+                    .unwrap()
+                    .correlate_managed(&complex_a, &complex_b)
+                    .unwrap();
+                for (&x, &y) in gated.iter().zip(expected[start..].iter()) {
+                    assert!((x - y).norm() < 1e-9, "{mode:?}");
+                }
+            }
+        }
+
+        let beyond = CrossCorrelationMode::Range {
+            start_lag: 600,
+            end_lag: 700,
+        };
+        assert_eq!(beyond.get_size(buffer.len(), other.len()), 0);
+        let result = Correlate::create_real_f64(buffer.len(), other.len(), beyond);
+        assert!(matches!(
+            result,
+            Err(CrossCorrelateError::ModeSelectsNoLags(523, 64))
+        ));
+    }
+
+    #[test]
+    fn test_circular() {
+        let mode = CrossCorrelationMode::Circular;
+        // 1023 chips as a Gold code would have, and uneven lengths padded to the period
+        for (n, m) in [(1023usize, 1023usize), (37, 37), (40, 29), (29, 40)] {
+            let buffer = (0..n)
+                .map(|i| if (i * i * 7 + i) % 11 < 5 { 1. } else { -1. })
+                .collect::<Vec<f64>>();
+            let other = (0..m)
+                .map(|i| ((i * 3 % 13) as f64 / 6.) - 1.)
+                .collect::<Vec<f64>>();
+            let period = n.max(m);
+            let expected = (0..period)
+                .map(|k| {
+                    (0..m)
+                        .map(|j| {
+                            let index = (k + j) % period;
+                            if index < n {
+                                buffer[index] * other[j]
+                            } else {
+                                0.
+                            }
+                        })
+                        .sum::<f64>()
+                })
+                .collect::<Vec<_>>();
+
+            for method in [CorrelationMethod::Fft, CorrelationMethod::Direct] {
+                let result = Correlate::create_with_options::<f64>(
+                    n,
+                    m,
+                    &CorrelateOptions::new().mode(mode).method(method),
+                )
+                .unwrap()
+                .correlate_managed(&buffer, &other)
+                .unwrap();
+                assert_eq!(result.len(), period);
+                for (k, (&a, &b)) in result.iter().zip(expected.iter()).enumerate() {
+                    assert!(
+                        (a - b).abs() < 1e-9,
+                        "{method:?} {n}x{m} at {k}: {a} vs {b}"
+                    );
+                }
+
+                let complex_buffer = buffer
+                    .iter()
+                    .map(|&x| num_complex::Complex::new(x, -x))
+                    .collect::<Vec<_>>();
+                let complex_other = other
+                    .iter()
+                    .map(|&x| num_complex::Complex::new(x, 0.))
+                    .collect::<Vec<_>>();
+                let complex = Correlate::create_complex_with_options::<f64>(
+                    n,
+                    m,
+                    &CorrelateOptions::new().mode(mode).method(method),
+                )
+                .unwrap()
+                .correlate_managed(&complex_buffer, &complex_other)
+                .unwrap();
+                for (&a, &b) in complex.iter().zip(expected.iter()) {
+                    assert!((a.re - b).abs() < 1e-9 && (a.im + b).abs() < 1e-9);
+                }
+            }
+
+            let unbiased = Correlate::create_with_options::<f64>(
+                n,
+                m,
+                &CorrelateOptions::new()
+                    .mode(mode)
+                    .normalization(CrossCorrelationNormalization::Unbiased),
+            )
+            .unwrap()
+            .correlate_managed(&buffer, &other)
+            .unwrap();
+            for (&a, &b) in unbiased.iter().zip(expected.iter()) {
+                assert!((a - b / n.min(m) as f64).abs() < 1e-9);
+            }
+
+            let buffer32 = buffer.iter().map(|&x| x as f32).collect::<Vec<_>>();
+            let other32 = other.iter().map(|&x| x as f32).collect::<Vec<_>>();
+            let result32 = Correlate::create_real_f32(n, m, mode)
+                .unwrap()
+                .correlate_managed(&buffer32, &other32)
+                .unwrap();
+            for (&a, &b) in result32.iter().zip(expected.iter()) {
+                assert!((a as f64 - b).abs() < 1e-3 * b.abs().max(1.));
+            }
+        }
+
+        let buffer = [1., 2., 3., 4.];
+        let other = [1., 0., 0., 1.];
+        let convolution = Convolve::create_real_f64(4, 4, mode)
+            .unwrap()
+            .convolve_managed(&buffer, &other)
+            .unwrap();
+        for (&a, b) in convolution.iter().zip([3., 5., 7., 5.]) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_generic_create() {
+        fn correlate<T: CorrelateSample>(buffer: &[T], other: &[T]) -> Vec<T> {
+            Correlate::create::<T>(buffer.len(), other.len(), CrossCorrelationMode::Same)
+                .unwrap()
+                .correlate_managed(buffer, other)
+                .unwrap()
+        }
+
+        fn correlate_complex<T: CorrelateSample>(
+            buffer: &[num_complex::Complex<T>],
+            other: &[num_complex::Complex<T>],
+        ) -> Vec<num_complex::Complex<T>> {
+            Correlate::create_complex::<T>(buffer.len(), other.len(), CrossCorrelationMode::Same)
+                .unwrap()
+                .correlate_managed(buffer, other)
+                .unwrap()
+        }
+
+        let buffer = (0..83)
+            .map(|i| ((i * 29 % 47) as f64 / 23.) - 1.)
+            .collect::<Vec<_>>();
+        let other = (0..21).map(|i| (i as f64 * 0.3).sin()).collect::<Vec<_>>();
+        let mode = CrossCorrelationMode::Same;
+
+        let expected = Correlate::create_real_f64(buffer.len(), other.len(), mode)
+            .unwrap()
+            .correlate_managed(&buffer, &other)
+            .unwrap();
+        assert_eq!(correlate(&buffer, &other), expected);
+
+        let buffer32 = buffer.iter().map(|&x| x as f32).collect::<Vec<_>>();
+        let other32 = other.iter().map(|&x| x as f32).collect::<Vec<_>>();
+        let expected = Correlate::create_real_f32(buffer.len(), other.len(), mode)
+            .unwrap()
+            .correlate_managed(&buffer32, &other32)
+            .unwrap();
+        assert_eq!(correlate(&buffer32, &other32), expected);
+
+        let complex_buffer = buffer
+            .iter()
+            .map(|&x| num_complex::Complex::new(x as f32, 0.5 - x as f32))
+            .collect::<Vec<_>>();
+        let complex_other = other
+            .iter()
+            .map(|&x| num_complex::Complex::new(-x as f32, x as f32))
+            .collect::<Vec<_>>();
+        let expected = Correlate::create_complex_f32(buffer.len(), other.len(), mode)
+            .unwrap()
+            .correlate_managed(&complex_buffer, &complex_other)
+            .unwrap();
+        assert_eq!(correlate_complex(&complex_buffer, &complex_other), expected);
+    }
+
+    #[test]
+    fn test_correlate_options() {
+        let buffer = (0..211)
+            .map(|i| ((i * 23 % 67) as f64 / 33.) - 1.)
+            .collect::<Vec<_>>();
+        let other = (0..45).map(|i| (i as f64 * 0.7).cos()).collect::<Vec<_>>();
+        let (n, m) = (buffer.len(), other.len());
+
+        assert_eq!(
+            FftSizePolicy::Smooth.fft_size(CrossCorrelationMode::Full, n, m),
+            256
+        );
+        assert_eq!(
+            FftSizePolicy::PowerOfTwo.fft_size(CrossCorrelationMode::Full, n, m),
+            256
+        );
+        assert_eq!(
+            FftSizePolicy::Exact.fft_size(CrossCorrelationMode::Full, n, m),
+            255
+        );
+        assert_eq!(
+            FftSizePolicy::PowerOfTwo.fft_size(CrossCorrelationMode::Circular, 1023, 1023),
+            1023
+        );
+
+        let base = CorrelateOptions::new()
+            .mode(CrossCorrelationMode::Same)
+            .normalization(CrossCorrelationNormalization::Unbiased);
+        let expected = Correlate::create_with_options::<f64>(
+            n,
+            m,
+            &CorrelateOptions::new()
+                .mode(CrossCorrelationMode::Same)
+                .normalization(CrossCorrelationNormalization::Unbiased),
+        )
+        .unwrap()
+        .correlate_managed(&buffer, &other)
+        .unwrap();
+        for options in [
+            base.clone(),
+            base.clone().fft_size_policy(FftSizePolicy::PowerOfTwo),
+            base.clone().fft_size_policy(FftSizePolicy::Exact),
+            base.clone().method(CorrelationMethod::Direct),
+            base.clone().method(CorrelationMethod::Auto),
+        ] {
+            let result = Correlate::create_with_options::<f64>(n, m, &options)
+                .unwrap()
+                .correlate_managed(&buffer, &other)
+                .unwrap();
+            assert_eq!(result.len(), expected.len());
+            for (&a, &b) in result.iter().zip(expected.iter()) {
+                assert!((a - b).abs() < 1e-9, "{options:?}");
+            }
+        }
+
+        let complex_buffer = buffer
+            .iter()
+            .map(|&x| num_complex::Complex::new(x as f32, 0.))
+            .collect::<Vec<_>>();
+        let complex_other = other
+            .iter()
+            .map(|&x| num_complex::Complex::new(x as f32, 0.))
+            .collect::<Vec<_>>();
+        let complex = Correlate::create_complex_with_options::<f32>(
+            n,
+            m,
+            &base.clone().fft_size_policy(FftSizePolicy::Exact),
+        )
+        .unwrap()
+        .correlate_managed(&complex_buffer, &complex_other)
+        .unwrap();
+        for (&a, &b) in complex.iter().zip(expected.iter()) {
+            assert!((a.re as f64 - b).abs() < 1e-4 && a.im.abs() < 1e-4);
+        }
+
+        let phat = CorrelateOptions::new().weighting(GccWeighting::Phat);
+        let weighted = Correlate::create_with_options::<f64>(
+            n,
+            m,
+            &CorrelateOptions::new()
+                .mode(CrossCorrelationMode::Full)
+                .weighting(GccWeighting::Phat),
+        )
+        .unwrap()
+        .correlate_managed(&buffer, &other)
+        .unwrap();
+        let auto = Correlate::create_with_options::<f64>(
+            n,
+            m,
+            &phat.clone().method(CorrelationMethod::Auto),
+        )
+        .unwrap()
+        .correlate_managed(&buffer, &other)
+        .unwrap();
+        assert_eq!(auto, weighted);
+
+        assert!(matches!(
+            Correlate::create_with_options::<f64>(n, m, &phat.method(CorrelationMethod::Direct)),
+            Err(CrossCorrelateError::WeightingRequiresFft)
+        ));
+        assert!(matches!(
+            Correlate::create_with_options::<f32>(0, m, &CorrelateOptions::new()),
+            Err(CrossCorrelateError::BuffersMustNotHaveZeroSize)
+        ));
+        assert!(matches!(
+            Correlate::create_complex_with_options::<f64>(
+                n,
+                m,
+                &CorrelateOptions::new().weighting(GccWeighting::Ml(vec![0.5; 3]))
+            ),
+            Err(CrossCorrelateError::WeightingSizeDoNotMatch(256, 3))
+        ));
+    }
+
+    struct NaiveDft {
+        length: usize,
+        sign: f64,
+    }
+
+    impl NaiveDft {
+        fn transform(&self, input: &[num_complex::Complex<f64>]) -> Vec<num_complex::Complex<f64>> {
+            (0..self.length)
+                .map(|k| {
+                    input.iter().enumerate().fold(
+                        num_complex::Complex::new(0., 0.),
+                        |acc, (j, &x)| {
+                            let angle = self.sign * std::f64::consts::TAU * (j * k) as f64
+                                / self.length as f64;
+                            acc + x * num_complex::Complex::from_polar(1., angle)
+                        },
+                    )
+                })
+                .collect()
+        }
+    }
+
+    impl ComplexFft<f64> for NaiveDft {
+        fn execute(
+            &self,
+            buffer: &mut [num_complex::Complex<f64>],
+        ) -> Result<(), CrossCorrelateError> {
+            let spectrum = self.transform(buffer);
+            buffer.copy_from_slice(&spectrum);
+            Ok(())
+        }
+
+        fn length(&self) -> usize {
+            self.length
+        }
+    }
+
+    impl RealToComplexFft<f64> for NaiveDft {
+        fn execute(
+            &self,
+            input: &[f64],
+            output: &mut [num_complex::Complex<f64>],
+        ) -> Result<(), CrossCorrelateError> {
+            let widened = input
+                .iter()
+                .map(|&x| num_complex::Complex::new(x, 0.))
+                .collect::<Vec<_>>();
+            let spectrum = self.transform(&widened);
+            output.copy_from_slice(&spectrum[..output.len()]);
+            Ok(())
+        }
+
+        fn real_length(&self) -> usize {
+            self.length
+        }
+    }
+
+    impl ComplexToRealFft<f64> for NaiveDft {
+        fn execute(
+            &self,
+            input: &mut [num_complex::Complex<f64>],
+            output: &mut [f64],
+        ) -> Result<(), CrossCorrelateError> {
+            let full = (0..self.length)
+                .map(|k| {
+                    if k < input.len() {
+                        input[k]
+                    } else {
+                        input[self.length - k].conj()
+                    }
+                })
+                .collect::<Vec<_>>();
+            for (dst, src) in output.iter_mut().zip(self.transform(&full)) {
+                *dst = src.re;
+            }
+            Ok(())
+        }
+
+        fn real_length(&self) -> usize {
+            self.length
+        }
+    }
+
+    #[test]
+    fn test_fft_executors() {
+        let buffer = (0..37)
+            .map(|i| ((i * 11 % 19) as f64 / 9.) - 1.)
+            .collect::<Vec<_>>();
+        let other = (0..14).map(|i| (i as f64 * 0.4).sin()).collect::<Vec<_>>();
+        let (n, m) = (buffer.len(), other.len());
+        let options = CorrelateOptions::new()
+            .mode(CrossCorrelationMode::Same)
+            .normalization(CrossCorrelationNormalization::Coeff)
+            .fft_size_policy(FftSizePolicy::Exact);
+        let fft_size = FftSizePolicy::Exact.fft_size(CrossCorrelationMode::Same, n, m);
+        let forward = |length| std::sync::Arc::new(NaiveDft { length, sign: -1. });
+        let inverse = |length| std::sync::Arc::new(NaiveDft { length, sign: 1. });
+
+        let expected = Correlate::create_with_options::<f64>(n, m, &options)
+            .unwrap()
+            .correlate_managed(&buffer, &other)
+            .unwrap();
+        let real = Correlate::create_with_executors::<f64>(
+            n,
+            m,
+            forward(fft_size),
+            inverse(fft_size),
+            &options,
+        )
+        .unwrap()
+        .correlate_managed(&buffer, &other)
+        .unwrap();
+        assert_eq!(real.len(), expected.len());
+        for (&a, &b) in real.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-9);
+        }
+
+        let complex_buffer = buffer
+            .iter()
+            .map(|&x| num_complex::Complex::new(x, 0.))
+            .collect::<Vec<_>>();
+        let complex_other = other
+            .iter()
+            .map(|&x| num_complex::Complex::new(x, 0.))
+            .collect::<Vec<_>>();
+        let complex = Correlate::create_complex_with_executors::<f64>(
+            n,
+            m,
+            forward(fft_size),
+            inverse(fft_size),
+            &options,
+        )
+        .unwrap()
+        .correlate_managed(&complex_buffer, &complex_other)
+        .unwrap();
+        for (&a, &b) in complex.iter().zip(expected.iter()) {
+            assert!((a.re - b).abs() < 1e-9 && a.im.abs() < 1e-9);
+        }
+
+        assert!(matches!(
+            Correlate::create_with_executors::<f64>(
+                n,
+                m,
+                forward(fft_size),
+                inverse(fft_size + 1),
+                &options
+            ),
+            Err(CrossCorrelateError::FftSizesDoNotMatch(_, _))
+        ));
+        assert!(matches!(
+            Correlate::create_complex_with_executors::<f64>(
+                n,
+                m,
+                forward(fft_size + 1),
+                inverse(fft_size + 1),
+                &options
+            ),
+            Err(CrossCorrelateError::FftAndBuffersSizeDoNotMatch(_, _))
+        ));
+
+        #[cfg(feature = "rustfft")]
+        {
+            let mut planner = rustfft::FftPlanner::<f64>::new();
+            let forward = RustFftRealToComplex::new(planner.plan_fft_forward(fft_size)).unwrap();
+            // The widened signal lives in the caller's scratch
+            assert!(forward.scratch_length() >= fft_size);
+            let mut spectrum = vec![num_complex::Complex::<f64>::default(); fft_size / 2 + 1];
+            assert!(matches!(
+                forward.execute_with_scratch(&vec![0.; fft_size], &mut spectrum, &mut []),
+                Err(CrossCorrelateError::ScratchSizeIsTooSmall(_, 0))
+            ));
+            let rustfft_correlator = Correlate::create_with_executors::<f64>(
+                n,
+                m,
+                std::sync::Arc::new(forward),
+                std::sync::Arc::new(
+                    RustFftComplexToReal::new(planner.plan_fft_inverse(fft_size)).unwrap(),
+                ),
+                &options,
+            )
+            .unwrap();
+            let mut rustfft_real = vec![0.; expected.len()];
+            let mut scratch = vec![0.; rustfft_correlator.scratch_length()];
+            rustfft_correlator
+                .correlate_with_scratch(&mut rustfft_real, &buffer, &other, &mut scratch)
+                .unwrap();
+            for (&a, &b) in rustfft_real.iter().zip(expected.iter()) {
+                assert!((a - b).abs() < 1e-9);
+            }
+            let rustfft_complex = Correlate::create_complex_with_executors::<f64>(
+                n,
+                m,
+                std::sync::Arc::new(RustFftComplex::new(planner.plan_fft_forward(fft_size))),
+                std::sync::Arc::new(RustFftComplex::new(planner.plan_fft_inverse(fft_size))),
+                &options,
+            )
+            .unwrap()
+            .correlate_managed(&complex_buffer, &complex_other)
+            .unwrap();
+            for (&a, &b) in rustfft_complex.iter().zip(expected.iter()) {
+                assert!((a.re - b).abs() < 1e-9 && a.im.abs() < 1e-9);
+            }
+            assert!(RustFftRealToComplex::new(planner.plan_fft_inverse(fft_size)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_plan_cache() {
+        let cache = FftPlanCache::<f32>::new();
+        assert!(cache.is_empty());
+        let options = CorrelateOptions::new();
+        let buffer = (0..100)
+            .map(|i| ((i * 7 % 23) as f32 / 11.) - 1.)
+            .collect::<Vec<_>>();
+        let other = (0..29).map(|i| (i as f32 * 0.3).cos()).collect::<Vec<_>>();
+
+        // 100 + 29 - 1 and 97 + 32 - 1 both round up to 128
+        let first = Correlate::create_with_cache::<f32>(100, 29, &cache, &options).unwrap();
+        let _second = Correlate::create_with_cache::<f32>(97, 32, &cache, &options).unwrap();
+        assert_eq!(cache.len(), 2);
+        let expected = Correlate::create_real_f32(100, 29, CrossCorrelationMode::Full)
+            .unwrap()
+            .correlate_managed(&buffer, &other)
+            .unwrap();
+        assert_eq!(first.correlate_managed(&buffer, &other).unwrap(), expected);
+
+        assert!(std::sync::Arc::ptr_eq(
+            &cache.real_to_complex(128).unwrap(),
+            &cache.real_to_complex(128).unwrap()
+        ));
+        let _complex =
+            Correlate::create_complex_with_cache::<f32>(100, 29, &cache, &options).unwrap();
+        assert_eq!(cache.len(), 4);
+        let _direct = Correlate::create_with_cache::<f32>(
+            100,
+            29,
+            &cache,
+            &options.clone().method(CorrelationMethod::Direct),
+        )
+        .unwrap();
+        assert_eq!(cache.len(), 4);
+
+        std::thread::scope(|s| {
+            for length in [40, 50, 60, 70] {
+                let (cache, options) = (&cache, &options);
+                s.spawn(move || {
+                    Correlate::create_with_cache::<f32>(length, 29, cache, options).unwrap();
+                });
+            }
+        });
+        assert_eq!(cache.len(), 12);
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(first.correlate_managed(&buffer, &other).unwrap(), expected);
+
+        #[cfg(feature = "rustfft")]
+        {
+            let cache = FftPlanCache::<f32>::with_planner(std::sync::Arc::new(
+                RustFftPlanner::<f32>::new(),
+            ));
+            let correlator =
+                Correlate::create_with_cache::<f32>(100, 29, &cache, &options).unwrap();
+            assert_eq!(cache.len(), 2);
+            let actual = correlator.correlate_managed(&buffer, &other).unwrap();
+            for (a, e) in actual.iter().zip(expected.iter()) {
+                assert!((a - e).abs() < 1e-4, "{a} != {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_family_options() {
+        let a = (0..90)
+            .map(|i| ((i * 11 % 41) as f64 / 20.) - 1.)
+            .collect::<Vec<_>>();
+        let b = (0..90)
+            .map(|i| ((i * 5 % 37) as f64 / 18.) - 1.)
+            .collect::<Vec<_>>();
+        let exact = CorrelateOptions::new().fft_size_policy(FftSizePolicy::Exact);
+        let options = exact
+            .clone()
+            .normalization(CrossCorrelationNormalization::Coeff)
+            .weighting(GccWeighting::Phat);
+        let close = |actual: &[f64], expected: &[f64]| {
+            assert_eq!(actual.len(), expected.len());
+            for (i, (x, y)) in actual.iter().zip(expected.iter()).enumerate() {
+                assert!((x - y).abs() < 1e-9, "at {i}: {x} vs {y}");
+            }
+        };
+
+        let expected = Correlate::create_with_options::<f64>(90, 90, &options)
+            .unwrap()
+            .correlate_managed(&a, &b)
+            .unwrap();
+        let matrix = Correlate::create_real_matrix_with_options::<f64>(
+            90,
+            ChannelPairs::UpperTriangle,
+            &options,
+        )
+        .unwrap();
+        let pairs = matrix.correlate(&[&a, &b]).unwrap();
+        assert_eq!(pairs.len(), 1);
+        close(&pairs[0].output, &expected);
+
+        let complex_a = a
+            .iter()
+            .zip(b.iter())
+            .map(|(&x, &y)| num_complex::Complex::new(x, y))
+            .collect::<Vec<_>>();
+        let complex_b = b
+            .iter()
+            .map(|&x| num_complex::Complex::new(x, -0.5 * x))
+            .collect::<Vec<_>>();
+        let expected_complex = Correlate::create_complex_with_options::<f64>(90, 90, &options)
+            .unwrap()
+            .correlate_managed(&complex_a, &complex_b)
+            .unwrap();
+        let complex_pairs = Correlate::create_complex_matrix_with_options::<f64>(
+            90,
+            ChannelPairs::UpperTriangle,
+            &options,
+        )
+        .unwrap()
+        .correlate(&[&complex_a, &complex_b])
+        .unwrap();
+        for (x, y) in complex_pairs[0].output.iter().zip(expected_complex.iter()) {
+            assert!((x - y).norm() < 1e-9);
+        }
+
+        // A single row makes the column transforms identities
+        let image = Correlate::create_real_2d_with_options::<f64>(
+            90,
+            1,
+            90,
+            1,
+            &exact.clone().weighting(GccWeighting::Phat),
+        )
+        .unwrap()
+        .correlate_managed(&a, 90, &b, 90)
+        .unwrap();
+        let expected_phat = Correlate::create_with_options::<f64>(
+            90,
+            90,
+            &exact.clone().weighting(GccWeighting::Phat),
+        )
+        .unwrap()
+        .correlate_managed(&a, &b)
+        .unwrap();
+        close(&image, &expected_phat);
+
+        let biased = exact
+            .clone()
+            .normalization(CrossCorrelationNormalization::Biased);
+        let autocorrelation = Correlate::create_autocorrelation_with_options::<f64>(
+            90,
+            AutocorrelationLags::OneSided,
+            &biased,
+        )
+        .unwrap()
+        .autocorrelate_managed(&a)
+        .unwrap();
+        let smooth = Correlate::create_autocorrelation_f64(
+            90,
+            AutocorrelationLags::OneSided,
+            CrossCorrelationNormalization::Biased,
+        )
+        .unwrap()
+        .autocorrelate_managed(&a)
+        .unwrap();
+        close(&autocorrelation, &smooth);
+
+        let same = exact.clone().mode(CrossCorrelationMode::Same);
+        let convolved = Convolve::create_with_options::<f64>(90, 17, &same)
+            .unwrap()
+            .convolve_managed(&a, &b[..17])
+            .unwrap();
+        let expected_convolved = Convolve::create_real_f64(90, 17, CrossCorrelationMode::Same)
+            .unwrap()
+            .convolve_managed(&a, &b[..17])
+            .unwrap();
+        close(&convolved, &expected_convolved);
+
+        let cache = FftPlanCache::<f64>::new();
+        let _convolve = Convolve::create_with_cache::<f64>(90, 17, &cache, &same).unwrap();
+        let _matrix =
+            Correlate::create_real_matrix_with_cache::<f64>(90, ChannelPairs::All, &cache, &exact)
+                .unwrap();
+        let _autocorrelation = Correlate::create_autocorrelation_with_cache::<f64>(
+            90,
+            AutocorrelationLags::TwoSided,
+            &cache,
+            &exact,
+        )
+        .unwrap();
+        // 90 + 17 - 1 for the convolution, 2 * 90 - 1 shared by the other two
+        assert_eq!(cache.len(), 4);
+
+        let direct = CorrelateOptions::new().method(CorrelationMethod::Direct);
+        let coeff = CorrelateOptions::new().normalization(CrossCorrelationNormalization::Coeff);
+        let phat = CorrelateOptions::new().weighting(GccWeighting::Phat);
+        let unsupported = |result: Result<(), CrossCorrelateError>| {
+            assert!(matches!(
+                result,
+                Err(CrossCorrelateError::UnsupportedOption(_))
+            ));
+        };
+        unsupported(Convolve::create_with_options::<f64>(90, 17, &coeff).map(|_| ()));
+        unsupported(Convolve::create_complex_with_options::<f64>(90, 17, &phat).map(|_| ()));
+        unsupported(Convolve::create_with_options::<f64>(90, 17, &direct).map(|_| ()));
+        unsupported(
+            Correlate::create_autocorrelation_with_options::<f64>(
+                90,
+                AutocorrelationLags::OneSided,
+                &phat,
+            )
+            .map(|_| ()),
+        );
+        unsupported(
+            Correlate::create_real_matrix_with_options::<f64>(90, ChannelPairs::All, &direct)
+                .map(|_| ()),
+        );
+        unsupported(
+            Correlate::create_real_2d_with_options::<f64>(90, 1, 90, 1, &coeff).map(|_| ()),
+        );
+        unsupported(Correlate::create_zncc_with_options::<f64>(90, &b[..17], &phat).map(|_| ()));
+        unsupported(
+            Correlate::create_streaming_with_options::<f64>(&b[..17], 64, &coeff).map(|_| ()),
+        );
+    }
+
+    #[test]
+    fn test_mixed_correlation() {
+        let real = (0..83)
+            .map(|i| ((i * 13 % 41) as f64 / 20.) - 1.)
+            .collect::<Vec<_>>();
+        let complex = (0..26)
+            .map(|i| num_complex::Complex::new((i as f64 * 0.5).cos(), (i as f64 * 0.2).sin()))
+            .collect::<Vec<_>>();
+        let promoted = real
+            .iter()
+            .map(|&x| num_complex::Complex::new(x, 0.))
+            .collect::<Vec<_>>();
+        let (n, m) = (real.len(), complex.len());
+
+        for mode in [
+            CrossCorrelationMode::Full,
+            CrossCorrelationMode::Valid,
+            CrossCorrelationMode::Circular,
+            CrossCorrelationMode::max_lag(9),
+        ] {
+            let options = CorrelateOptions::new()
+                .mode(mode)
+                .normalization(CrossCorrelationNormalization::Coeff);
+
+            let expected = Correlate::create_complex_with_options::<f64>(n, m, &options)
+                .unwrap()
+                .correlate_managed(&promoted, &complex)
+                .unwrap();
+            let real_complex = Correlate::create_real_complex::<f64>(n, m, &options).unwrap();
+            let result = real_complex.correlate_managed(&real, &complex).unwrap();
+            assert_eq!(result.len(), expected.len());
+            for (a, b) in result.iter().zip(expected.iter()) {
+                assert!((a - b).norm() < 1e-9, "{mode:?}");
+            }
+
+            let mut scratch =
+                vec![num_complex::Complex::<f64>::default(); real_complex.scratch_length()];
+            let mut output = vec![num_complex::Complex::<f64>::default(); expected.len()];
+            real_complex
+                .correlate_with_scratch(&mut output, &real, &complex, &mut scratch)
+                .unwrap();
+            assert_eq!(output, result);
+
+            let expected = Correlate::create_complex_with_options::<f64>(m, n, &options)
+                .unwrap()
+                .correlate_managed(&complex, &promoted)
+                .unwrap();
+            let result = Correlate::create_complex_real::<f64>(m, n, &options)
+                .unwrap()
+                .correlate_managed(&complex, &real)
+                .unwrap();
+            assert_eq!(result.len(), expected.len());
+            for (a, b) in result.iter().zip(expected.iter()) {
+                assert!((a - b).norm() < 1e-9, "{mode:?}");
+            }
+        }
+
+        let real_f32 = real.iter().map(|&x| x as f32).collect::<Vec<_>>();
+        let complex_f32 = complex
+            .iter()
+            .map(|x| num_complex::Complex::new(x.re as f32, x.im as f32))
+            .collect::<Vec<_>>();
+        let options = CorrelateOptions::new().fft_size_policy(FftSizePolicy::Exact);
+        let expected = Correlate::create_complex_real::<f64>(m, n, &options)
+            .unwrap()
+            .correlate_managed(&complex, &real)
+            .unwrap();
+        let result = Correlate::create_complex_real::<f32>(m, n, &options)
+            .unwrap()
+            .correlate_managed(&complex_f32, &real_f32)
+            .unwrap();
+        for (a, b) in result.iter().zip(expected.iter()) {
+            assert!((a.re as f64 - b.re).abs() < 1e-3 && (a.im as f64 - b.im).abs() < 1e-3);
+        }
+
+        assert!(matches!(
+            Correlate::create_real_complex::<f64>(0, m, &CorrelateOptions::new()),
+            Err(CrossCorrelateError::BuffersMustNotHaveZeroSize)
+        ));
+        assert!(matches!(
+            Correlate::create_real_complex::<f64>(n, m, &CorrelateOptions::new())
+                .unwrap()
+                .correlate_managed(&real[..n / 2], &complex),
+            Err(CrossCorrelateError::FftAndBuffersSizeDoNotMatch(_, _))
+        ));
+        assert!(matches!(
+            Correlate::create_complex_real::<f64>(
+                m,
+                n,
+                &CorrelateOptions::new().method(CorrelationMethod::Direct)
+            ),
+            Err(CrossCorrelateError::UnsupportedOption(_))
+        ));
+
+        let options = CorrelateOptions::new().mode(CrossCorrelationMode::Same);
+        let expected = Correlate::create_real_complex::<f64>(n, m, &options)
+            .unwrap()
+            .correlate_managed(&real, &complex)
+            .unwrap();
+        let cache = FftPlanCache::<f64>::new();
+        let cached = Correlate::create_real_complex_with_cache::<f64>(n, m, &cache, &options)
+            .unwrap()
+            .correlate_managed(&real, &complex)
+            .unwrap();
+        assert_eq!(cached, expected);
+        let _swapped =
+            Correlate::create_complex_real_with_cache::<f64>(m, n, &cache, &options).unwrap();
+        assert_eq!(cache.len(), 3);
+
+        let fft_size = options
+            .fft_size_policy
+            .fft_size(CrossCorrelationMode::Same, n, m);
+        let naive = |length, sign| std::sync::Arc::new(NaiveDft { length, sign });
+        let result = Correlate::create_real_complex_with_executors::<f64>(
+            n,
+            m,
+            naive(fft_size, -1.),
+            naive(fft_size, -1.),
+            naive(fft_size, 1.),
+            &options,
+        )
+        .unwrap()
+        .correlate_managed(&real, &complex)
+        .unwrap();
+        for (a, b) in result.iter().zip(expected.iter()) {
+            assert!((a - b).norm() < 1e-9);
+        }
+        assert!(matches!(
+            Correlate::create_complex_real_with_executors::<f64>(
+                m,
+                n,
+                naive(fft_size + 1, -1.),
+                naive(fft_size, -1.),
+                naive(fft_size, 1.),
+                &options,
+            ),
+            Err(CrossCorrelateError::FftSizesDoNotMatch(_, _))
+        ));
+    }
 }
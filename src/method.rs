@@ -0,0 +1,161 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::{CrossCorrelationMode, FftSizePolicy};
+
+/// Selects the algorithm used to compute cross-correlation.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
+pub enum CorrelationMethod {
+    /// FFT-based correlation, `O((n + m) log(n + m))` (default)
+    #[default]
+    Fft,
+    /// Direct time-domain correlation, `O(n * m)`, fastest for short kernels
+    Direct,
+    /// Picks [`CorrelationMethod::Direct`] or [`CorrelationMethod::Fft`] using a cost
+    /// model of the two input lengths, similar to SciPy's `choose_conv_method`
+    Auto,
+}
+
+impl CorrelationMethod {
+    /// Resolves [`CorrelationMethod::Auto`] into a concrete method for the given lengths.
+    ///
+    /// The FFT is costed at the size `fft_size_policy` builds. `Fft` and `Direct` are
+    /// returned unchanged.
+    pub(crate) fn resolve(
+        self,
+        mode: CrossCorrelationMode,
+        fft_size_policy: FftSizePolicy,
+        buffer_len: usize,
+        other_len: usize,
+        is_complex: bool,
+    ) -> CorrelationMethod {
+        match self {
+            CorrelationMethod::Fft | CorrelationMethod::Direct => self,
+            CorrelationMethod::Auto => {
+                if buffer_len == 0 || other_len == 0 {
+                    return CorrelationMethod::Fft;
+                }
+                let output_len = mode.get_size(buffer_len, other_len) as f64;
+                let direct_ops = match mode {
+                    CrossCorrelationMode::Full => buffer_len as f64 * other_len as f64,
+                    // Every tap of `other` runs over the whole period
+                    CrossCorrelationMode::Circular => output_len * other_len as f64,
+                    CrossCorrelationMode::Same
+                    | CrossCorrelationMode::Valid
+                    | CrossCorrelationMode::MaxLag { .. }
+                    | CrossCorrelationMode::Range { .. } => {
+                        output_len * buffer_len.min(other_len) as f64
+                    }
+                };
+                let fft_size = fft_size_policy.fft_size(mode, buffer_len, other_len) as f64;
+                // Three transforms: both forward passes and the inverse.
+                let fft_ops = 3. * fft_size * fft_size.log2().max(1.);
+                let (direct_weight, fft_weight, fft_offset) = if is_complex {
+                    (DIRECT_COMPLEX_WEIGHT, FFT_COMPLEX_WEIGHT, FFT_OFFSET)
+                } else {
+                    (DIRECT_REAL_WEIGHT, FFT_REAL_WEIGHT, FFT_OFFSET)
+                };
+                if direct_ops * direct_weight < fft_ops * fft_weight + fft_offset {
+                    CorrelationMethod::Direct
+                } else {
+                    CorrelationMethod::Fft
+                }
+            }
+        }
+    }
+}
+
+// Relative cost per operation, only the ratios between the weights and the offset
+// decide the outcome. The values are picked by hand after the fitted constants of
+// SciPy's `choose_conv_method`; they have not been benchmarked against the SIMD
+// accumulators and FFT backends of this crate.
+const DIRECT_REAL_WEIGHT: f64 = 0.1;
+const DIRECT_COMPLEX_WEIGHT: f64 = 0.25;
+const FFT_REAL_WEIGHT: f64 = 0.3;
+const FFT_COMPLEX_WEIGHT: f64 = 0.5;
+// Fixed FFT overhead: padding, spectrum multiply and extraction setup.
+const FFT_OFFSET: f64 = 300.;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_method() {
+        let mode = CrossCorrelationMode::Full;
+        assert_eq!(
+            CorrelationMethod::Auto.resolve(mode, FftSizePolicy::Smooth, 4096, 16, false),
+            CorrelationMethod::Direct
+        );
+        assert_eq!(
+            CorrelationMethod::Auto.resolve(mode, FftSizePolicy::Smooth, 4096, 1024, false),
+            CorrelationMethod::Fft
+        );
+        assert_eq!(
+            CorrelationMethod::Fft.resolve(mode, FftSizePolicy::Smooth, 4096, 16, false),
+            CorrelationMethod::Fft
+        );
+        assert_eq!(
+            CorrelationMethod::Direct.resolve(mode, FftSizePolicy::Smooth, 4096, 1024, true),
+            CorrelationMethod::Direct
+        );
+
+        // A short buffer only costs `min(len)` per linear lag, but every tap of
+        // `other` runs over the whole circular period
+        assert_eq!(
+            CorrelationMethod::Auto.resolve(
+                CrossCorrelationMode::Valid,
+                FftSizePolicy::Smooth,
+                48,
+                4096,
+                false
+            ),
+            CorrelationMethod::Direct
+        );
+        assert_eq!(
+            CorrelationMethod::Auto.resolve(
+                CrossCorrelationMode::Circular,
+                FftSizePolicy::Smooth,
+                48,
+                4096,
+                false
+            ),
+            CorrelationMethod::Fft
+        );
+
+        // 1179 rounds up to 1200, or to 2048 as a power of two
+        assert_eq!(
+            CorrelationMethod::Auto.resolve(mode, FftSizePolicy::Smooth, 1030, 150, false),
+            CorrelationMethod::Fft
+        );
+        assert_eq!(
+            CorrelationMethod::Auto.resolve(mode, FftSizePolicy::PowerOfTwo, 1030, 150, false),
+            CorrelationMethod::Direct
+        );
+    }
+}
@@ -0,0 +1,325 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::error::try_vec;
+use crate::extract::extract_correlation;
+use crate::fft::{ComplexFft, RealToComplexFft};
+use crate::normalization::signal_energy;
+use crate::pad::pad_signal;
+use crate::parallel::{PARALLEL_FFT_SIZE, join};
+use crate::scratch::as_scalar_mut;
+use crate::spectrum::SpectrumMultiplier;
+use crate::weighting::SpectrumWeighting;
+use crate::{
+    CorrelateSample, CrossCorrelateError, CrossCorrelateMixed, CrossCorrelationMode,
+    CrossCorrelationNormalization, FftSizePolicy,
+};
+use num_complex::Complex;
+use std::sync::Arc;
+
+/// Correlates a real signal with a complex one, in either order.
+///
+/// The real side is transformed by the R2C plan and its half spectrum mirrored into
+/// the full Hermitian one, the complex side by the complex forward plan.
+pub(crate) struct CrossCorrelateRealComplex<T: CorrelateSample> {
+    pub(crate) fft_real: Arc<dyn RealToComplexFft<T> + Send + Sync>,
+    pub(crate) fft_forward: Arc<dyn ComplexFft<T> + Send + Sync>,
+    pub(crate) fft_inverse: Arc<dyn ComplexFft<T> + Send + Sync>,
+    pub(crate) multiplier: Arc<dyn SpectrumMultiplier<T> + Send + Sync>,
+    pub(crate) mode: CrossCorrelationMode,
+    pub(crate) normalization: CrossCorrelationNormalization,
+    pub(crate) weighting: SpectrumWeighting<T>,
+    pub(crate) fft_size_policy: FftSizePolicy,
+}
+
+/// Fills the upper half of a real signal's spectrum from its lower half.
+fn expand_hermitian<T: CorrelateSample>(spectrum: &mut [Complex<T>], half_length: usize) {
+    let fft_size = spectrum.len();
+    for k in half_length..fft_size {
+        spectrum[k] = spectrum[fft_size - k].conj();
+    }
+}
+
+impl<T: CorrelateSample> CrossCorrelateRealComplex<T> {
+    fn scratch_size(&self) -> usize {
+        // Two full spectrums, the work space of the real and the complex transforms running
+        // side by side, then the padded real signal stored as interleaved scalars.
+        let fft_size = self.fft_forward.length();
+        fft_size * 2
+            + self.fft_real.scratch_length()
+            + self.complex_scratch_length()
+            + fft_size.div_ceil(2)
+    }
+
+    /// Values of work space handed to one complex transform at a time.
+    fn complex_scratch_length(&self) -> usize {
+        self.fft_forward
+            .scratch_length()
+            .max(self.fft_inverse.scratch_length())
+    }
+
+    /// Splits `scratch` into both spectrums, the work space of the real and the
+    /// complex transforms, and the padded real signal.
+    #[allow(clippy::type_complexity)]
+    fn split_scratch<'a>(
+        &self,
+        scratch: &'a mut [Complex<T>],
+        fft_size: usize,
+    ) -> (
+        &'a mut [Complex<T>],
+        &'a mut [Complex<T>],
+        &'a mut [Complex<T>],
+        &'a mut [Complex<T>],
+        &'a mut [T],
+    ) {
+        let (spectrum_src, rest) = scratch[..self.scratch_size()].split_at_mut(fft_size);
+        let (spectrum_other, rest) = rest.split_at_mut(fft_size);
+        let (work_real, rest) = rest.split_at_mut(self.fft_real.scratch_length());
+        let (work_complex, padded) = rest.split_at_mut(self.complex_scratch_length());
+        let padded = &mut as_scalar_mut(padded)[..fft_size];
+        (
+            spectrum_src,
+            spectrum_other,
+            work_real,
+            work_complex,
+            padded,
+        )
+    }
+
+    /// Validates the arguments and returns the FFT size.
+    fn check(
+        &self,
+        output_length: usize,
+        buffer_length: usize,
+        other_length: usize,
+        scratch_length: usize,
+    ) -> Result<usize, CrossCorrelateError> {
+        if buffer_length == 0 || other_length == 0 || output_length == 0 {
+            return Err(CrossCorrelateError::BuffersMustNotHaveZeroSize);
+        }
+        let fft_length = self.fft_forward.length();
+        for length in [self.fft_real.real_length(), self.fft_inverse.length()] {
+            if length != fft_length {
+                return Err(CrossCorrelateError::FftSizesDoNotMatch(fft_length, length));
+            }
+        }
+        let data_length = self.mode.get_size(buffer_length, other_length);
+        let fft_size = self
+            .fft_size_policy
+            .fft_size(self.mode, buffer_length, other_length);
+
+        if fft_size != fft_length {
+            return Err(CrossCorrelateError::FftAndBuffersSizeDoNotMatch(
+                fft_length, fft_size,
+            ));
+        }
+
+        if output_length != data_length {
+            return Err(CrossCorrelateError::OutputSizeDoNotMatch(
+                data_length,
+                output_length,
+            ));
+        }
+
+        let required = self.scratch_size();
+        if scratch_length < required {
+            return Err(CrossCorrelateError::ScratchSizeIsTooSmall(
+                required,
+                scratch_length,
+            ));
+        }
+        Ok(fft_size)
+    }
+
+    fn real_spectrum(
+        &self,
+        spectrum: &mut [Complex<T>],
+        padded: &mut [T],
+        work: &mut [Complex<T>],
+        signal: &[T],
+    ) -> Result<(), CrossCorrelateError> {
+        let half_length = spectrum.len() / 2 + 1;
+        pad_signal(padded, signal);
+        self.fft_real
+            .execute_with_scratch(padded, &mut spectrum[..half_length], work)?;
+        expand_hermitian(spectrum, half_length);
+        Ok(())
+    }
+
+    fn complex_spectrum(
+        &self,
+        spectrum: &mut [Complex<T>],
+        work: &mut [Complex<T>],
+        signal: &[Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        pad_signal(spectrum, signal);
+        self.fft_forward.execute_with_scratch(spectrum, work)
+    }
+
+    fn finish(
+        &self,
+        output: &mut [Complex<T>],
+        spectrum_src: &mut [Complex<T>],
+        spectrum_other: &[Complex<T>],
+        work: &mut [Complex<T>],
+        (buffer_length, other_length): (usize, usize),
+        energy: impl FnOnce() -> f64,
+    ) -> Result<(), CrossCorrelateError> {
+        let fft_size = spectrum_src.len();
+        self.weighting.multiply(
+            self.multiplier.as_ref(),
+            spectrum_src,
+            spectrum_other,
+            fft_size,
+        );
+        self.fft_inverse.execute_with_scratch(spectrum_src, work)?;
+
+        let scale = self
+            .normalization
+            .lag_scale(self.mode, buffer_length, other_length, energy);
+        extract_correlation(
+            output,
+            spectrum_src,
+            self.mode,
+            buffer_length,
+            other_length,
+            &scale,
+        );
+        Ok(())
+    }
+}
+
+impl<T: CorrelateSample> CrossCorrelateMixed<T, Complex<T>, Complex<T>>
+    for CrossCorrelateRealComplex<T>
+{
+    fn correlate(
+        &self,
+        output: &mut [Complex<T>],
+        buffer: &[T],
+        other: &[Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        let mut scratch = try_vec![Complex::<T>::default(); self.scratch_size()];
+        self.correlate_with_scratch(output, buffer, other, &mut scratch)
+    }
+
+    fn correlate_managed(
+        &self,
+        buffer: &[T],
+        other: &[Complex<T>],
+    ) -> Result<Vec<Complex<T>>, CrossCorrelateError> {
+        let data_length = self.mode.get_size(buffer.len(), other.len());
+        let mut output = try_vec![Complex::<T>::default(); data_length];
+        self.correlate(&mut output, buffer, other).map(|_| output)
+    }
+
+    fn scratch_length(&self) -> usize {
+        self.scratch_size()
+    }
+
+    fn correlate_with_scratch(
+        &self,
+        output: &mut [Complex<T>],
+        buffer: &[T],
+        other: &[Complex<T>],
+        scratch: &mut [Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        let fft_size = self.check(output.len(), buffer.len(), other.len(), scratch.len())?;
+        let (spectrum_src, spectrum_other, work_real, work_complex, padded) =
+            self.split_scratch(scratch, fft_size);
+
+        let (src_result, other_result) = join(
+            fft_size >= PARALLEL_FFT_SIZE,
+            || self.real_spectrum(spectrum_src, padded, work_real, buffer),
+            || self.complex_spectrum(spectrum_other, work_complex, other),
+        );
+        src_result.and(other_result)?;
+        self.finish(
+            output,
+            spectrum_src,
+            spectrum_other,
+            work_complex,
+            (buffer.len(), other.len()),
+            || signal_energy(buffer) * signal_energy(other),
+        )
+    }
+}
+
+impl<T: CorrelateSample> CrossCorrelateMixed<Complex<T>, T, Complex<T>>
+    for CrossCorrelateRealComplex<T>
+{
+    fn correlate(
+        &self,
+        output: &mut [Complex<T>],
+        buffer: &[Complex<T>],
+        other: &[T],
+    ) -> Result<(), CrossCorrelateError> {
+        let mut scratch = try_vec![Complex::<T>::default(); self.scratch_size()];
+        self.correlate_with_scratch(output, buffer, other, &mut scratch)
+    }
+
+    fn correlate_managed(
+        &self,
+        buffer: &[Complex<T>],
+        other: &[T],
+    ) -> Result<Vec<Complex<T>>, CrossCorrelateError> {
+        let data_length = self.mode.get_size(buffer.len(), other.len());
+        let mut output = try_vec![Complex::<T>::default(); data_length];
+        self.correlate(&mut output, buffer, other).map(|_| output)
+    }
+
+    fn scratch_length(&self) -> usize {
+        self.scratch_size()
+    }
+
+    fn correlate_with_scratch(
+        &self,
+        output: &mut [Complex<T>],
+        buffer: &[Complex<T>],
+        other: &[T],
+        scratch: &mut [Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        let fft_size = self.check(output.len(), buffer.len(), other.len(), scratch.len())?;
+        let (spectrum_src, spectrum_other, work_real, work_complex, padded) =
+            self.split_scratch(scratch, fft_size);
+
+        let (src_result, other_result) = join(
+            fft_size >= PARALLEL_FFT_SIZE,
+            || self.complex_spectrum(spectrum_src, work_complex, buffer),
+            || self.real_spectrum(spectrum_other, padded, work_real, other),
+        );
+        src_result.and(other_result)?;
+        self.finish(
+            output,
+            spectrum_src,
+            spectrum_other,
+            work_complex,
+            (buffer.len(), other.len()),
+            || signal_energy(buffer) * signal_energy(other),
+        )
+    }
+}
@@ -34,15 +34,65 @@
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
 pub enum CrossCorrelationMode {
     /// Only fully overlapping elements are returned
+    ///
+    /// The window is anchored at the shorter input whichever argument it is, as in
+    /// NumPy's `correlate`, so a `buffer` shorter than `other` is slid across `other`.
     Valid,
     /// Output has the same length as the largest input
+    ///
+    /// The window is centred on the full correlation the same way NumPy's `correlate`
+    /// does, trimming `(shorter - 1) / 2` leading samples.
     Same,
     /// Full cross-correlation (default)
     #[default]
     Full,
+    /// Only lags `-negative..=positive` are returned, clamped to the lags
+    /// the inputs can produce.
+    ///
+    /// The FFT is sized to keep just these lags free of circular aliasing,
+    /// so a narrow window needs a much shorter transform than `Full`.
+    MaxLag { negative: usize, positive: usize },
+    /// Only lags `start_lag..end_lag` are returned, clamped to the lags
+    /// the inputs can produce.
+    ///
+    /// Like [`CrossCorrelationMode::MaxLag`] the FFT only covers the requested
+    /// lags, and the window does not have to include lag zero.
+    Range { start_lag: isize, end_lag: isize },
+    /// Circular correlation over one period of `max(buffer_len, other_len)`
+    /// samples, returning lags `0..period`.
+    ///
+    /// The FFT has exactly the period length, so equal-length inputs are not
+    /// padded at all and a shorter input is zero-padded to the period.
+    Circular,
 }
 
 impl CrossCorrelationMode {
+    /// Returns [`CrossCorrelationMode::MaxLag`] with the symmetric window `-lag..=lag`.
+    #[inline]
+    pub const fn max_lag(lag: usize) -> CrossCorrelationMode {
+        CrossCorrelationMode::MaxLag {
+            negative: lag,
+            positive: lag,
+        }
+    }
+
+    /// Returns the requested lags of `MaxLag` and `Range` clamped to
+    /// `-(other_len - 1)..buffer_len`, or `None` for the other modes.
+    #[inline]
+    fn lag_window(self, buffer_len: usize, other_len: usize) -> Option<std::ops::Range<isize>> {
+        let (start, end) = match self {
+            CrossCorrelationMode::MaxLag { negative, positive } => (
+                -(negative.min(other_len.saturating_sub(1)) as isize),
+                positive.min(buffer_len.saturating_sub(1)) as isize + 1,
+            ),
+            CrossCorrelationMode::Range { start_lag, end_lag } => (start_lag, end_lag),
+            _ => return None,
+        };
+        let first = 1 - other_len as isize;
+        let start = start.clamp(first, buffer_len as isize);
+        Some(start..end.clamp(start, buffer_len as isize))
+    }
+
     /// Compute the output length for a cross-correlation operation.
     ///
     /// The length of the correlation result depends on the chosen
@@ -54,16 +104,222 @@ impl CrossCorrelationMode {
             }
             CrossCorrelationMode::Same => buffer_len.max(other_len),
             CrossCorrelationMode::Full => buffer_len + other_len - 1,
+            CrossCorrelationMode::Circular => buffer_len.max(other_len),
+            CrossCorrelationMode::MaxLag { .. } | CrossCorrelationMode::Range { .. } => self
+                .lag_window(buffer_len, other_len)
+                .map_or(0, |window| window.len()),
+        }
+    }
+
+    /// Index of the first output sample within the full correlation of length
+    /// `buffer_len + other_len - 1`.
+    #[inline]
+    pub(crate) fn start_index(self, buffer_len: usize, other_len: usize) -> usize {
+        let shortest = buffer_len.min(other_len);
+        match self {
+            CrossCorrelationMode::Full => 0,
+            CrossCorrelationMode::Valid => shortest - 1,
+            CrossCorrelationMode::Same => (shortest - 1) / 2,
+            CrossCorrelationMode::Circular => other_len - 1,
+            CrossCorrelationMode::MaxLag { .. } | CrossCorrelationMode::Range { .. } => self
+                .lag_window(buffer_len, other_len)
+                .map_or(0, |window| (window.start + other_len as isize - 1) as usize),
         }
     }
 
     /// Compute the FFT size required for cross-correlation.
     ///
     /// This method determines the minimum "good" FFT size needed to perform
-    /// cross-correlation between two real-valued input sequences. Internally it
+    /// cross-correlation between two input sequences. Internally it
     /// calls [`fft_next_good_size`] to round up to an efficient FFT length.
+    ///
+    /// For [`CrossCorrelationMode::MaxLag`] and [`CrossCorrelationMode::Range`]
+    /// returning lags `a..=b` the size only has to reach
+    /// `max(buffer_len - a, other_len + b)`, beyond that every other lag wraps
+    /// onto indices outside the window. It never drops below the length of
+    /// either input, which still has to fit unfolded.
+    ///
+    /// [`CrossCorrelationMode::Circular`] uses exactly the period length.
     #[inline]
     pub fn fft_size(self, buffer_len: usize, other_len: usize) -> usize {
-        buffer_len + other_len - 1
+        let size = self.min_fft_size(buffer_len, other_len);
+        if self == CrossCorrelationMode::Circular {
+            return size;
+        }
+        fft_next_good_size(size)
+    }
+
+    /// Smallest FFT size that yields the selected lags without circular aliasing,
+    /// before rounding to an efficient length.
+    #[inline]
+    pub(crate) fn min_fft_size(self, buffer_len: usize, other_len: usize) -> usize {
+        if self == CrossCorrelationMode::Circular {
+            return buffer_len.max(other_len);
+        }
+        match self.lag_window(buffer_len, other_len) {
+            Some(window) => {
+                let lower = buffer_len as isize - window.start;
+                let upper = other_len as isize + window.end - 1;
+                (lower.max(upper).max(0) as usize)
+                    .max(buffer_len)
+                    .max(other_len)
+            }
+            None => buffer_len + other_len - 1,
+        }
+    }
+}
+
+/// Returns the lag of every output sample produced by `mode`.
+///
+/// Output index `i` of a correlation of `buffer_len` by `other_len` samples holds
+/// the lag `lags(..).start + i`, where lag `k` is `sum buffer[n + k] * conj(other[n])`.
+/// A positive lag therefore means that `other` appears delayed by `k` samples
+/// within `buffer`.
+pub fn lags(
+    mode: CrossCorrelationMode,
+    buffer_len: usize,
+    other_len: usize,
+) -> std::ops::Range<isize> {
+    let first = mode.start_index(buffer_len, other_len) as isize - (other_len as isize - 1);
+    first..first + mode.get_size(buffer_len, other_len) as isize
+}
+
+/// Returns the smallest 7-smooth number greater than or equal to `n`.
+///
+/// 7-smooth lengths (`2^a * 3^b * 5^c * 7^d`) are decomposed entirely into
+/// small radix butterflies, while lengths with large prime factors fall back
+/// to much slower Rader or Bluestein algorithms.
+///
+/// If no 7-smooth number `>= n` fits in a `usize`, `n` is returned unchanged.
+pub fn fft_next_good_size(n: usize) -> usize {
+    if n <= 8 {
+        return n;
+    }
+    let mut best = n.checked_next_power_of_two();
+    let bound = |best: Option<usize>| best.unwrap_or(usize::MAX);
+    let mut p7 = 1usize;
+    while p7 < bound(best) {
+        let mut p5 = p7;
+        while p5 < bound(best) {
+            let mut p3 = p5;
+            while p3 < bound(best) {
+                let mut candidate = Some(p3);
+                while let Some(value) = candidate.filter(|&x| x < n) {
+                    candidate = value.checked_mul(2);
+                }
+                match candidate {
+                    Some(candidate) if candidate == n => return n,
+                    Some(candidate) if best.is_none_or(|x| candidate < x) => best = Some(candidate),
+                    _ => {}
+                }
+                let Some(next) = p3.checked_mul(3) else {
+                    break;
+                };
+                p3 = next;
+            }
+            let Some(next) = p5.checked_mul(5) else {
+                break;
+            };
+            p5 = next;
+        }
+        let Some(next) = p7.checked_mul(7) else {
+            break;
+        };
+        p7 = next;
+    }
+    best.unwrap_or(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fft_next_good_size() {
+        assert_eq!(fft_next_good_size(1), 1);
+        assert_eq!(fft_next_good_size(7), 7);
+        assert_eq!(fft_next_good_size(11), 12);
+        assert_eq!(fft_next_good_size(13), 14);
+        assert_eq!(fft_next_good_size(17), 18);
+        assert_eq!(fft_next_good_size(1024), 1024);
+        assert_eq!(fft_next_good_size(2017), 2025);
+        assert_eq!(fft_next_good_size(1009), 1024);
+        assert_eq!(fft_next_good_size(usize::MAX), usize::MAX);
+        let above_powers = fft_next_good_size(usize::MAX / 2 + 2);
+        assert!(above_powers > usize::MAX / 2 + 2);
+        let mut factor = above_powers;
+        for p in [2, 3, 5, 7] {
+            while factor % p == 0 {
+                factor /= p;
+            }
+        }
+        assert_eq!(factor, 1);
+        let largest_power = 1usize << (usize::BITS - 1);
+        assert_eq!(fft_next_good_size(largest_power - 1), largest_power);
+        let is_smooth = |mut rem: usize| {
+            for p in [2, 3, 5, 7] {
+                while rem % p == 0 {
+                    rem /= p;
+                }
+            }
+            rem == 1
+        };
+        for n in 1..5000usize {
+            let expected = (n..).find(|&x| is_smooth(x)).unwrap();
+            assert_eq!(fft_next_good_size(n), expected, "{n}");
+        }
+    }
+
+    #[test]
+    fn test_lags() {
+        assert_eq!(lags(CrossCorrelationMode::Full, 5, 3), -2..5);
+        assert_eq!(lags(CrossCorrelationMode::Same, 5, 3), -1..4);
+        assert_eq!(lags(CrossCorrelationMode::Valid, 5, 3), 0..3);
+        assert_eq!(lags(CrossCorrelationMode::Full, 3, 5), -4..3);
+        assert_eq!(lags(CrossCorrelationMode::Same, 3, 5), -3..2);
+        assert_eq!(lags(CrossCorrelationMode::Valid, 3, 5), -2..1);
+        assert_eq!(lags(CrossCorrelationMode::max_lag(1), 5, 3), -1..2);
+        assert_eq!(
+            lags(
+                CrossCorrelationMode::MaxLag {
+                    negative: 0,
+                    positive: 9
+                },
+                5,
+                3
+            ),
+            0..5
+        );
+        assert_eq!(lags(CrossCorrelationMode::max_lag(9), 3, 5), -4..3);
+        let range = |start_lag, end_lag| CrossCorrelationMode::Range { start_lag, end_lag };
+        assert_eq!(lags(range(-1, 3), 5, 3), -1..3);
+        assert_eq!(lags(range(2, 4), 5, 3), 2..4);
+        assert_eq!(lags(range(-7, -3), 3, 5), -4..-3);
+        assert_eq!(lags(range(-9, 9), 5, 3), -2..5);
+        assert_eq!(range(6, 9).get_size(5, 3), 0);
+    }
+
+    #[test]
+    fn test_circular() {
+        let mode = CrossCorrelationMode::Circular;
+        assert_eq!(lags(mode, 1023, 1023), 0..1023);
+        assert_eq!(lags(mode, 5, 3), 0..5);
+        assert_eq!(lags(mode, 3, 5), 0..5);
+        assert_eq!(mode.fft_size(1023, 1023), 1023);
+        assert_eq!(mode.fft_size(11, 13), 13);
+    }
+
+    #[test]
+    fn test_max_lag_fft_size() {
+        let full = CrossCorrelationMode::Full.fft_size(1000, 1000);
+        assert_eq!(full, 2000);
+        assert_eq!(
+            CrossCorrelationMode::max_lag(10).fft_size(1000, 1000),
+            fft_next_good_size(1010)
+        );
+        assert_eq!(
+            CrossCorrelationMode::max_lag(5000).fft_size(1000, 1000),
+            full
+        );
     }
 }
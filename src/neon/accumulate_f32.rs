@@ -0,0 +1,133 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::accumulate::ScaledAccumulate;
+use num_complex::Complex;
+use std::arch::aarch64::{vdupq_n_f32, vfmaq_f32, vld1q_f32, vrev64q_f32, vst1q_f32};
+
+#[derive(Copy, Clone, Default)]
+pub(crate) struct AccumulateSingleNeon {}
+
+impl ScaledAccumulate<f32> for AccumulateSingleNeon {
+    fn accumulate(&self, dst: &mut [f32], src: &[f32], tap: f32) {
+        unsafe {
+            let v_scale = vdupq_n_f32(tap);
+
+            for (dst, src) in dst.chunks_exact_mut(16).zip(src.chunks_exact(16)) {
+                let vs0 = vld1q_f32(src.as_ptr());
+                let vs1 = vld1q_f32(src.get_unchecked(4..).as_ptr());
+                let vs2 = vld1q_f32(src.get_unchecked(8..).as_ptr());
+                let vs3 = vld1q_f32(src.get_unchecked(12..).as_ptr());
+                let vd0 = vld1q_f32(dst.as_ptr());
+                let vd1 = vld1q_f32(dst.get_unchecked(4..).as_ptr());
+                let vd2 = vld1q_f32(dst.get_unchecked(8..).as_ptr());
+                let vd3 = vld1q_f32(dst.get_unchecked(12..).as_ptr());
+
+                vst1q_f32(dst.as_mut_ptr(), vfmaq_f32(vd0, vs0, v_scale));
+                vst1q_f32(
+                    dst.get_unchecked_mut(4..).as_mut_ptr(),
+                    vfmaq_f32(vd1, vs1, v_scale),
+                );
+                vst1q_f32(
+                    dst.get_unchecked_mut(8..).as_mut_ptr(),
+                    vfmaq_f32(vd2, vs2, v_scale),
+                );
+                vst1q_f32(
+                    dst.get_unchecked_mut(12..).as_mut_ptr(),
+                    vfmaq_f32(vd3, vs3, v_scale),
+                );
+            }
+
+            let dst_rem = dst.chunks_exact_mut(16).into_remainder();
+            let src_rem = src.chunks_exact(16).remainder();
+
+            for (dst, src) in dst_rem.chunks_exact_mut(4).zip(src_rem.chunks_exact(4)) {
+                let vs = vld1q_f32(src.as_ptr());
+                let vd = vld1q_f32(dst.as_ptr());
+                vst1q_f32(dst.as_mut_ptr(), vfmaq_f32(vd, vs, v_scale));
+            }
+
+            let dst_rem = dst_rem.chunks_exact_mut(4).into_remainder();
+            let src_rem = src_rem.chunks_exact(4).remainder();
+
+            for (dst, &src) in dst_rem.iter_mut().zip(src_rem.iter()) {
+                *dst = f32::mul_add(src, tap, *dst);
+            }
+        }
+    }
+}
+
+impl ScaledAccumulate<Complex<f32>> for AccumulateSingleNeon {
+    fn accumulate(&self, dst: &mut [Complex<f32>], src: &[Complex<f32>], tap: Complex<f32>) {
+        unsafe {
+            let scale = tap.conj();
+            // src * scale = src * re(scale) + swap(src) * [-im(scale), im(scale)]
+            let v_re = vdupq_n_f32(scale.re);
+            let signed_im = [-scale.im, scale.im, -scale.im, scale.im];
+            let v_im = vld1q_f32(signed_im.as_ptr());
+
+            for (dst, src) in dst.chunks_exact_mut(8).zip(src.chunks_exact(8)) {
+                let vs0 = vld1q_f32(src.as_ptr().cast());
+                let vs1 = vld1q_f32(src.get_unchecked(2..).as_ptr().cast());
+                let vs2 = vld1q_f32(src.get_unchecked(4..).as_ptr().cast());
+                let vs3 = vld1q_f32(src.get_unchecked(6..).as_ptr().cast());
+                let vd0 = vld1q_f32(dst.as_ptr().cast());
+                let vd1 = vld1q_f32(dst.get_unchecked(2..).as_ptr().cast());
+                let vd2 = vld1q_f32(dst.get_unchecked(4..).as_ptr().cast());
+                let vd3 = vld1q_f32(dst.get_unchecked(6..).as_ptr().cast());
+
+                let p0 = vfmaq_f32(vfmaq_f32(vd0, vs0, v_re), vrev64q_f32(vs0), v_im);
+                let p1 = vfmaq_f32(vfmaq_f32(vd1, vs1, v_re), vrev64q_f32(vs1), v_im);
+                let p2 = vfmaq_f32(vfmaq_f32(vd2, vs2, v_re), vrev64q_f32(vs2), v_im);
+                let p3 = vfmaq_f32(vfmaq_f32(vd3, vs3, v_re), vrev64q_f32(vs3), v_im);
+
+                vst1q_f32(dst.as_mut_ptr().cast(), p0);
+                vst1q_f32(dst.get_unchecked_mut(2..).as_mut_ptr().cast(), p1);
+                vst1q_f32(dst.get_unchecked_mut(4..).as_mut_ptr().cast(), p2);
+                vst1q_f32(dst.get_unchecked_mut(6..).as_mut_ptr().cast(), p3);
+            }
+
+            let dst_rem = dst.chunks_exact_mut(8).into_remainder();
+            let src_rem = src.chunks_exact(8).remainder();
+
+            for (dst, src) in dst_rem.chunks_exact_mut(2).zip(src_rem.chunks_exact(2)) {
+                let vs = vld1q_f32(src.as_ptr().cast());
+                let vd = vld1q_f32(dst.as_ptr().cast());
+                let p = vfmaq_f32(vfmaq_f32(vd, vs, v_re), vrev64q_f32(vs), v_im);
+                vst1q_f32(dst.as_mut_ptr().cast(), p);
+            }
+
+            let dst_rem = dst_rem.chunks_exact_mut(2).into_remainder();
+            let src_rem = src_rem.chunks_exact(2).remainder();
+
+            for (dst, &src) in dst_rem.iter_mut().zip(src_rem.iter()) {
+                *dst += src * scale;
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::accumulate::ScaledAccumulate;
+use num_complex::Complex;
+use std::arch::aarch64::{vdupq_n_f64, vextq_f64, vfmaq_f64, vld1q_f64, vst1q_f64};
+
+#[derive(Copy, Clone, Default)]
+pub(crate) struct AccumulateDoubleNeon {}
+
+impl ScaledAccumulate<f64> for AccumulateDoubleNeon {
+    fn accumulate(&self, dst: &mut [f64], src: &[f64], tap: f64) {
+        unsafe {
+            let v_scale = vdupq_n_f64(tap);
+
+            for (dst, src) in dst.chunks_exact_mut(8).zip(src.chunks_exact(8)) {
+                let vs0 = vld1q_f64(src.as_ptr());
+                let vs1 = vld1q_f64(src.get_unchecked(2..).as_ptr());
+                let vs2 = vld1q_f64(src.get_unchecked(4..).as_ptr());
+                let vs3 = vld1q_f64(src.get_unchecked(6..).as_ptr());
+                let vd0 = vld1q_f64(dst.as_ptr());
+                let vd1 = vld1q_f64(dst.get_unchecked(2..).as_ptr());
+                let vd2 = vld1q_f64(dst.get_unchecked(4..).as_ptr());
+                let vd3 = vld1q_f64(dst.get_unchecked(6..).as_ptr());
+
+                vst1q_f64(dst.as_mut_ptr(), vfmaq_f64(vd0, vs0, v_scale));
+                vst1q_f64(
+                    dst.get_unchecked_mut(2..).as_mut_ptr(),
+                    vfmaq_f64(vd1, vs1, v_scale),
+                );
+                vst1q_f64(
+                    dst.get_unchecked_mut(4..).as_mut_ptr(),
+                    vfmaq_f64(vd2, vs2, v_scale),
+                );
+                vst1q_f64(
+                    dst.get_unchecked_mut(6..).as_mut_ptr(),
+                    vfmaq_f64(vd3, vs3, v_scale),
+                );
+            }
+
+            let dst_rem = dst.chunks_exact_mut(8).into_remainder();
+            let src_rem = src.chunks_exact(8).remainder();
+
+            for (dst, src) in dst_rem.chunks_exact_mut(2).zip(src_rem.chunks_exact(2)) {
+                let vs = vld1q_f64(src.as_ptr());
+                let vd = vld1q_f64(dst.as_ptr());
+                vst1q_f64(dst.as_mut_ptr(), vfmaq_f64(vd, vs, v_scale));
+            }
+
+            let dst_rem = dst_rem.chunks_exact_mut(2).into_remainder();
+            let src_rem = src_rem.chunks_exact(2).remainder();
+
+            for (dst, &src) in dst_rem.iter_mut().zip(src_rem.iter()) {
+                *dst = f64::mul_add(src, tap, *dst);
+            }
+        }
+    }
+}
+
+impl ScaledAccumulate<Complex<f64>> for AccumulateDoubleNeon {
+    fn accumulate(&self, dst: &mut [Complex<f64>], src: &[Complex<f64>], tap: Complex<f64>) {
+        unsafe {
+            let scale = tap.conj();
+            // src * scale = src * re(scale) + swap(src) * [-im(scale), im(scale)]
+            let v_re = vdupq_n_f64(scale.re);
+            let signed_im = [-scale.im, scale.im];
+            let v_im = vld1q_f64(signed_im.as_ptr());
+
+            for (dst, src) in dst.chunks_exact_mut(4).zip(src.chunks_exact(4)) {
+                let vs0 = vld1q_f64(src.as_ptr().cast());
+                let vs1 = vld1q_f64(src.get_unchecked(1..).as_ptr().cast());
+                let vs2 = vld1q_f64(src.get_unchecked(2..).as_ptr().cast());
+                let vs3 = vld1q_f64(src.get_unchecked(3..).as_ptr().cast());
+                let vd0 = vld1q_f64(dst.as_ptr().cast());
+                let vd1 = vld1q_f64(dst.get_unchecked(1..).as_ptr().cast());
+                let vd2 = vld1q_f64(dst.get_unchecked(2..).as_ptr().cast());
+                let vd3 = vld1q_f64(dst.get_unchecked(3..).as_ptr().cast());
+
+                let p0 = vfmaq_f64(vfmaq_f64(vd0, vs0, v_re), vextq_f64::<1>(vs0, vs0), v_im);
+                let p1 = vfmaq_f64(vfmaq_f64(vd1, vs1, v_re), vextq_f64::<1>(vs1, vs1), v_im);
+                let p2 = vfmaq_f64(vfmaq_f64(vd2, vs2, v_re), vextq_f64::<1>(vs2, vs2), v_im);
+                let p3 = vfmaq_f64(vfmaq_f64(vd3, vs3, v_re), vextq_f64::<1>(vs3, vs3), v_im);
+
+                vst1q_f64(dst.as_mut_ptr().cast(), p0);
+                vst1q_f64(dst.get_unchecked_mut(1..).as_mut_ptr().cast(), p1);
+                vst1q_f64(dst.get_unchecked_mut(2..).as_mut_ptr().cast(), p2);
+                vst1q_f64(dst.get_unchecked_mut(3..).as_mut_ptr().cast(), p3);
+            }
+
+            let dst_rem = dst.chunks_exact_mut(4).into_remainder();
+            let src_rem = src.chunks_exact(4).remainder();
+
+            for (dst, src) in dst_rem.iter_mut().zip(src_rem.iter()) {
+                let vs = vld1q_f64(src as *const Complex<f64> as *const f64);
+                let vd = vld1q_f64(dst as *const Complex<f64> as *const f64);
+                let p = vfmaq_f64(vfmaq_f64(vd, vs, v_re), vextq_f64::<1>(vs, vs), v_im);
+                vst1q_f64(dst as *mut Complex<f64> as *mut f64, p);
+            }
+        }
+    }
+}
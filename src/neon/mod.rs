@@ -26,14 +26,18 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
+mod accumulate_f32;
+mod accumulate_f64;
 mod complex;
-mod mul_spectrum_f32;
+pub(crate) mod mul_spectrum_f32;
 #[cfg(feature = "fcma")]
 mod mul_spectrum_f32_fcm;
-mod mul_spectrum_f64;
+pub(crate) mod mul_spectrum_f64;
 #[cfg(feature = "fcma")]
 mod mul_spectrum_f64_fcm;
 
+pub(crate) use accumulate_f32::AccumulateSingleNeon;
+pub(crate) use accumulate_f64::AccumulateDoubleNeon;
 pub(crate) use mul_spectrum_f32::SpectrumMulSingleNeon;
 #[cfg(feature = "fcma")]
 pub(crate) use mul_spectrum_f32_fcm::SpectrumMulSingleFcma;
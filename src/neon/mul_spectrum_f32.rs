@@ -28,7 +28,12 @@
  */
 use crate::neon::complex::{mul_complex_f32, mulh_complex_f32};
 use crate::spectrum::SpectrumMultiplier;
+use crate::spectrum::{mul_spectrum_gains_impl, mul_spectrum_phat_impl, mul_spectrum_roth_impl};
 use num_complex::Complex;
+use std::arch::aarch64::{
+    float32x4_t, vaddq_f32, vandq_u32, vcgtq_f32, vcombine_f32, vdivq_f32, vdup_lane_f32,
+    vrev64q_f32, vsqrtq_f32,
+};
 use std::arch::aarch64::{
     vdupq_n_f32, veor_u32, veorq_u32, vget_low_f32, vget_low_u32, vld1_f32, vld1q_f32, vmul_f32,
     vmulq_f32, vreinterpret_f32_u32, vreinterpret_u32_f32, vreinterpretq_f32_u32,
@@ -41,70 +46,268 @@ pub(crate) struct SpectrumMulSingleNeon {}
 impl SpectrumMultiplier<f32> for SpectrumMulSingleNeon {
     fn mul_spectrum(&self, buffer: &mut [Complex<f32>], other: &[Complex<f32>], len: usize) {
         unsafe {
-            let normalization_factor = (1f64 / len as f64) as f32;
-            let v_norm_factor = vdupq_n_f32(normalization_factor);
+            mul_spectrum_f32_neon::<true>(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_convolve(
+        &self,
+        buffer: &mut [Complex<f32>],
+        other: &[Complex<f32>],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_f32_neon::<false>(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_phat(
+        &self,
+        buffer: &mut [Complex<f32>],
+        other: &[Complex<f32>],
+        gains: &[f32],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_phat_f32_neon(buffer, other, gains, len);
+        }
+    }
+
+    fn mul_spectrum_roth(&self, buffer: &mut [Complex<f32>], other: &[Complex<f32>], len: usize) {
+        unsafe {
+            mul_spectrum_roth_f32_neon(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_gains(
+        &self,
+        buffer: &mut [Complex<f32>],
+        other: &[Complex<f32>],
+        gains: &[f32],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_gains_f32_neon(buffer, other, gains, len);
+        }
+    }
+}
 
-            static CONJ_FACTORS: [f32; 4] = [0.0, -0.0, 0.0, -0.0];
-            let conj_factors = vreinterpretq_u32_f32(vld1q_f32(CONJ_FACTORS.as_ptr()));
+/// Computes `buffer * conj(other) / len`, or `buffer * other / len` without `CONJ`.
+pub(crate) unsafe fn mul_spectrum_f32_neon<const CONJ: bool>(
+    buffer: &mut [Complex<f32>],
+    other: &[Complex<f32>],
+    len: usize,
+) {
+    unsafe {
+        let normalization_factor = (1f64 / len as f64) as f32;
+        let v_norm_factor = vdupq_n_f32(normalization_factor);
 
-            let source = &mut buffer[..];
-            let other = &other;
+        static CONJ_FACTORS: [f32; 4] = [0.0, -0.0, 0.0, -0.0];
+        let conj_factors = vreinterpretq_u32_f32(vld1q_f32(CONJ_FACTORS.as_ptr()));
 
-            for (dst, kernel) in source.chunks_exact_mut(8).zip(other.chunks_exact(8)) {
-                let vd0 = vld1q_f32(dst.as_ptr().cast());
-                let vd1 = vld1q_f32(dst.get_unchecked(2..).as_ptr().cast());
-                let vd2 = vld1q_f32(dst.get_unchecked(4..).as_ptr().cast());
-                let vd3 = vld1q_f32(dst.get_unchecked(6..).as_ptr().cast());
+        let source = &mut buffer[..];
+        let other = &other;
 
-                let mut vk0 = vld1q_f32(kernel.as_ptr().cast());
-                let mut vk1 = vld1q_f32(kernel.get_unchecked(2..).as_ptr().cast());
-                let mut vk2 = vld1q_f32(kernel.get_unchecked(4..).as_ptr().cast());
-                let mut vk3 = vld1q_f32(kernel.get_unchecked(6..).as_ptr().cast());
+        for (dst, kernel) in source.chunks_exact_mut(8).zip(other.chunks_exact(8)) {
+            let vd0 = vld1q_f32(dst.as_ptr().cast());
+            let vd1 = vld1q_f32(dst.get_unchecked(2..).as_ptr().cast());
+            let vd2 = vld1q_f32(dst.get_unchecked(4..).as_ptr().cast());
+            let vd3 = vld1q_f32(dst.get_unchecked(6..).as_ptr().cast());
 
+            let mut vk0 = vld1q_f32(kernel.as_ptr().cast());
+            let mut vk1 = vld1q_f32(kernel.get_unchecked(2..).as_ptr().cast());
+            let mut vk2 = vld1q_f32(kernel.get_unchecked(4..).as_ptr().cast());
+            let mut vk3 = vld1q_f32(kernel.get_unchecked(6..).as_ptr().cast());
+
+            if CONJ {
                 vk0 = vreinterpretq_f32_u32(veorq_u32(vreinterpretq_u32_f32(vk0), conj_factors));
                 vk1 = vreinterpretq_f32_u32(veorq_u32(vreinterpretq_u32_f32(vk1), conj_factors));
                 vk2 = vreinterpretq_f32_u32(veorq_u32(vreinterpretq_u32_f32(vk2), conj_factors));
                 vk3 = vreinterpretq_f32_u32(veorq_u32(vreinterpretq_u32_f32(vk3), conj_factors));
+            }
 
-                let p0 = vmulq_f32(mul_complex_f32(vd0, vk0), v_norm_factor);
-                let p1 = vmulq_f32(mul_complex_f32(vd1, vk1), v_norm_factor);
-                let p2 = vmulq_f32(mul_complex_f32(vd2, vk2), v_norm_factor);
-                let p3 = vmulq_f32(mul_complex_f32(vd3, vk3), v_norm_factor);
+            let p0 = vmulq_f32(mul_complex_f32(vd0, vk0), v_norm_factor);
+            let p1 = vmulq_f32(mul_complex_f32(vd1, vk1), v_norm_factor);
+            let p2 = vmulq_f32(mul_complex_f32(vd2, vk2), v_norm_factor);
+            let p3 = vmulq_f32(mul_complex_f32(vd3, vk3), v_norm_factor);
 
-                vst1q_f32(dst.as_mut_ptr().cast(), p0);
-                vst1q_f32(dst.get_unchecked_mut(2..).as_mut_ptr().cast(), p1);
-                vst1q_f32(dst.get_unchecked_mut(4..).as_mut_ptr().cast(), p2);
-                vst1q_f32(dst.get_unchecked_mut(6..).as_mut_ptr().cast(), p3);
-            }
+            vst1q_f32(dst.as_mut_ptr().cast(), p0);
+            vst1q_f32(dst.get_unchecked_mut(2..).as_mut_ptr().cast(), p1);
+            vst1q_f32(dst.get_unchecked_mut(4..).as_mut_ptr().cast(), p2);
+            vst1q_f32(dst.get_unchecked_mut(6..).as_mut_ptr().cast(), p3);
+        }
 
-            let dst_rem = source.chunks_exact_mut(8).into_remainder();
-            let src_rem = other.chunks_exact(8).remainder();
+        let dst_rem = source.chunks_exact_mut(8).into_remainder();
+        let src_rem = other.chunks_exact(8).remainder();
 
-            for (dst, kernel) in dst_rem.chunks_exact_mut(2).zip(src_rem.chunks_exact(2)) {
-                let v0 = vld1q_f32(dst.as_ptr().cast());
-                let mut v1 = vld1q_f32(kernel.as_ptr().cast());
+        for (dst, kernel) in dst_rem.chunks_exact_mut(2).zip(src_rem.chunks_exact(2)) {
+            let v0 = vld1q_f32(dst.as_ptr().cast());
+            let mut v1 = vld1q_f32(kernel.as_ptr().cast());
 
+            if CONJ {
                 v1 = vreinterpretq_f32_u32(veorq_u32(vreinterpretq_u32_f32(v1), conj_factors));
-
-                let p1 = vmulq_f32(mul_complex_f32(v0, v1), v_norm_factor);
-                vst1q_f32(dst.as_mut_ptr().cast(), p1);
             }
 
-            let dst_rem = dst_rem.chunks_exact_mut(2).into_remainder();
-            let src_rem = src_rem.chunks_exact(2).remainder();
+            let p1 = vmulq_f32(mul_complex_f32(v0, v1), v_norm_factor);
+            vst1q_f32(dst.as_mut_ptr().cast(), p1);
+        }
 
-            for (dst, kernel) in dst_rem.iter_mut().zip(src_rem.iter()) {
-                let v0 = vld1_f32(dst as *const Complex<f32> as *const f32);
-                let mut v1 = vld1_f32(kernel as *const Complex<f32> as *const f32);
+        let dst_rem = dst_rem.chunks_exact_mut(2).into_remainder();
+        let src_rem = src_rem.chunks_exact(2).remainder();
 
+        for (dst, kernel) in dst_rem.iter_mut().zip(src_rem.iter()) {
+            let v0 = vld1_f32(dst as *const Complex<f32> as *const f32);
+            let mut v1 = vld1_f32(kernel as *const Complex<f32> as *const f32);
+
+            if CONJ {
                 v1 = vreinterpret_f32_u32(veor_u32(
                     vreinterpret_u32_f32(v1),
                     vget_low_u32(conj_factors),
                 ));
+            }
 
-                let p1 = vmul_f32(mulh_complex_f32(v0, v1), vget_low_f32(v_norm_factor));
-                vst1_f32(dst as *mut Complex<f32> as *mut f32, p1);
+            let p1 = vmul_f32(mulh_complex_f32(v0, v1), vget_low_f32(v_norm_factor));
+            vst1_f32(dst as *mut Complex<f32> as *mut f32, p1);
+        }
+    }
+}
+
+// Sums squared re/im pairs, so both lanes of every complex hold its squared magnitude
+#[inline(always)]
+unsafe fn power_f32(a: float32x4_t) -> float32x4_t {
+    unsafe {
+        let squared = vmulq_f32(a, a);
+        vaddq_f32(squared, vrev64q_f32(squared))
+    }
+}
+
+pub(crate) unsafe fn mul_spectrum_phat_f32_neon(
+    value1: &mut [Complex<f32>],
+    other: &[Complex<f32>],
+    gains: &[f32],
+    len: usize,
+) {
+    unsafe {
+        let v_norm_factor = vdupq_n_f32((1f64 / len as f64) as f32);
+        let v_min_power = vdupq_n_f32(f32::MIN_POSITIVE);
+
+        static CONJ_FACTORS: [f32; 4] = [0.0, -0.0, 0.0, -0.0];
+        let conj_factors = vreinterpretq_u32_f32(vld1q_f32(CONJ_FACTORS.as_ptr()));
+
+        for (i, (dst, kernel)) in value1
+            .chunks_exact_mut(2)
+            .zip(other.chunks_exact(2))
+            .enumerate()
+        {
+            let a0 = vld1q_f32(dst.as_ptr().cast());
+            let mut b0 = vld1q_f32(kernel.as_ptr().cast());
+            b0 = vreinterpretq_f32_u32(veorq_u32(vreinterpretq_u32_f32(b0), conj_factors));
+
+            let product = mul_complex_f32(a0, b0);
+            let power = power_f32(product);
+            let mut factor = vdivq_f32(v_norm_factor, vsqrtq_f32(power));
+            if !gains.is_empty() {
+                let g = vld1_f32(gains.get_unchecked(i * 2..).as_ptr());
+                factor = vmulq_f32(
+                    factor,
+                    vcombine_f32(vdup_lane_f32::<0>(g), vdup_lane_f32::<1>(g)),
+                );
             }
+            let mask = vcgtq_f32(power, v_min_power);
+            let d0 = vreinterpretq_f32_u32(vandq_u32(
+                vreinterpretq_u32_f32(vmulq_f32(product, factor)),
+                mask,
+            ));
+
+            vst1q_f32(dst.as_mut_ptr().cast(), d0);
         }
+
+        let processed = value1.len().min(other.len()) / 2 * 2;
+        mul_spectrum_phat_impl(
+            value1.chunks_exact_mut(2).into_remainder(),
+            other.chunks_exact(2).remainder(),
+            gains.get(processed..).unwrap_or_default(),
+            len,
+        );
+    }
+}
+
+pub(crate) unsafe fn mul_spectrum_roth_f32_neon(
+    value1: &mut [Complex<f32>],
+    other: &[Complex<f32>],
+    len: usize,
+) {
+    unsafe {
+        let v_norm_factor = vdupq_n_f32((1f64 / len as f64) as f32);
+        let v_min_power = vdupq_n_f32(f32::MIN_POSITIVE);
+
+        static CONJ_FACTORS: [f32; 4] = [0.0, -0.0, 0.0, -0.0];
+        let conj_factors = vreinterpretq_u32_f32(vld1q_f32(CONJ_FACTORS.as_ptr()));
+
+        for (dst, kernel) in value1.chunks_exact_mut(2).zip(other.chunks_exact(2)) {
+            let a0 = vld1q_f32(dst.as_ptr().cast());
+            let b0 = vld1q_f32(kernel.as_ptr().cast());
+
+            let product = mul_complex_f32(
+                a0,
+                vreinterpretq_f32_u32(veorq_u32(vreinterpretq_u32_f32(b0), conj_factors)),
+            );
+            let power = power_f32(b0);
+            let factor = vdivq_f32(v_norm_factor, power);
+            let mask = vcgtq_f32(power, v_min_power);
+            let d0 = vreinterpretq_f32_u32(vandq_u32(
+                vreinterpretq_u32_f32(vmulq_f32(product, factor)),
+                mask,
+            ));
+
+            vst1q_f32(dst.as_mut_ptr().cast(), d0);
+        }
+
+        mul_spectrum_roth_impl(
+            value1.chunks_exact_mut(2).into_remainder(),
+            other.chunks_exact(2).remainder(),
+            len,
+        );
+    }
+}
+
+pub(crate) unsafe fn mul_spectrum_gains_f32_neon(
+    value1: &mut [Complex<f32>],
+    other: &[Complex<f32>],
+    gains: &[f32],
+    len: usize,
+) {
+    unsafe {
+        let v_norm_factor = vdupq_n_f32((1f64 / len as f64) as f32);
+
+        static CONJ_FACTORS: [f32; 4] = [0.0, -0.0, 0.0, -0.0];
+        let conj_factors = vreinterpretq_u32_f32(vld1q_f32(CONJ_FACTORS.as_ptr()));
+
+        for ((dst, kernel), gain) in value1
+            .chunks_exact_mut(2)
+            .zip(other.chunks_exact(2))
+            .zip(gains.chunks_exact(2))
+        {
+            let a0 = vld1q_f32(dst.as_ptr().cast());
+            let mut b0 = vld1q_f32(kernel.as_ptr().cast());
+            b0 = vreinterpretq_f32_u32(veorq_u32(vreinterpretq_u32_f32(b0), conj_factors));
+
+            let g = vld1_f32(gain.as_ptr());
+            let factor = vmulq_f32(
+                v_norm_factor,
+                vcombine_f32(vdup_lane_f32::<0>(g), vdup_lane_f32::<1>(g)),
+            );
+            let d0 = vmulq_f32(mul_complex_f32(a0, b0), factor);
+
+            vst1q_f32(dst.as_mut_ptr().cast(), d0);
+        }
+
+        let processed = value1.len().min(other.len()).min(gains.len()) / 2 * 2;
+        mul_spectrum_gains_impl(
+            &mut value1[processed..],
+            other.get(processed..).unwrap_or_default(),
+            gains.get(processed..).unwrap_or_default(),
+            len,
+        );
     }
 }
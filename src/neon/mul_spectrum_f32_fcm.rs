@@ -26,6 +26,10 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
+use crate::neon::mul_spectrum_f32::{
+    mul_spectrum_f32_neon, mul_spectrum_gains_f32_neon, mul_spectrum_phat_f32_neon,
+    mul_spectrum_roth_f32_neon,
+};
 use crate::spectrum::SpectrumMultiplier;
 use num_complex::Complex;
 use std::arch::aarch64::{
@@ -40,6 +44,47 @@ impl SpectrumMultiplier<f32> for SpectrumMulSingleFcma {
     fn mul_spectrum(&self, buffer: &mut [Complex<f32>], other: &[Complex<f32>], len: usize) {
         unsafe { self.worker_impl(buffer, other, len) }
     }
+
+    fn mul_spectrum_convolve(
+        &self,
+        buffer: &mut [Complex<f32>],
+        other: &[Complex<f32>],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_f32_neon::<false>(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_phat(
+        &self,
+        buffer: &mut [Complex<f32>],
+        other: &[Complex<f32>],
+        gains: &[f32],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_phat_f32_neon(buffer, other, gains, len);
+        }
+    }
+
+    fn mul_spectrum_roth(&self, buffer: &mut [Complex<f32>], other: &[Complex<f32>], len: usize) {
+        unsafe {
+            mul_spectrum_roth_f32_neon(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_gains(
+        &self,
+        buffer: &mut [Complex<f32>],
+        other: &[Complex<f32>],
+        gains: &[f32],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_gains_f32_neon(buffer, other, gains, len);
+        }
+    }
 }
 
 impl SpectrumMulSingleFcma {
@@ -30,6 +30,9 @@
 use crate::neon::complex::mul_complex_f64;
 use crate::spectrum::SpectrumMultiplier;
 use num_complex::Complex;
+use std::arch::aarch64::{
+    float64x2_t, vaddq_f64, vandq_u64, vcgtq_f64, vdivq_f64, vextq_f64, vsqrtq_f64,
+};
 use std::arch::aarch64::{
     vdupq_n_f64, veorq_u64, vld1q_f64, vmulq_f64, vreinterpretq_f64_u64, vreinterpretq_u64_f64,
     vst1q_f64,
@@ -41,54 +44,212 @@ pub(crate) struct SpectrumMulDoubleNeon {}
 impl SpectrumMultiplier<f64> for SpectrumMulDoubleNeon {
     fn mul_spectrum(&self, buffer: &mut [Complex<f64>], other: &[Complex<f64>], len: usize) {
         unsafe {
-            let normalization_factor = 1f64 / len as f64;
-            let v_norm_factor = vdupq_n_f64(normalization_factor);
+            mul_spectrum_f64_neon::<true>(buffer, other, len);
+        }
+    }
 
-            static CONJ_FACTORS: [f64; 2] = [0.0, -0.0];
-            let conj_factors = vreinterpretq_u64_f64(vld1q_f64(CONJ_FACTORS.as_ptr()));
+    fn mul_spectrum_convolve(
+        &self,
+        buffer: &mut [Complex<f64>],
+        other: &[Complex<f64>],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_f64_neon::<false>(buffer, other, len);
+        }
+    }
 
-            let source = &mut buffer[..];
-            let other = &other;
+    fn mul_spectrum_phat(
+        &self,
+        buffer: &mut [Complex<f64>],
+        other: &[Complex<f64>],
+        gains: &[f64],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_phat_f64_neon(buffer, other, gains, len);
+        }
+    }
 
-            for (dst, kernel) in source.chunks_exact_mut(4).zip(other.chunks_exact(4)) {
-                let vd0 = vld1q_f64(dst.as_ptr().cast());
-                let vd1 = vld1q_f64(dst.get_unchecked(1..).as_ptr().cast());
-                let vd2 = vld1q_f64(dst.get_unchecked(2..).as_ptr().cast());
-                let vd3 = vld1q_f64(dst.get_unchecked(3..).as_ptr().cast());
+    fn mul_spectrum_roth(&self, buffer: &mut [Complex<f64>], other: &[Complex<f64>], len: usize) {
+        unsafe {
+            mul_spectrum_roth_f64_neon(buffer, other, len);
+        }
+    }
 
-                let mut vk0 = vld1q_f64(kernel.as_ptr().cast());
-                let mut vk1 = vld1q_f64(kernel.get_unchecked(1..).as_ptr().cast());
-                let mut vk2 = vld1q_f64(kernel.get_unchecked(2..).as_ptr().cast());
-                let mut vk3 = vld1q_f64(kernel.get_unchecked(3..).as_ptr().cast());
+    fn mul_spectrum_gains(
+        &self,
+        buffer: &mut [Complex<f64>],
+        other: &[Complex<f64>],
+        gains: &[f64],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_gains_f64_neon(buffer, other, gains, len);
+        }
+    }
+}
+
+/// Computes `buffer * conj(other) / len`, or `buffer * other / len` without `CONJ`.
+pub(crate) unsafe fn mul_spectrum_f64_neon<const CONJ: bool>(
+    buffer: &mut [Complex<f64>],
+    other: &[Complex<f64>],
+    len: usize,
+) {
+    unsafe {
+        let normalization_factor = 1f64 / len as f64;
+        let v_norm_factor = vdupq_n_f64(normalization_factor);
+
+        static CONJ_FACTORS: [f64; 2] = [0.0, -0.0];
+        let conj_factors = vreinterpretq_u64_f64(vld1q_f64(CONJ_FACTORS.as_ptr()));
+
+        let source = &mut buffer[..];
+        let other = &other;
+
+        for (dst, kernel) in source.chunks_exact_mut(4).zip(other.chunks_exact(4)) {
+            let vd0 = vld1q_f64(dst.as_ptr().cast());
+            let vd1 = vld1q_f64(dst.get_unchecked(1..).as_ptr().cast());
+            let vd2 = vld1q_f64(dst.get_unchecked(2..).as_ptr().cast());
+            let vd3 = vld1q_f64(dst.get_unchecked(3..).as_ptr().cast());
 
+            let mut vk0 = vld1q_f64(kernel.as_ptr().cast());
+            let mut vk1 = vld1q_f64(kernel.get_unchecked(1..).as_ptr().cast());
+            let mut vk2 = vld1q_f64(kernel.get_unchecked(2..).as_ptr().cast());
+            let mut vk3 = vld1q_f64(kernel.get_unchecked(3..).as_ptr().cast());
+
+            if CONJ {
                 vk0 = vreinterpretq_f64_u64(veorq_u64(vreinterpretq_u64_f64(vk0), conj_factors));
                 vk1 = vreinterpretq_f64_u64(veorq_u64(vreinterpretq_u64_f64(vk1), conj_factors));
                 vk2 = vreinterpretq_f64_u64(veorq_u64(vreinterpretq_u64_f64(vk2), conj_factors));
                 vk3 = vreinterpretq_f64_u64(veorq_u64(vreinterpretq_u64_f64(vk3), conj_factors));
+            }
 
-                let p0 = vmulq_f64(mul_complex_f64(vd0, vk0), v_norm_factor);
-                let p1 = vmulq_f64(mul_complex_f64(vd1, vk1), v_norm_factor);
-                let p2 = vmulq_f64(mul_complex_f64(vd2, vk2), v_norm_factor);
-                let p3 = vmulq_f64(mul_complex_f64(vd3, vk3), v_norm_factor);
+            let p0 = vmulq_f64(mul_complex_f64(vd0, vk0), v_norm_factor);
+            let p1 = vmulq_f64(mul_complex_f64(vd1, vk1), v_norm_factor);
+            let p2 = vmulq_f64(mul_complex_f64(vd2, vk2), v_norm_factor);
+            let p3 = vmulq_f64(mul_complex_f64(vd3, vk3), v_norm_factor);
 
-                vst1q_f64(dst.as_mut_ptr().cast(), p0);
-                vst1q_f64(dst.get_unchecked_mut(1..).as_mut_ptr().cast(), p1);
-                vst1q_f64(dst.get_unchecked_mut(2..).as_mut_ptr().cast(), p2);
-                vst1q_f64(dst.get_unchecked_mut(3..).as_mut_ptr().cast(), p3);
-            }
+            vst1q_f64(dst.as_mut_ptr().cast(), p0);
+            vst1q_f64(dst.get_unchecked_mut(1..).as_mut_ptr().cast(), p1);
+            vst1q_f64(dst.get_unchecked_mut(2..).as_mut_ptr().cast(), p2);
+            vst1q_f64(dst.get_unchecked_mut(3..).as_mut_ptr().cast(), p3);
+        }
 
-            let dst_rem = source.chunks_exact_mut(4).into_remainder();
-            let src_rem = other.chunks_exact(4).remainder();
+        let dst_rem = source.chunks_exact_mut(4).into_remainder();
+        let src_rem = other.chunks_exact(4).remainder();
 
-            for (dst, kernel) in dst_rem.iter_mut().zip(src_rem.iter()) {
-                let v0 = vld1q_f64(dst as *const Complex<f64> as *const f64);
-                let mut v1 = vld1q_f64(kernel as *const Complex<f64> as *const f64);
+        for (dst, kernel) in dst_rem.iter_mut().zip(src_rem.iter()) {
+            let v0 = vld1q_f64(dst as *const Complex<f64> as *const f64);
+            let mut v1 = vld1q_f64(kernel as *const Complex<f64> as *const f64);
 
+            if CONJ {
                 v1 = vreinterpretq_f64_u64(veorq_u64(vreinterpretq_u64_f64(v1), conj_factors));
+            }
 
-                let p1 = vmulq_f64(mul_complex_f64(v0, v1), v_norm_factor);
-                vst1q_f64(dst as *mut Complex<f64> as *mut f64, p1);
+            let p1 = vmulq_f64(mul_complex_f64(v0, v1), v_norm_factor);
+            vst1q_f64(dst as *mut Complex<f64> as *mut f64, p1);
+        }
+    }
+}
+
+// Sums squared re/im parts, so both lanes hold the squared magnitude
+#[inline(always)]
+unsafe fn power_f64(a: float64x2_t) -> float64x2_t {
+    unsafe {
+        let squared = vmulq_f64(a, a);
+        vaddq_f64(squared, vextq_f64::<1>(squared, squared))
+    }
+}
+
+pub(crate) unsafe fn mul_spectrum_phat_f64_neon(
+    value1: &mut [Complex<f64>],
+    other: &[Complex<f64>],
+    gains: &[f64],
+    len: usize,
+) {
+    unsafe {
+        let v_norm_factor = vdupq_n_f64(1f64 / len as f64);
+        let v_min_power = vdupq_n_f64(f64::MIN_POSITIVE);
+
+        static CONJ_FACTORS: [f64; 2] = [0.0, -0.0];
+        let conj_factors = vreinterpretq_u64_f64(vld1q_f64(CONJ_FACTORS.as_ptr()));
+
+        for (i, (dst, kernel)) in value1.iter_mut().zip(other.iter()).enumerate() {
+            let a0 = vld1q_f64(dst as *const Complex<f64> as *const f64);
+            let mut b0 = vld1q_f64(kernel as *const Complex<f64> as *const f64);
+            b0 = vreinterpretq_f64_u64(veorq_u64(vreinterpretq_u64_f64(b0), conj_factors));
+
+            let product = mul_complex_f64(a0, b0);
+            let power = power_f64(product);
+            let mut factor = vdivq_f64(v_norm_factor, vsqrtq_f64(power));
+            if !gains.is_empty() {
+                factor = vmulq_f64(factor, vdupq_n_f64(*gains.get_unchecked(i)));
             }
+            let mask = vcgtq_f64(power, v_min_power);
+            let d0 = vreinterpretq_f64_u64(vandq_u64(
+                vreinterpretq_u64_f64(vmulq_f64(product, factor)),
+                mask,
+            ));
+
+            vst1q_f64(dst as *mut Complex<f64> as *mut f64, d0);
+        }
+    }
+}
+
+pub(crate) unsafe fn mul_spectrum_roth_f64_neon(
+    value1: &mut [Complex<f64>],
+    other: &[Complex<f64>],
+    len: usize,
+) {
+    unsafe {
+        let v_norm_factor = vdupq_n_f64(1f64 / len as f64);
+        let v_min_power = vdupq_n_f64(f64::MIN_POSITIVE);
+
+        static CONJ_FACTORS: [f64; 2] = [0.0, -0.0];
+        let conj_factors = vreinterpretq_u64_f64(vld1q_f64(CONJ_FACTORS.as_ptr()));
+
+        for (dst, kernel) in value1.iter_mut().zip(other.iter()) {
+            let a0 = vld1q_f64(dst as *const Complex<f64> as *const f64);
+            let b0 = vld1q_f64(kernel as *const Complex<f64> as *const f64);
+
+            let product = mul_complex_f64(
+                a0,
+                vreinterpretq_f64_u64(veorq_u64(vreinterpretq_u64_f64(b0), conj_factors)),
+            );
+            let power = power_f64(b0);
+            let factor = vdivq_f64(v_norm_factor, power);
+            let mask = vcgtq_f64(power, v_min_power);
+            let d0 = vreinterpretq_f64_u64(vandq_u64(
+                vreinterpretq_u64_f64(vmulq_f64(product, factor)),
+                mask,
+            ));
+
+            vst1q_f64(dst as *mut Complex<f64> as *mut f64, d0);
+        }
+    }
+}
+
+pub(crate) unsafe fn mul_spectrum_gains_f64_neon(
+    value1: &mut [Complex<f64>],
+    other: &[Complex<f64>],
+    gains: &[f64],
+    len: usize,
+) {
+    unsafe {
+        let normalization_factor = 1f64 / len as f64;
+
+        static CONJ_FACTORS: [f64; 2] = [0.0, -0.0];
+        let conj_factors = vreinterpretq_u64_f64(vld1q_f64(CONJ_FACTORS.as_ptr()));
+
+        for ((dst, kernel), &gain) in value1.iter_mut().zip(other.iter()).zip(gains.iter()) {
+            let a0 = vld1q_f64(dst as *const Complex<f64> as *const f64);
+            let mut b0 = vld1q_f64(kernel as *const Complex<f64> as *const f64);
+            b0 = vreinterpretq_f64_u64(veorq_u64(vreinterpretq_u64_f64(b0), conj_factors));
+
+            let factor = vdupq_n_f64(gain * normalization_factor);
+            let d0 = vmulq_f64(mul_complex_f64(a0, b0), factor);
+
+            vst1q_f64(dst as *mut Complex<f64> as *mut f64, d0);
         }
     }
 }
@@ -27,6 +27,10 @@
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
 
+use crate::neon::mul_spectrum_f64::{
+    mul_spectrum_f64_neon, mul_spectrum_gains_f64_neon, mul_spectrum_phat_f64_neon,
+    mul_spectrum_roth_f64_neon,
+};
 use crate::spectrum::SpectrumMultiplier;
 use num_complex::Complex;
 use std::arch::aarch64::{
@@ -40,6 +44,47 @@ impl SpectrumMultiplier<f64> for SpectrumMulDoubleFcma {
     fn mul_spectrum(&self, buffer: &mut [Complex<f64>], other: &[Complex<f64>], len: usize) {
         unsafe { self.worker_impl(buffer, other, len) }
     }
+
+    fn mul_spectrum_convolve(
+        &self,
+        buffer: &mut [Complex<f64>],
+        other: &[Complex<f64>],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_f64_neon::<false>(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_phat(
+        &self,
+        buffer: &mut [Complex<f64>],
+        other: &[Complex<f64>],
+        gains: &[f64],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_phat_f64_neon(buffer, other, gains, len);
+        }
+    }
+
+    fn mul_spectrum_roth(&self, buffer: &mut [Complex<f64>], other: &[Complex<f64>], len: usize) {
+        unsafe {
+            mul_spectrum_roth_f64_neon(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_gains(
+        &self,
+        buffer: &mut [Complex<f64>],
+        other: &[Complex<f64>],
+        gains: &[f64],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_gains_f64_neon(buffer, other, gains, len);
+        }
+    }
 }
 
 impl SpectrumMulDoubleFcma {
@@ -0,0 +1,193 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::CrossCorrelationMode;
+use num_complex::Complex;
+use num_traits::Float;
+
+/// Scaling applied to each lag of the cross-correlation result.
+///
+/// Mirrors the `scaleopt` argument of MATLAB `xcorr`.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
+pub enum CrossCorrelationNormalization {
+    /// Raw, unscaled correlation (default)
+    #[default]
+    None,
+    /// Every lag is divided by the length of the longest input
+    Biased,
+    /// Every lag is divided by the number of overlapping samples at that lag
+    Unbiased,
+    /// Every lag is divided by `sqrt(sum |buffer|^2 * sum |other|^2)`, so the
+    /// result is bounded to `[-1, 1]`
+    Coeff,
+}
+
+/// Sample types whose correlation can be rescaled by a real factor.
+///
+/// Reachable only as a supertrait of the sealed [`CorrelateSample`](crate::CorrelateSample),
+/// it cannot be implemented for other types.
+pub trait NormalizeSample: Copy {
+    /// Squared magnitude of the sample.
+    fn energy(self) -> f64;
+    /// Multiplies the sample by the real `factor`, both parts alike for complex samples.
+    fn scale(self, factor: f64) -> Self;
+    /// Clamps the sample to a magnitude of at most 1, keeping its sign or phase.
+    ///
+    /// Applied to [`CrossCorrelationNormalization::Coeff`] results, whose magnitude can
+    /// exceed 1 by the rounding of the FFT round trip.
+    fn clamp_unit(self) -> Self;
+}
+
+macro_rules! impl_normalize_sample {
+    ($t:ty) => {
+        impl NormalizeSample for $t {
+            #[inline]
+            fn energy(self) -> f64 {
+                (self as f64) * (self as f64)
+            }
+            #[inline]
+            fn scale(self, factor: f64) -> $t {
+                self * factor as $t
+            }
+            #[inline]
+            fn clamp_unit(self) -> $t {
+                self.clamp(-1., 1.)
+            }
+        }
+    };
+}
+
+impl_normalize_sample!(f32);
+impl_normalize_sample!(f64);
+
+impl<T: NormalizeSample + Float> NormalizeSample for Complex<T> {
+    #[inline]
+    fn energy(self) -> f64 {
+        self.re.energy() + self.im.energy()
+    }
+    #[inline]
+    fn scale(self, factor: f64) -> Complex<T> {
+        Complex::new(self.re.scale(factor), self.im.scale(factor))
+    }
+    #[inline]
+    fn clamp_unit(self) -> Complex<T> {
+        let magnitude = self.norm();
+        if magnitude > T::one() {
+            self / magnitude
+        } else {
+            self
+        }
+    }
+}
+
+/// Sum of squared magnitudes of `signal`, accumulated in `f64`.
+pub(crate) fn signal_energy<V: NormalizeSample>(signal: &[V]) -> f64 {
+    signal.iter().map(|x| x.energy()).sum()
+}
+
+/// Per-lag scale factors resolved for one pair of input lengths.
+pub(crate) enum LagScale {
+    Identity,
+    Constant(f64),
+    Coeff(f64),
+    Overlap {
+        buffer_len: usize,
+        other_len: usize,
+        start: usize,
+    },
+}
+
+impl CrossCorrelationNormalization {
+    /// Resolves the scaling for a correlation of `buffer_len` by `other_len` samples.
+    ///
+    /// `energy` returns the product of both signal energies and is only evaluated
+    /// for [`CrossCorrelationNormalization::Coeff`].
+    pub(crate) fn lag_scale(
+        self,
+        mode: CrossCorrelationMode,
+        buffer_len: usize,
+        other_len: usize,
+        energy: impl FnOnce() -> f64,
+    ) -> LagScale {
+        match self {
+            CrossCorrelationNormalization::None => LagScale::Identity,
+            CrossCorrelationNormalization::Biased => {
+                LagScale::Constant(1. / buffer_len.max(other_len) as f64)
+            }
+            // Every circular lag overlaps all samples of the shorter input
+            CrossCorrelationNormalization::Unbiased if mode == CrossCorrelationMode::Circular => {
+                LagScale::Constant(1. / buffer_len.min(other_len) as f64)
+            }
+            CrossCorrelationNormalization::Unbiased => LagScale::Overlap {
+                buffer_len,
+                other_len,
+                start: mode.start_index(buffer_len, other_len),
+            },
+            CrossCorrelationNormalization::Coeff => {
+                let energy = energy();
+                if energy > 0. {
+                    LagScale::Coeff(1. / energy.sqrt())
+                } else {
+                    // Every lag of a correlation with a silent signal is already zero
+                    LagScale::Identity
+                }
+            }
+        }
+    }
+}
+
+impl LagScale {
+    pub(crate) fn apply<V: NormalizeSample>(&self, output: &mut [V]) {
+        match *self {
+            LagScale::Identity => {}
+            LagScale::Constant(factor) => {
+                for dst in output.iter_mut() {
+                    *dst = dst.scale(factor);
+                }
+            }
+            LagScale::Coeff(factor) => {
+                for dst in output.iter_mut() {
+                    *dst = dst.scale(factor).clamp_unit();
+                }
+            }
+            LagScale::Overlap {
+                buffer_len,
+                other_len,
+                start,
+            } => {
+                let full_length = buffer_len + other_len - 1;
+                let shortest = buffer_len.min(other_len);
+                for (i, dst) in output.iter_mut().enumerate() {
+                    let j = start + i;
+                    let overlap = (j + 1).min(full_length - j).min(shortest);
+                    *dst = dst.scale(1. / overlap as f64);
+                }
+            }
+        }
+    }
+}
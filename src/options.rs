@@ -0,0 +1,196 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::{
+    CorrelationMethod, CrossCorrelateError, CrossCorrelationMode, CrossCorrelationNormalization,
+    GccWeighting, fft_next_good_size,
+};
+
+/// Rounding applied to the smallest FFT size a correlation needs.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
+pub enum FftSizePolicy {
+    /// Round up to the next 7-smooth length, see [`fft_next_good_size`] (default)
+    #[default]
+    Smooth,
+    /// Round up to the next power of two
+    PowerOfTwo,
+    /// Use the smallest size that avoids circular aliasing, whatever its factors
+    Exact,
+}
+
+impl FftSizePolicy {
+    /// Returns the FFT size for a correlation of `buffer_len` by `other_len` samples.
+    ///
+    /// [`CrossCorrelationMode::Circular`] always transforms exactly one period.
+    #[inline]
+    pub fn fft_size(
+        self,
+        mode: CrossCorrelationMode,
+        buffer_len: usize,
+        other_len: usize,
+    ) -> usize {
+        let size = mode.min_fft_size(buffer_len, other_len);
+        if mode == CrossCorrelationMode::Circular {
+            return size;
+        }
+        match self {
+            FftSizePolicy::Smooth => fft_next_good_size(size),
+            FftSizePolicy::PowerOfTwo => size.next_power_of_two(),
+            FftSizePolicy::Exact => size,
+        }
+    }
+}
+
+/// Options consumed by the `_with_options` and `_with_cache` factories of
+/// [`Correlate`](crate::Correlate) and [`Convolve`](crate::Convolve).
+///
+/// Every option starts at its default, which matches [`Correlate::create`](crate::Correlate::create)
+/// in [`CrossCorrelationMode::Full`]. Combinations are validated when the correlator is created,
+/// options a correlator cannot honor are rejected with [`CrossCorrelateError::UnsupportedOption`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CorrelateOptions {
+    pub(crate) mode: CrossCorrelationMode,
+    pub(crate) normalization: CrossCorrelationNormalization,
+    pub(crate) method: CorrelationMethod,
+    pub(crate) weighting: GccWeighting,
+    pub(crate) fft_size_policy: FftSizePolicy,
+}
+
+impl CorrelateOptions {
+    /// Creates options with every knob at its default.
+    pub fn new() -> CorrelateOptions {
+        CorrelateOptions::default()
+    }
+
+    /// Sets the [`CrossCorrelationMode`] that determines the shape of the output.
+    pub fn mode(mut self, mode: CrossCorrelationMode) -> CorrelateOptions {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the [`CrossCorrelationNormalization`] applied to every lag.
+    pub fn normalization(
+        mut self,
+        normalization: CrossCorrelationNormalization,
+    ) -> CorrelateOptions {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Sets the [`CorrelationMethod`] used to compute the correlation.
+    pub fn method(mut self, method: CorrelationMethod) -> CorrelateOptions {
+        self.method = method;
+        self
+    }
+
+    /// Sets the [`GccWeighting`] applied to the cross spectrum.
+    ///
+    /// Weighting needs the spectrum, so it cannot be combined with
+    /// [`CorrelationMethod::Direct`] and makes [`CorrelationMethod::Auto`] pick the FFT.
+    pub fn weighting(mut self, weighting: GccWeighting) -> CorrelateOptions {
+        self.weighting = weighting;
+        self
+    }
+
+    /// Sets the [`FftSizePolicy`] used to round the transform length.
+    pub fn fft_size_policy(mut self, fft_size_policy: FftSizePolicy) -> CorrelateOptions {
+        self.fft_size_policy = fft_size_policy;
+        self
+    }
+
+    /// Checks that the mode selects at least one lag for the input lengths.
+    pub(crate) fn check_lengths(
+        &self,
+        buffer_length: usize,
+        other_length: usize,
+    ) -> Result<(), CrossCorrelateError> {
+        if buffer_length == 0 || other_length == 0 {
+            return Err(CrossCorrelateError::BuffersMustNotHaveZeroSize);
+        }
+        if self.mode.get_size(buffer_length, other_length) == 0 {
+            return Err(CrossCorrelateError::ModeSelectsNoLags(
+                buffer_length,
+                other_length,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks the options of a correlator that always runs through the FFT.
+    pub(crate) fn check_fft_method(&self) -> Result<(), CrossCorrelateError> {
+        if self.method == CorrelationMethod::Direct {
+            return Err(CrossCorrelateError::UnsupportedOption(
+                "CorrelationMethod::Direct",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that no normalization is requested from a correlator that cannot scale its output.
+    pub(crate) fn check_no_normalization(&self) -> Result<(), CrossCorrelateError> {
+        if self.normalization != CrossCorrelationNormalization::None {
+            return Err(CrossCorrelateError::UnsupportedOption(
+                "CrossCorrelationNormalization other than None",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that no weighting is requested from a correlator that cannot weight its spectrum.
+    pub(crate) fn check_no_weighting(&self) -> Result<(), CrossCorrelateError> {
+        if self.weighting != GccWeighting::None {
+            return Err(CrossCorrelateError::UnsupportedOption(
+                "GccWeighting other than None",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks the options against the input lengths and resolves the method to use.
+    pub(crate) fn resolve_method(
+        &self,
+        buffer_length: usize,
+        other_length: usize,
+        is_complex: bool,
+    ) -> Result<CorrelationMethod, CrossCorrelateError> {
+        self.check_lengths(buffer_length, other_length)?;
+        if self.weighting == GccWeighting::None {
+            return Ok(self.method.resolve(
+                self.mode,
+                self.fft_size_policy,
+                buffer_length,
+                other_length,
+                is_complex,
+            ));
+        }
+        match self.method {
+            CorrelationMethod::Direct => Err(CrossCorrelateError::WeightingRequiresFft),
+            _ => Ok(CorrelationMethod::Fft),
+        }
+    }
+}
@@ -26,20 +26,15 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
-use crate::CrossCorrelateError;
-use crate::error::try_vec;
-
-pub(crate) fn pad_signal<V: Default + Clone + Copy>(
-    src: &[V],
-    new_length: usize,
-) -> Result<Vec<V>, CrossCorrelateError> {
+/// Copies `src` into the beginning of `dst` and zeroes the remaining tail.
+pub(crate) fn pad_signal<V: Default + Clone + Copy>(dst: &mut [V], src: &[V]) {
     assert!(
-        new_length >= src.len(),
+        dst.len() >= src.len(),
         "Something unexpected happened, src length must be always smaller or equal than pad length"
     );
-    let mut new_vec = try_vec![V::default(); new_length];
-    for (dst, &src) in new_vec.iter_mut().zip(src.iter()) {
-        *dst = src;
+    let (head, tail) = dst.split_at_mut(src.len());
+    head.copy_from_slice(src);
+    for dst in tail.iter_mut() {
+        *dst = V::default();
     }
-    Ok(new_vec)
 }
@@ -0,0 +1,426 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::error::try_vec;
+use crate::extract::extract_correlation;
+use crate::fft::{ComplexFft, ComplexToRealFft, RealToComplexFft};
+use crate::normalization::{NormalizeSample, signal_energy};
+use crate::pad::pad_signal;
+#[cfg(feature = "rayon")]
+use crate::parallel::worker_scratch;
+use crate::peak::{PeakValue, find_peak};
+use crate::scratch::as_complex_mut;
+use crate::spectrum::SpectrumMultiplier;
+use crate::weighting::SpectrumWeighting;
+use crate::{
+    CorrelateSample, CorrelationPeak, CrossCorrelateError, CrossCorrelationMode,
+    CrossCorrelationNormalization, lags,
+};
+use num_complex::Complex;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Selects which channel pairs a correlation matrix computes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum ChannelPairs {
+    /// Pairs `(i, j)` with `i < j` (default)
+    ///
+    /// The remaining pairs carry no extra information, the correlation of `(j, i)` is
+    /// the conjugated correlation of `(i, j)` reversed in lag.
+    #[default]
+    UpperTriangle,
+    /// All `N * N` pairs, including every channel with itself
+    All,
+}
+
+impl ChannelPairs {
+    /// Returns the selected pairs of `channels` channels in row-major order.
+    pub(crate) fn pairs(self, channels: usize) -> Vec<(usize, usize)> {
+        match self {
+            ChannelPairs::UpperTriangle => (0..channels)
+                .flat_map(|i| (i + 1..channels).map(move |j| (i, j)))
+                .collect(),
+            ChannelPairs::All => (0..channels)
+                .flat_map(|i| (0..channels).map(move |j| (i, j)))
+                .collect(),
+        }
+    }
+}
+
+/// Cross-correlation of one channel pair, `output` correlates `first` against `second`
+/// as `buffer` and `other` of [`crate::CrossCorrelate::correlate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairCorrelation<V> {
+    /// Index of the first channel.
+    pub first: usize,
+    /// Index of the second channel.
+    pub second: usize,
+    /// Correlation output, see [`CorrelationMatrix::lags`].
+    pub output: Vec<V>,
+}
+
+/// Largest value of the cross-correlation of one channel pair.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PairPeak<V> {
+    /// Index of the first channel.
+    pub first: usize,
+    /// Index of the second channel.
+    pub second: usize,
+    /// Peak of the correlation output as [`crate::find_peak`] reports it.
+    pub peak: CorrelationPeak<V>,
+}
+
+/// Trait for computing the cross-correlations of every pair among a set of channels.
+///
+/// Every channel is transformed once, and each selected pair costs one spectrum
+/// multiply and one inverse transform.
+pub trait CorrelationMatrix<V> {
+    /// Correlates the selected channel pairs and returns their outputs in row-major pair order.
+    fn correlate(&self, channels: &[&[V]]) -> Result<Vec<PairCorrelation<V>>, CrossCorrelateError>;
+    /// Correlates the selected channel pairs and keeps only the peak of each output.
+    fn peaks(&self, channels: &[&[V]]) -> Result<Vec<PairPeak<V>>, CrossCorrelateError>;
+    /// Returns the lag of every output sample.
+    fn lags(&self) -> Range<isize>;
+    /// Returns the length of every pair output.
+    fn output_length(&self) -> usize;
+}
+
+/// Runs `correlate_pair` for every pair with an output buffer and scratch that are
+/// reused across pairs, and collects its results.
+fn for_each_pair<V, R, F>(
+    pairs: &[(usize, usize)],
+    output_length: usize,
+    scratch_length: usize,
+    correlate_pair: F,
+) -> Result<Vec<R>, CrossCorrelateError>
+where
+    V: Copy + Default + Send + Sync,
+    R: Send,
+    F: Fn((usize, usize), &mut [V], &mut [V]) -> Result<R, CrossCorrelateError> + Sync,
+{
+    #[cfg(feature = "rayon")]
+    {
+        pairs
+            .par_iter()
+            .map_init(
+                || {
+                    (
+                        worker_scratch(output_length),
+                        worker_scratch(scratch_length),
+                    )
+                },
+                |(output, scratch), &pair| {
+                    let output = output.as_mut().map_err(|e| e.clone())?;
+                    let scratch = scratch.as_mut().map_err(|e| e.clone())?;
+                    correlate_pair(pair, output, scratch)
+                },
+            )
+            .collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        let mut output = try_vec![V::default(); output_length];
+        let mut scratch = try_vec![V::default(); scratch_length];
+        let mut results = Vec::with_capacity(pairs.len());
+        for &pair in pairs.iter() {
+            results.push(correlate_pair(pair, &mut output, &mut scratch)?);
+        }
+        Ok(results)
+    }
+}
+
+fn check_channels<V>(channels: &[&[V]], channel_length: usize) -> Result<(), CrossCorrelateError> {
+    for channel in channels.iter() {
+        if channel.len() != channel_length {
+            return Err(CrossCorrelateError::ChannelLengthDoNotMatch(
+                channel_length,
+                channel.len(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) struct CorrelationMatrixReal<T: CorrelateSample> {
+    pub(crate) fft_forward: Arc<dyn RealToComplexFft<T> + Send + Sync>,
+    pub(crate) fft_inverse: Arc<dyn ComplexToRealFft<T> + Send + Sync>,
+    pub(crate) multiplier: Arc<dyn SpectrumMultiplier<T> + Send + Sync>,
+    pub(crate) mode: CrossCorrelationMode,
+    pub(crate) normalization: CrossCorrelationNormalization,
+    pub(crate) weighting: SpectrumWeighting<T>,
+    pub(crate) channel_length: usize,
+    pub(crate) pairs: ChannelPairs,
+}
+
+impl<T: CorrelateSample> CorrelationMatrixReal<T> {
+    /// Number of bins in the half-spectrum of a padded channel.
+    fn complex_length(&self) -> usize {
+        self.fft_forward.real_length() / 2 + 1
+    }
+
+    /// Complex values of work space handed to one transform at a time.
+    fn executor_scratch_length(&self) -> usize {
+        self.fft_forward
+            .scratch_length()
+            .max(self.fft_inverse.scratch_length())
+    }
+
+    /// Transforms every channel, spectra are stored one after another.
+    fn spectra(&self, channels: &[&[T]]) -> Result<Vec<Complex<T>>, CrossCorrelateError> {
+        check_channels(channels, self.channel_length)?;
+        let complex_length = self.complex_length();
+        let mut spectra = try_vec![Complex::<T>::default(); complex_length * channels.len()];
+        let mut padded = try_vec![T::default(); self.fft_forward.real_length()];
+        let mut work = try_vec![Complex::<T>::default(); self.executor_scratch_length()];
+        for (spectrum, channel) in spectra.chunks_exact_mut(complex_length).zip(channels) {
+            pad_signal(&mut padded, channel);
+            self.fft_forward
+                .execute_with_scratch(&padded, spectrum, &mut work)?;
+        }
+        Ok(spectra)
+    }
+
+    /// Correlates one pair of spectra into `output`.
+    fn correlate_pair(
+        &self,
+        (spectra, channels): (&[Complex<T>], &[&[T]]),
+        (first, second): (usize, usize),
+        output: &mut [T],
+        scratch: &mut [T],
+    ) -> Result<(), CrossCorrelateError> {
+        let fft_size = self.fft_forward.real_length();
+        let complex_length = self.complex_length();
+        let (padded, product) = scratch.split_at_mut(fft_size);
+        let (product, work) = as_complex_mut(product).split_at_mut(complex_length);
+        product.copy_from_slice(&spectra[first * complex_length..(first + 1) * complex_length]);
+        self.weighting.multiply(
+            self.multiplier.as_ref(),
+            product,
+            &spectra[second * complex_length..(second + 1) * complex_length],
+            fft_size,
+        );
+        self.fft_inverse
+            .execute_with_scratch(product, padded, work)?;
+        let scale = self.normalization.lag_scale(
+            self.mode,
+            self.channel_length,
+            self.channel_length,
+            || signal_energy(channels[first]) * signal_energy(channels[second]),
+        );
+        extract_correlation(
+            output,
+            padded,
+            self.mode,
+            self.channel_length,
+            self.channel_length,
+            &scale,
+        );
+        Ok(())
+    }
+
+    fn scratch_length(&self) -> usize {
+        self.fft_forward.real_length()
+            + (self.complex_length() + self.executor_scratch_length()) * 2
+    }
+}
+
+impl<T: CorrelateSample + PeakValue> CorrelationMatrix<T> for CorrelationMatrixReal<T> {
+    fn correlate(&self, channels: &[&[T]]) -> Result<Vec<PairCorrelation<T>>, CrossCorrelateError> {
+        let spectra = self.spectra(channels)?;
+        let pairs = self.pairs.pairs(channels.len());
+        for_each_pair(
+            &pairs,
+            self.output_length(),
+            self.scratch_length(),
+            |(first, second), output: &mut [T], scratch| {
+                self.correlate_pair((&spectra, channels), (first, second), output, scratch)?;
+                Ok(PairCorrelation {
+                    first,
+                    second,
+                    output: output.to_vec(),
+                })
+            },
+        )
+    }
+
+    fn peaks(&self, channels: &[&[T]]) -> Result<Vec<PairPeak<T>>, CrossCorrelateError> {
+        let spectra = self.spectra(channels)?;
+        let pairs = self.pairs.pairs(channels.len());
+        for_each_pair(
+            &pairs,
+            self.output_length(),
+            self.scratch_length(),
+            |(first, second), output: &mut [T], scratch| {
+                self.correlate_pair((&spectra, channels), (first, second), output, scratch)?;
+                let peak = find_peak(output, self.mode, self.channel_length, self.channel_length)
+                    .ok_or(CrossCorrelateError::BuffersMustNotHaveZeroSize)?;
+                Ok(PairPeak {
+                    first,
+                    second,
+                    peak,
+                })
+            },
+        )
+    }
+
+    fn lags(&self) -> Range<isize> {
+        lags(self.mode, self.channel_length, self.channel_length)
+    }
+
+    fn output_length(&self) -> usize {
+        self.mode.get_size(self.channel_length, self.channel_length)
+    }
+}
+
+pub(crate) struct CorrelationMatrixComplex<T: CorrelateSample> {
+    pub(crate) fft_forward: Arc<dyn ComplexFft<T> + Send + Sync>,
+    pub(crate) fft_inverse: Arc<dyn ComplexFft<T> + Send + Sync>,
+    pub(crate) multiplier: Arc<dyn SpectrumMultiplier<T> + Send + Sync>,
+    pub(crate) mode: CrossCorrelationMode,
+    pub(crate) normalization: CrossCorrelationNormalization,
+    pub(crate) weighting: SpectrumWeighting<T>,
+    pub(crate) channel_length: usize,
+    pub(crate) pairs: ChannelPairs,
+}
+
+impl<T: CorrelateSample> CorrelationMatrixComplex<T>
+where
+    Complex<T>: NormalizeSample,
+{
+    /// Transforms every channel, spectra are stored one after another.
+    fn spectra(&self, channels: &[&[Complex<T>]]) -> Result<Vec<Complex<T>>, CrossCorrelateError> {
+        check_channels(channels, self.channel_length)?;
+        let fft_size = self.fft_forward.length();
+        let mut spectra = try_vec![Complex::<T>::default(); fft_size * channels.len()];
+        let mut work = try_vec![Complex::<T>::default(); self.fft_forward.scratch_length()];
+        for (spectrum, channel) in spectra.chunks_exact_mut(fft_size).zip(channels) {
+            pad_signal(spectrum, channel);
+            self.fft_forward.execute_with_scratch(spectrum, &mut work)?;
+        }
+        Ok(spectra)
+    }
+
+    /// Correlates one pair of spectra into `output`.
+    fn correlate_pair(
+        &self,
+        (spectra, channels): (&[Complex<T>], &[&[Complex<T>]]),
+        (first, second): (usize, usize),
+        output: &mut [Complex<T>],
+        scratch: &mut [Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        let fft_size = self.fft_forward.length();
+        let (product, work) = scratch.split_at_mut(fft_size);
+        product.copy_from_slice(&spectra[first * fft_size..(first + 1) * fft_size]);
+        self.weighting.multiply(
+            self.multiplier.as_ref(),
+            product,
+            &spectra[second * fft_size..(second + 1) * fft_size],
+            fft_size,
+        );
+        self.fft_inverse.execute_with_scratch(product, work)?;
+        let scale = self.normalization.lag_scale(
+            self.mode,
+            self.channel_length,
+            self.channel_length,
+            || signal_energy(channels[first]) * signal_energy(channels[second]),
+        );
+        extract_correlation(
+            output,
+            product,
+            self.mode,
+            self.channel_length,
+            self.channel_length,
+            &scale,
+        );
+        Ok(())
+    }
+
+    /// One product spectrum followed by the work space of the inverse executor.
+    fn scratch_length(&self) -> usize {
+        self.fft_forward.length() + self.fft_inverse.scratch_length()
+    }
+}
+
+impl<T: CorrelateSample> CorrelationMatrix<Complex<T>> for CorrelationMatrixComplex<T>
+where
+    Complex<T>: NormalizeSample + PeakValue,
+{
+    fn correlate(
+        &self,
+        channels: &[&[Complex<T>]],
+    ) -> Result<Vec<PairCorrelation<Complex<T>>>, CrossCorrelateError> {
+        let spectra = self.spectra(channels)?;
+        let pairs = self.pairs.pairs(channels.len());
+        for_each_pair(
+            &pairs,
+            self.output_length(),
+            self.scratch_length(),
+            |(first, second), output: &mut [Complex<T>], scratch| {
+                self.correlate_pair((&spectra, channels), (first, second), output, scratch)?;
+                Ok(PairCorrelation {
+                    first,
+                    second,
+                    output: output.to_vec(),
+                })
+            },
+        )
+    }
+
+    fn peaks(
+        &self,
+        channels: &[&[Complex<T>]],
+    ) -> Result<Vec<PairPeak<Complex<T>>>, CrossCorrelateError> {
+        let spectra = self.spectra(channels)?;
+        let pairs = self.pairs.pairs(channels.len());
+        for_each_pair(
+            &pairs,
+            self.output_length(),
+            self.scratch_length(),
+            |(first, second), output: &mut [Complex<T>], scratch| {
+                self.correlate_pair((&spectra, channels), (first, second), output, scratch)?;
+                let peak = find_peak(output, self.mode, self.channel_length, self.channel_length)
+                    .ok_or(CrossCorrelateError::BuffersMustNotHaveZeroSize)?;
+                Ok(PairPeak {
+                    first,
+                    second,
+                    peak,
+                })
+            },
+        )
+    }
+
+    fn lags(&self) -> Range<isize> {
+        lags(self.mode, self.channel_length, self.channel_length)
+    }
+
+    fn output_length(&self) -> usize {
+        self.mode.get_size(self.channel_length, self.channel_length)
+    }
+}
@@ -0,0 +1,82 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+
+#[cfg(feature = "rayon")]
+use crate::CrossCorrelateError;
+#[cfg(feature = "rayon")]
+use crate::error::try_vec;
+
+/// Smallest FFT size whose independent transforms are worth handing to the thread pool.
+///
+/// Below it the cost of waking a worker outweighs the transform itself.
+pub(crate) const PARALLEL_FFT_SIZE: usize = 4096;
+
+/// Number of workers independent transforms can be spread over: the size of the
+/// current rayon pool, or one without the `rayon` feature.
+#[inline]
+pub(crate) fn parallel_tasks() -> usize {
+    #[cfg(feature = "rayon")]
+    {
+        rayon::current_num_threads()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        1
+    }
+}
+
+/// Runs `a` and `b`, concurrently on the rayon pool when `parallel` is set and the
+/// `rayon` feature is enabled, one after the other otherwise.
+#[inline]
+pub(crate) fn join<A, B, RA, RB>(parallel: bool, a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    #[cfg(feature = "rayon")]
+    if parallel {
+        return rayon::join(a, b);
+    }
+    #[cfg(not(feature = "rayon"))]
+    let _ = parallel;
+    (a(), b())
+}
+
+/// Allocates the scratch buffer of one rayon worker.
+///
+/// Used as the `init` of `map_init`/`try_for_each_init`, where a failed allocation
+/// is reported by every item the worker picks up.
+#[cfg(feature = "rayon")]
+pub(crate) fn worker_scratch<V: Clone + Default>(
+    length: usize,
+) -> Result<Vec<V>, CrossCorrelateError> {
+    Ok(try_vec![V::default(); length])
+}
@@ -0,0 +1,356 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::CrossCorrelationMode;
+use crate::mode::lags;
+use num_complex::Complex;
+
+/// A local maximum of a cross-correlation result.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CorrelationPeak<V> {
+    /// Signed lag of the peak, see [`lags`].
+    pub lag: isize,
+    /// Correlation value at the peak.
+    pub value: V,
+    /// Index of the peak in the correlation output.
+    pub index: usize,
+}
+
+/// Correlation values that can be ranked when searching for peaks.
+///
+/// Real values rank by their signed value, complex values by their magnitude.
+pub trait PeakValue: Copy {
+    /// `true` if peaks are ranked by magnitude rather than by signed value.
+    const IS_COMPLEX: bool;
+    /// Returns the value widened to a complex number.
+    fn to_complex(self) -> Complex<f64>;
+    /// Returns the ranking score, larger is better.
+    #[inline]
+    fn peak_score(self) -> f64 {
+        score(self.to_complex(), Self::IS_COMPLEX)
+    }
+}
+
+#[inline]
+fn score(value: Complex<f64>, is_complex: bool) -> f64 {
+    if is_complex { value.norm() } else { value.re }
+}
+
+impl PeakValue for f32 {
+    const IS_COMPLEX: bool = false;
+    #[inline]
+    fn to_complex(self) -> Complex<f64> {
+        Complex::new(self as f64, 0.)
+    }
+}
+
+impl PeakValue for f64 {
+    const IS_COMPLEX: bool = false;
+    #[inline]
+    fn to_complex(self) -> Complex<f64> {
+        Complex::new(self, 0.)
+    }
+}
+
+impl PeakValue for Complex<f32> {
+    const IS_COMPLEX: bool = true;
+    #[inline]
+    fn to_complex(self) -> Complex<f64> {
+        Complex::new(self.re as f64, self.im as f64)
+    }
+}
+
+impl PeakValue for Complex<f64> {
+    const IS_COMPLEX: bool = true;
+    #[inline]
+    fn to_complex(self) -> Complex<f64> {
+        self
+    }
+}
+
+/// Finds the largest value of a correlation output.
+///
+/// `mode`, `buffer_len` and `other_len` must be the ones `output` was computed with.
+/// When several samples share the largest value the first one is returned, and `NaN`
+/// values are ignored. Returns `None` if `output` has no comparable values.
+pub fn find_peak<V: PeakValue>(
+    output: &[V],
+    mode: CrossCorrelationMode,
+    buffer_len: usize,
+    other_len: usize,
+) -> Option<CorrelationPeak<V>> {
+    let first_lag = lags(mode, buffer_len, other_len).start;
+    let mut best: Option<(usize, f64)> = None;
+    for (index, value) in output.iter().enumerate() {
+        let score = value.peak_score();
+        if score.is_nan() {
+            continue;
+        }
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((index, score));
+        }
+    }
+    best.map(|(index, _)| CorrelationPeak {
+        lag: first_lag + index as isize,
+        value: output[index],
+        index,
+    })
+}
+
+/// Finds up to `k` of the largest local maxima of a correlation output.
+///
+/// A sample is a local maximum when it is strictly greater than its left neighbour and
+/// not smaller than its right one, so plateaus report their first sample. Peaks are then
+/// picked greedily from the largest down, discarding any candidate closer than
+/// `min_distance` samples to an already selected peak.
+///
+/// The result is ordered from the largest peak to the smallest.
+pub fn find_peaks<V: PeakValue>(
+    output: &[V],
+    mode: CrossCorrelationMode,
+    buffer_len: usize,
+    other_len: usize,
+    k: usize,
+    min_distance: usize,
+) -> Vec<CorrelationPeak<V>> {
+    let first_lag = lags(mode, buffer_len, other_len).start;
+    let scores = output.iter().map(|x| x.peak_score()).collect::<Vec<_>>();
+
+    let mut candidates = (0..scores.len())
+        .filter(|&i| {
+            let score = scores[i];
+            !score.is_nan()
+                && (i == 0 || score > scores[i - 1])
+                && (i + 1 == scores.len() || score >= scores[i + 1])
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]).then(a.cmp(&b)));
+
+    let mut peaks: Vec<CorrelationPeak<V>> = Vec::with_capacity(k.min(candidates.len()));
+    for index in candidates {
+        if peaks.len() == k {
+            break;
+        }
+        if peaks.iter().any(|p| p.index.abs_diff(index) < min_distance) {
+            continue;
+        }
+        peaks.push(CorrelationPeak {
+            lag: first_lag + index as isize,
+            value: output[index],
+            index,
+        });
+    }
+    peaks
+}
+
+/// Method used to locate a correlation peak between samples.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum PeakInterpolation {
+    /// Fits a parabola through the peak and its two neighbours (default)
+    #[default]
+    Parabolic,
+    /// Fits a parabola through the logarithm of the three samples, exact for Gaussian
+    /// shaped peaks. Falls back to [`PeakInterpolation::Parabolic`] if any of them is not positive.
+    Gaussian,
+    /// Center of mass of the samples within the given radius around the peak,
+    /// negative values are ignored
+    Centroid(usize),
+    /// Maximizes the band-limited (Whittaker–Shannon) reconstruction of the output
+    /// from the samples within the given radius around the peak
+    Sinc(usize),
+    /// Reads the delay off the phase slope across the spectrum of the samples within the
+    /// given radius around the peak, the frequency-domain counterpart of [`PeakInterpolation::Sinc`]
+    PhaseSlope(usize),
+}
+
+/// A correlation peak located with sub-sample precision.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SubSamplePeak {
+    /// Fractional lag of the peak in samples.
+    pub delay: f64,
+    /// Interpolated peak height, the magnitude for complex outputs.
+    pub height: f64,
+}
+
+/// Refines an integer peak found by [`find_peak`] or [`find_peaks`] to a fractional lag.
+///
+/// `output` must be the correlation output `peak` was found in. Peaks on the first or
+/// last sample, or without a well defined maximum around them, are returned unrefined.
+pub fn refine_peak<V: PeakValue>(
+    output: &[V],
+    peak: &CorrelationPeak<V>,
+    interpolation: PeakInterpolation,
+) -> SubSamplePeak {
+    let index = peak.index;
+    let height = peak.value.peak_score();
+    let unrefined = SubSamplePeak {
+        delay: peak.lag as f64,
+        height,
+    };
+    if index == 0 || index + 1 >= output.len() {
+        return unrefined;
+    }
+    let left = output[index - 1].peak_score();
+    let right = output[index + 1].peak_score();
+
+    let refined = match interpolation {
+        PeakInterpolation::Parabolic => parabolic(left, height, right),
+        PeakInterpolation::Gaussian => {
+            if left > 0. && height > 0. && right > 0. {
+                parabolic(left.ln(), height.ln(), right.ln()).map(|(o, h)| (o, h.exp()))
+            } else {
+                parabolic(left, height, right)
+            }
+        }
+        PeakInterpolation::Centroid(radius) => centroid(output, index, radius.max(1)),
+        PeakInterpolation::Sinc(radius) => sinc_maximum(output, index, radius.max(1)),
+        PeakInterpolation::PhaseSlope(radius) => phase_slope(output, index, radius.max(1)),
+    };
+
+    match refined {
+        Some((offset, height)) => SubSamplePeak {
+            delay: peak.lag as f64 + offset,
+            height,
+        },
+        None => unrefined,
+    }
+}
+
+/// Vertex of the parabola through `(-1, left)`, `(0, center)`, `(1, right)`.
+fn parabolic(left: f64, center: f64, right: f64) -> Option<(f64, f64)> {
+    let curvature = left - 2. * center + right;
+    if curvature.is_nan() || curvature >= 0. {
+        return None;
+    }
+    let offset = (0.5 * (left - right) / curvature).clamp(-0.5, 0.5);
+    Some((offset, center - 0.25 * (left - right) * offset))
+}
+
+fn centroid<V: PeakValue>(output: &[V], index: usize, radius: usize) -> Option<(f64, f64)> {
+    let start = index.saturating_sub(radius);
+    let end = (index + radius + 1).min(output.len());
+    let mut mass = 0.;
+    let mut moment = 0.;
+    for (i, value) in output[start..end].iter().enumerate() {
+        let weight = value.peak_score().max(0.);
+        mass += weight;
+        moment += weight * (start + i) as f64;
+    }
+    if mass <= 0. {
+        return None;
+    }
+    let position = moment / mass;
+    // Height is read off the straight line between the two samples around the centroid
+    let below = (position.floor() as usize).min(output.len() - 1);
+    let above = (below + 1).min(output.len() - 1);
+    let fraction = position - below as f64;
+    let height =
+        output[below].peak_score() * (1. - fraction) + output[above].peak_score() * fraction;
+    Some((position - index as f64, height))
+}
+
+fn sinc_maximum<V: PeakValue>(output: &[V], index: usize, radius: usize) -> Option<(f64, f64)> {
+    let start = index.saturating_sub(radius);
+    let end = (index + radius + 1).min(output.len());
+    let window = &output[start..end];
+    let center = (index - start) as f64;
+    let reconstruct = |offset: f64| {
+        let t = center + offset;
+        let mut sum = Complex::new(0., 0.);
+        for (i, value) in window.iter().enumerate() {
+            let x = std::f64::consts::PI * (t - i as f64);
+            let weight = if x.abs() < 1e-12 { 1. } else { x.sin() / x };
+            sum += value.to_complex() * weight;
+        }
+        score(sum, V::IS_COMPLEX)
+    };
+
+    // Golden-section search, the reconstruction is unimodal next to a sampled maximum
+    const INV_PHI: f64 = 0.618_033_988_749_894_8;
+    let (mut lo, mut hi) = (-1f64, 1f64);
+    let mut x1 = hi - INV_PHI * (hi - lo);
+    let mut x2 = lo + INV_PHI * (hi - lo);
+    let mut f1 = reconstruct(x1);
+    let mut f2 = reconstruct(x2);
+    while hi - lo > 1e-9 {
+        if f1 < f2 {
+            lo = x1;
+            x1 = x2;
+            f1 = f2;
+            x2 = lo + INV_PHI * (hi - lo);
+            f2 = reconstruct(x2);
+        } else {
+            hi = x2;
+            x2 = x1;
+            f2 = f1;
+            x1 = hi - INV_PHI * (hi - lo);
+            f1 = reconstruct(x1);
+        }
+    }
+    let offset = 0.5 * (lo + hi);
+    Some((offset, reconstruct(offset)))
+}
+
+fn phase_slope<V: PeakValue>(output: &[V], index: usize, radius: usize) -> Option<(f64, f64)> {
+    let start = index.saturating_sub(radius);
+    let end = (index + radius + 1).min(output.len());
+    let window = &output[start..end];
+    let center = (index - start) as f64;
+    let length = window.len() as f64;
+    // Bins are taken around DC with the time origin on the peak, so a peak at `center + d`
+    // turns every bin by `-2π d / length` against its lower neighbour
+    let half = (window.len() as isize - 1) / 2;
+    let spectrum = (-half..=half)
+        .map(|k| {
+            let mut sum = Complex::new(0., 0.);
+            for (i, value) in window.iter().enumerate() {
+                let angle = -std::f64::consts::TAU * k as f64 * (i as f64 - center) / length;
+                sum += value.to_complex() * Complex::from_polar(1., angle);
+            }
+            sum
+        })
+        .collect::<Vec<_>>();
+    let rotation = spectrum
+        .windows(2)
+        .map(|pair| pair[1] * pair[0].conj())
+        .sum::<Complex<f64>>();
+    if !rotation.is_finite() || rotation.norm_sqr() == 0. {
+        return None;
+    }
+    let offset = (-rotation.arg() * length / std::f64::consts::TAU).clamp(-1., 1.);
+    // Trigonometric interpolation of the window at the refined position
+    let height = spectrum
+        .iter()
+        .zip(-half..=half)
+        .map(|(bin, k)| {
+            bin * Complex::from_polar(1., std::f64::consts::TAU * k as f64 * offset / length)
+        })
+        .sum::<Complex<f64>>()
+        / length;
+    Some((offset, score(height, V::IS_COMPLEX)))
+}
@@ -0,0 +1,222 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::peak::{PeakValue, find_peak, refine_peak};
+use crate::{
+    CorrelateSample, CorrelationPeak, CrossCorrelate, CrossCorrelate2d, CrossCorrelateError,
+    CrossCorrelationMode, PeakInterpolation, SubSamplePeak,
+};
+use std::sync::Arc;
+
+/// Translation between two signals estimated by phase correlation.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct PhaseShift {
+    /// Integer shift in samples, `buffer[n + shift]` matches `other[n]`.
+    pub shift: isize,
+    /// Shift refined to a fraction of a sample.
+    pub sub_sample_shift: f64,
+    /// Height of the phase correlation peak in `[0, 1]`.
+    ///
+    /// For signals of equal length it reaches 1 when `buffer` is an exact circular
+    /// shift of `other` and drops towards 0 as the signals stop being translations
+    /// of each other. Signals of different lengths are correlated with zero padding,
+    /// where the edges spread the peak and it stays below 1 even for exact shifts.
+    pub confidence: f64,
+}
+
+/// Translation between two images estimated by phase correlation.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct PhaseShift2d {
+    /// Integer shift along rows in pixels.
+    pub shift_x: isize,
+    /// Integer shift along columns in pixels.
+    pub shift_y: isize,
+    /// Horizontal shift refined to a fraction of a pixel.
+    pub sub_pixel_x: f64,
+    /// Vertical shift refined to a fraction of a pixel.
+    pub sub_pixel_y: f64,
+    /// Height of the phase correlation peak in `[0, 1]`, see [`PhaseShift::confidence`].
+    pub confidence: f64,
+}
+
+/// Trait for estimating the translation between two signals by phase correlation.
+pub trait PhaseCorrelate<V> {
+    /// Estimates the shift of `buffer` relative to `other`.
+    fn estimate(&self, buffer: &[V], other: &[V]) -> Result<PhaseShift, CrossCorrelateError>;
+}
+
+/// Trait for estimating the translation between two row-major images by phase correlation.
+pub trait PhaseCorrelate2d<V> {
+    /// Estimates the shift of `buffer` relative to `other`, strides are as in
+    /// [`CrossCorrelate2d::correlate`].
+    fn estimate(
+        &self,
+        buffer: &[V],
+        buffer_stride: usize,
+        other: &[V],
+        other_stride: usize,
+    ) -> Result<PhaseShift2d, CrossCorrelateError>;
+}
+
+/// Phase correlation on top of a PHAT weighted correlator.
+///
+/// `mode` is [`CrossCorrelationMode::Circular`] for inputs of equal length and
+/// [`CrossCorrelationMode::Full`] otherwise.
+pub(crate) struct PhaseCorrelation<T> {
+    pub(crate) correlator: Arc<dyn CrossCorrelate<T> + Send + Sync>,
+    pub(crate) mode: CrossCorrelationMode,
+}
+
+impl<T: CorrelateSample + PeakValue> PhaseCorrelate<T> for PhaseCorrelation<T> {
+    fn estimate(&self, buffer: &[T], other: &[T]) -> Result<PhaseShift, CrossCorrelateError> {
+        let surface = self.correlator.correlate_managed(buffer, other)?;
+        let peak = find_peak(&surface, self.mode, buffer.len(), other.len())
+            .ok_or(CrossCorrelateError::BuffersMustNotHaveZeroSize)?;
+        let circular = self.mode == CrossCorrelationMode::Circular;
+        let refined = refine_around(&surface, peak.index, circular);
+        let shift = signed_lag(peak.lag, surface.len(), circular);
+        Ok(PhaseShift {
+            shift,
+            sub_sample_shift: shift as f64 + (refined.delay - peak.index as f64),
+            confidence: confidence(refined.height),
+        })
+    }
+}
+
+/// 2D counterpart of [`PhaseCorrelation`], circular when both images have the same size.
+pub(crate) struct PhaseCorrelation2d<T> {
+    pub(crate) correlator: Arc<dyn CrossCorrelate2d<T> + Send + Sync>,
+    pub(crate) kernel_width: usize,
+    pub(crate) kernel_height: usize,
+    pub(crate) mode: CrossCorrelationMode,
+}
+
+impl<T: CorrelateSample + PeakValue> PhaseCorrelate2d<T> for PhaseCorrelation2d<T> {
+    fn estimate(
+        &self,
+        buffer: &[T],
+        buffer_stride: usize,
+        other: &[T],
+        other_stride: usize,
+    ) -> Result<PhaseShift2d, CrossCorrelateError> {
+        let surface =
+            self.correlator
+                .correlate_managed(buffer, buffer_stride, other, other_stride)?;
+        let width = self.correlator.output_width();
+
+        let mut best: Option<(usize, f64)> = None;
+        for (index, value) in surface.iter().enumerate() {
+            let score = value.peak_score();
+            if !score.is_nan() && best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((index, score));
+            }
+        }
+        let (index, _) = best.ok_or(CrossCorrelateError::BuffersMustNotHaveZeroSize)?;
+        let height = surface.len() / width;
+        let (x, y) = (index % width, index / width);
+        let circular = self.mode == CrossCorrelationMode::Circular;
+        let (shift_x, shift_y) = if circular {
+            (
+                signed_lag(x as isize, width, true),
+                signed_lag(y as isize, height, true),
+            )
+        } else {
+            (
+                x as isize - (self.kernel_width - 1) as isize,
+                y as isize - (self.kernel_height - 1) as isize,
+            )
+        };
+
+        // Each axis is refined separately through the row and column crossing the peak
+        let row = &surface[y * width..(y + 1) * width];
+        let refined_x = refine_around(row, x, circular);
+        let column = surface
+            .iter()
+            .skip(x)
+            .step_by(width)
+            .copied()
+            .collect::<Vec<T>>();
+        let refined_y = refine_around(&column, y, circular);
+
+        // Summit of the separable paraboloid through both axis fits, each of which
+        // adds its own rise over the sampled peak
+        let peak_height = surface[index].peak_score();
+        let height_2d = refined_x.height + refined_y.height - peak_height;
+
+        Ok(PhaseShift2d {
+            shift_x,
+            shift_y,
+            sub_pixel_x: shift_x as f64 + (refined_x.delay - x as f64),
+            sub_pixel_y: shift_y as f64 + (refined_y.delay - y as f64),
+            confidence: confidence(height_2d),
+        })
+    }
+}
+
+/// Maps a lag of a circular surface of `length` samples to `-length/2..=length/2`.
+#[inline]
+fn signed_lag(lag: isize, length: usize, circular: bool) -> isize {
+    if circular && lag as usize * 2 > length {
+        lag - length as isize
+    } else {
+        lag
+    }
+}
+
+/// Refines the peak at `index` with the delay measured in samples from the start
+/// of `surface`, wrapping the neighbours around its ends when `circular`.
+fn refine_around<T: PeakValue>(surface: &[T], index: usize, circular: bool) -> SubSamplePeak {
+    let peak = CorrelationPeak {
+        lag: index as isize,
+        value: surface[index],
+        index,
+    };
+    if !circular || surface.len() < 3 {
+        return refine_peak(surface, &peak, PeakInterpolation::Parabolic);
+    }
+    let length = surface.len();
+    let window = [
+        surface[(index + length - 1) % length],
+        surface[index],
+        surface[(index + 1) % length],
+    ];
+    refine_peak(
+        &window,
+        &CorrelationPeak { index: 1, ..peak },
+        PeakInterpolation::Parabolic,
+    )
+}
+
+#[inline]
+fn confidence(height: f64) -> f64 {
+    if height.is_nan() {
+        0.
+    } else {
+        height.clamp(0., 1.)
+    }
+}
@@ -0,0 +1,211 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::fft::{ComplexFft, ComplexToRealFft, FftPlanner, RealToComplexFft, ZaftPlanner};
+use crate::{CorrelateSample, CrossCorrelateError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+type PlanMap<P> = Mutex<HashMap<usize, Arc<P>>>;
+
+/// Thread-safe cache of FFT plans keyed by size and direction.
+///
+/// Planning a transform is far more expensive than running it, so correlators
+/// created through [`Correlate::create_with_cache`](crate::Correlate::create_with_cache)
+/// share the executors of every FFT size they have in common.
+/// Plans stay alive until the cache is cleared or dropped and no correlator uses them.
+///
+/// Missing plans come from zaft, or from the planner given to [`FftPlanCache::with_planner`].
+#[derive(Default)]
+pub struct FftPlanCache<T: CorrelateSample> {
+    planner: Option<Arc<dyn FftPlanner<T>>>,
+    real_to_complex: PlanMap<dyn RealToComplexFft<T> + Send + Sync>,
+    complex_to_real: PlanMap<dyn ComplexToRealFft<T> + Send + Sync>,
+    forward: PlanMap<dyn ComplexFft<T> + Send + Sync>,
+    inverse: PlanMap<dyn ComplexFft<T> + Send + Sync>,
+}
+
+fn get_or_plan<P: ?Sized>(
+    plans: &PlanMap<P>,
+    size: usize,
+    plan: impl FnOnce(usize) -> Result<Arc<P>, CrossCorrelateError>,
+) -> Result<Arc<P>, CrossCorrelateError> {
+    // Planning can only panic before anything is inserted, so a poisoned map is still consistent
+    let mut plans = plans.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(executor) = plans.get(&size) {
+        return Ok(executor.clone());
+    }
+    let executor = plan(size)?;
+    plans.insert(size, executor.clone());
+    Ok(executor)
+}
+
+fn plan_count<P: ?Sized>(plans: &PlanMap<P>) -> usize {
+    plans.lock().unwrap_or_else(|e| e.into_inner()).len()
+}
+
+impl<T: CorrelateSample> FftPlanCache<T> {
+    /// Creates an empty cache.
+    pub fn new() -> FftPlanCache<T> {
+        FftPlanCache::default()
+    }
+
+    /// Creates an empty cache planning missing transforms with `planner`.
+    pub fn with_planner(planner: Arc<dyn FftPlanner<T>>) -> FftPlanCache<T> {
+        FftPlanCache {
+            planner: Some(planner),
+            ..FftPlanCache::default()
+        }
+    }
+
+    fn planner(&self) -> &dyn FftPlanner<T> {
+        match &self.planner {
+            Some(planner) => planner.as_ref(),
+            None => &ZaftPlanner,
+        }
+    }
+
+    /// Returns the real-to-complex plan of `size` samples, planning it on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if the FFT plan cannot be created.
+    pub fn real_to_complex(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn RealToComplexFft<T> + Send + Sync>, CrossCorrelateError> {
+        get_or_plan(&self.real_to_complex, size, |size| {
+            self.planner().plan_real_to_complex(size)
+        })
+    }
+
+    /// Returns the complex-to-real plan of `size` samples, planning it on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if the FFT plan cannot be created.
+    pub fn complex_to_real(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn ComplexToRealFft<T> + Send + Sync>, CrossCorrelateError> {
+        get_or_plan(&self.complex_to_real, size, |size| {
+            self.planner().plan_complex_to_real(size)
+        })
+    }
+
+    /// Returns the forward complex plan of `size` samples, planning it on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if the FFT plan cannot be created.
+    pub fn forward(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn ComplexFft<T> + Send + Sync>, CrossCorrelateError> {
+        get_or_plan(&self.forward, size, |size| {
+            self.planner().plan_forward(size)
+        })
+    }
+
+    /// Returns the inverse complex plan of `size` samples, planning it on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError`] if the FFT plan cannot be created.
+    pub fn inverse(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn ComplexFft<T> + Send + Sync>, CrossCorrelateError> {
+        get_or_plan(&self.inverse, size, |size| {
+            self.planner().plan_inverse(size)
+        })
+    }
+
+    /// Returns the number of plans held, counting every size and direction.
+    pub fn len(&self) -> usize {
+        plan_count(&self.real_to_complex)
+            + plan_count(&self.complex_to_real)
+            + plan_count(&self.forward)
+            + plan_count(&self.inverse)
+    }
+
+    /// Returns `true` if no plan has been created yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops every cached plan, correlators holding one keep it alive.
+    pub fn clear(&self) {
+        self.real_to_complex
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+        self.complex_to_real
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+        self.forward
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+        self.inverse
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+impl<T: CorrelateSample> FftPlanner<T> for FftPlanCache<T> {
+    fn plan_real_to_complex(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn RealToComplexFft<T> + Send + Sync>, CrossCorrelateError> {
+        self.real_to_complex(size)
+    }
+
+    fn plan_complex_to_real(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn ComplexToRealFft<T> + Send + Sync>, CrossCorrelateError> {
+        self.complex_to_real(size)
+    }
+
+    fn plan_forward(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn ComplexFft<T> + Send + Sync>, CrossCorrelateError> {
+        self.forward(size)
+    }
+
+    fn plan_inverse(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn ComplexFft<T> + Send + Sync>, CrossCorrelateError> {
+        self.inverse(size)
+    }
+}
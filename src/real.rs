@@ -26,20 +26,41 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
+use crate::convolve::SpectrumProduct;
 use crate::error::try_vec;
-use crate::fast_divider::DividerUsize;
+use crate::extract::{extract_convolution, extract_correlation};
+use crate::fft::{ComplexToRealFft, RealToComplexFft};
+use crate::normalization::signal_energy;
 use crate::pad::pad_signal;
+#[cfg(feature = "rayon")]
+use crate::parallel::{PARALLEL_FFT_SIZE, join};
+use crate::scratch::as_complex_mut;
 use crate::spectrum::SpectrumMultiplier;
-use crate::{CorrelateSample, CrossCorrelate, CrossCorrelateError, CrossCorrelationMode};
-use num_complex::Complex;
+use crate::weighting::SpectrumWeighting;
+use crate::{
+    CorrelateSample, CrossCorrelate, CrossCorrelateError, CrossCorrelationMode,
+    CrossCorrelationNormalization, FftSizePolicy,
+};
 use std::sync::Arc;
-use zaft::{C2RFftExecutor, R2CFftExecutor};
 
 pub(crate) struct CrossCorrelateReal<T: CorrelateSample> {
-    pub(crate) fft_forward: Arc<dyn R2CFftExecutor<T> + Send + Sync>,
-    pub(crate) fft_inverse: Arc<dyn C2RFftExecutor<T> + Send + Sync>,
+    pub(crate) fft_forward: Arc<dyn RealToComplexFft<T> + Send + Sync>,
+    pub(crate) fft_inverse: Arc<dyn ComplexToRealFft<T> + Send + Sync>,
     pub(crate) multiplier: Arc<dyn SpectrumMultiplier<T> + Send + Sync>,
     pub(crate) mode: CrossCorrelationMode,
+    pub(crate) normalization: CrossCorrelationNormalization,
+    pub(crate) weighting: SpectrumWeighting<T>,
+    pub(crate) product: SpectrumProduct,
+    pub(crate) fft_size_policy: FftSizePolicy,
+}
+
+impl<T: CorrelateSample> CrossCorrelateReal<T> {
+    /// Complex values of work space handed to one transform at a time.
+    fn executor_scratch_length(&self) -> usize {
+        self.fft_forward
+            .scratch_length()
+            .max(self.fft_inverse.scratch_length())
+    }
 }
 
 impl<T: CorrelateSample> CrossCorrelate<T> for CrossCorrelateReal<T> {
@@ -48,6 +69,33 @@ impl<T: CorrelateSample> CrossCorrelate<T> for CrossCorrelateReal<T> {
         output: &mut [T],
         buffer: &[T],
         other: &[T],
+    ) -> Result<(), CrossCorrelateError> {
+        let mut scratch = try_vec![T::default(); self.scratch_length()];
+        self.correlate_with_scratch(output, buffer, other, &mut scratch)
+    }
+
+    fn correlate_managed(&self, buffer: &[T], other: &[T]) -> Result<Vec<T>, CrossCorrelateError> {
+        let data_length = self.mode.get_size(buffer.len(), other.len());
+        let mut output = try_vec![T::default(); data_length];
+        self.correlate(&mut output, buffer, other).map(|_| output)
+    }
+
+    fn scratch_length(&self) -> usize {
+        // Padded real signal followed by two half-spectrums and the work space of the
+        // executors, all complex values stored as interleaved scalars.
+        // With `rayon` both signals are padded and transformed at once.
+        let padded_signals = if cfg!(feature = "rayon") { 2 } else { 1 };
+        let real_length = self.fft_forward.real_length();
+        let fft_scratch = self.executor_scratch_length();
+        real_length * padded_signals + (real_length / 2 + 1) * 4 + fft_scratch * 2 * padded_signals
+    }
+
+    fn correlate_with_scratch(
+        &self,
+        output: &mut [T],
+        buffer: &[T],
+        other: &[T],
+        scratch: &mut [T],
     ) -> Result<(), CrossCorrelateError> {
         if buffer.is_empty() || other.is_empty() || output.is_empty() {
             return Err(CrossCorrelateError::BuffersMustNotHaveZeroSize);
@@ -59,7 +107,9 @@ impl<T: CorrelateSample> CrossCorrelate<T> for CrossCorrelateReal<T> {
             ));
         }
         let data_length = self.mode.get_size(buffer.len(), other.len());
-        let fft_size = self.mode.fft_size(buffer.len(), other.len());
+        let fft_size = self
+            .fft_size_policy
+            .fft_size(self.mode, buffer.len(), other.len());
 
         if fft_size != self.fft_forward.real_length() {
             return Err(CrossCorrelateError::FftAndBuffersSizeDoNotMatch(
@@ -75,64 +125,87 @@ impl<T: CorrelateSample> CrossCorrelate<T> for CrossCorrelateReal<T> {
             ));
         }
 
-        let mut padded_src = pad_signal(buffer, fft_size)?;
-        let padded_other = pad_signal(other, fft_size)?;
-
-        let mut complex_src = try_vec![Complex::<T>::default(); padded_src.len() / 2 + 1];
-        let mut complex_other = try_vec![Complex::<T>::default(); padded_other.len() / 2 + 1];
+        let scratch_length = self.scratch_length();
+        if scratch.len() < scratch_length {
+            return Err(CrossCorrelateError::ScratchSizeIsTooSmall(
+                scratch_length,
+                scratch.len(),
+            ));
+        }
 
-        self.fft_forward
-            .execute(&padded_src, &mut complex_src)
-            .map_err(|x| CrossCorrelateError::FftError(x.to_string()))?;
-        self.fft_forward
-            .execute(&padded_other, &mut complex_other)
-            .map_err(|x| CrossCorrelateError::FftError(x.to_string()))?;
-        self.multiplier
-            .mul_spectrum(&mut complex_src, &complex_other, fft_size);
-        self.fft_inverse
-            .execute(&complex_src, &mut padded_src)
-            .map_err(|x| CrossCorrelateError::FftError(x.to_string()))?;
+        let complex_length = self.fft_forward.real_length() / 2 + 1;
+        let fft_scratch = self.executor_scratch_length();
+        let (padded_src, spectrum_scratch) = scratch[..scratch_length].split_at_mut(fft_size);
+        #[cfg(feature = "rayon")]
+        let (padded_other, spectrum_scratch) = spectrum_scratch.split_at_mut(fft_size);
+        let (complex_src, spectrum_scratch) =
+            as_complex_mut(spectrum_scratch).split_at_mut(complex_length);
+        let (complex_other, work_src) = spectrum_scratch.split_at_mut(complex_length);
 
-        let lag = other.len() - 1;
-        let offset = fft_size - lag;
-        match self.mode {
-            CrossCorrelationMode::Full => {
-                if fft_size == 1 {
-                    for dst in output.iter_mut() {
-                        *dst = unsafe { *padded_src.get_unchecked(0) }
-                    }
-                } else {
-                    let divisor = DividerUsize::new(fft_size);
-                    for (i, dst) in output.iter_mut().enumerate() {
-                        *dst = unsafe { *padded_src.get_unchecked((i + offset) % divisor) }
-                    }
-                }
-            }
-            CrossCorrelationMode::Valid | CrossCorrelationMode::Same => {
-                let start = match self.mode {
-                    CrossCorrelationMode::Valid => other.len() - 1,
-                    CrossCorrelationMode::Same => (other.len() - 1) / 2,
-                    CrossCorrelationMode::Full => unreachable!(),
-                };
-                if fft_size == 1 {
-                    for dst in output.iter_mut() {
-                        *dst = unsafe { *padded_src.get_unchecked(0) }
-                    }
-                } else {
-                    let divisor = DividerUsize::new(fft_size);
-                    for (i, dst) in output.iter_mut().enumerate() {
-                        *dst = unsafe { *padded_src.get_unchecked((start + i + offset) % divisor) };
-                    }
-                }
+        #[cfg(feature = "rayon")]
+        {
+            let (work_src, work_other) = work_src.split_at_mut(fft_scratch);
+            let (src_result, other_result) = join(
+                fft_size >= PARALLEL_FFT_SIZE,
+                || {
+                    pad_signal(padded_src, buffer);
+                    self.fft_forward
+                        .execute_with_scratch(padded_src, complex_src, work_src)
+                },
+                || {
+                    pad_signal(padded_other, other);
+                    self.fft_forward
+                        .execute_with_scratch(padded_other, complex_other, work_other)
+                },
+            );
+            src_result.and(other_result)?;
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            pad_signal(padded_src, other);
+            self.fft_forward
+                .execute_with_scratch(padded_src, complex_other, work_src)?;
+            pad_signal(padded_src, buffer);
+            self.fft_forward
+                .execute_with_scratch(padded_src, complex_src, work_src)?;
+        }
+        match self.product {
+            SpectrumProduct::Correlation => self.weighting.multiply(
+                self.multiplier.as_ref(),
+                complex_src,
+                complex_other,
+                fft_size,
+            ),
+            SpectrumProduct::Convolution => {
+                self.multiplier
+                    .mul_spectrum_convolve(complex_src, complex_other, fft_size)
             }
         }
+        self.fft_inverse.execute_with_scratch(
+            complex_src,
+            padded_src,
+            &mut work_src[..fft_scratch],
+        )?;
 
-        Ok(())
-    }
+        if self.product == SpectrumProduct::Convolution {
+            extract_convolution(output, padded_src, self.mode, buffer.len(), other.len());
+            return Ok(());
+        }
 
-    fn correlate_managed(&self, buffer: &[T], other: &[T]) -> Result<Vec<T>, CrossCorrelateError> {
-        let data_length = self.mode.get_size(buffer.len(), other.len());
-        let mut output = try_vec![T::default(); data_length];
-        self.correlate(&mut output, buffer, other).map(|_| output)
+        let scale = self
+            .normalization
+            .lag_scale(self.mode, buffer.len(), other.len(), || {
+                signal_energy(buffer) * signal_energy(other)
+            });
+        extract_correlation(
+            output,
+            padded_src,
+            self.mode,
+            buffer.len(),
+            other.len(),
+            &scale,
+        );
+
+        Ok(())
     }
 }
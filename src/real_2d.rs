@@ -0,0 +1,442 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::error::try_vec;
+use crate::fft::{ComplexFft, ComplexToRealFft, RealToComplexFft};
+use crate::pad::pad_signal;
+use crate::parallel::PARALLEL_FFT_SIZE;
+use crate::scratch::as_complex_mut;
+use crate::spectrum::SpectrumMultiplier;
+use crate::weighting::SpectrumWeighting;
+use crate::{
+    CorrelateSample, CrossCorrelate2d, CrossCorrelateError, CrossCorrelationMode, FftSizePolicy,
+};
+use num_complex::Complex;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::sync::Arc;
+
+/// Two-dimensional correlation of real images.
+///
+/// Rows are transformed with real FFTs and the resulting half-spectrum columns
+/// with complex FFTs. After the multiply only the rows that end up in the output
+/// are transformed back.
+pub(crate) struct CrossCorrelateReal2d<T: CorrelateSample> {
+    pub(crate) row_forward: Arc<dyn RealToComplexFft<T> + Send + Sync>,
+    pub(crate) row_inverse: Arc<dyn ComplexToRealFft<T> + Send + Sync>,
+    pub(crate) column_forward: Arc<dyn ComplexFft<T> + Send + Sync>,
+    pub(crate) column_inverse: Arc<dyn ComplexFft<T> + Send + Sync>,
+    pub(crate) multiplier: Arc<dyn SpectrumMultiplier<T> + Send + Sync>,
+    pub(crate) mode: CrossCorrelationMode,
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    pub(crate) kernel_width: usize,
+    pub(crate) kernel_height: usize,
+    pub(crate) weighting: SpectrumWeighting<T>,
+    pub(crate) fft_size_policy: FftSizePolicy,
+    /// Number of tasks the row and column passes may be split into.
+    pub(crate) tasks: usize,
+}
+
+/// Checks that a row-major image of `width` x `height` fits into `data` with `stride`.
+pub(crate) fn check_image<V>(
+    data: &[V],
+    width: usize,
+    height: usize,
+    stride: usize,
+) -> Result<(), CrossCorrelateError> {
+    if stride < width {
+        return Err(CrossCorrelateError::StrideIsTooSmall(width, stride));
+    }
+    if width == 0 || height == 0 {
+        return Ok(());
+    }
+    let required = (height - 1) * stride + width;
+    if data.len() < required {
+        return Err(CrossCorrelateError::ImageSizeDoNotMatch(
+            required,
+            data.len(),
+        ));
+    }
+    Ok(())
+}
+
+impl<T: CorrelateSample> CrossCorrelateReal2d<T> {
+    /// Number of bins in the half-spectrum of a padded row.
+    fn complex_width(&self) -> usize {
+        self.row_forward.real_length() / 2 + 1
+    }
+
+    /// Complex values of work space handed to one transform at a time.
+    fn executor_scratch_length(&self) -> usize {
+        self.row_forward
+            .scratch_length()
+            .max(self.row_inverse.scratch_length())
+            .max(self.column_forward.scratch_length())
+            .max(self.column_inverse.scratch_length())
+    }
+
+    /// Number of tasks with their own slice of work space, at most one per spectrum row.
+    fn lanes(&self) -> usize {
+        self.tasks.clamp(1, self.column_forward.length())
+    }
+
+    /// Scalars of work space owned by one task: a padded row, a row half-spectrum
+    /// and the executor scratch, the last two stored as interleaved scalars.
+    fn lane_length(&self) -> usize {
+        self.row_forward.real_length() + (self.complex_width() + self.executor_scratch_length()) * 2
+    }
+
+    /// Splits the work space of one task into a padded row, a row half-spectrum and
+    /// the executor scratch.
+    fn split_lane<'a>(
+        &self,
+        lane: &'a mut [T],
+    ) -> (&'a mut [T], &'a mut [Complex<T>], &'a mut [Complex<T>]) {
+        let (row, rest) = lane.split_at_mut(self.row_forward.real_length());
+        let (row_spectrum, work) = as_complex_mut(rest).split_at_mut(self.complex_width());
+        (row, row_spectrum, work)
+    }
+
+    /// Runs `task` over `data` split into one contiguous group of whole `item`-sized
+    /// chunks per lane of work space.
+    ///
+    /// `task` receives the index of the first item of its group. With `rayon` the
+    /// groups run concurrently when `parallel` is set, otherwise everything runs as
+    /// a single group on the first lane.
+    fn for_each_lane<V: Send>(
+        &self,
+        parallel: bool,
+        (data, item): (&mut [V], usize),
+        lanes: &mut [T],
+        task: impl Fn(usize, &mut [V], &mut [T]) -> Result<(), CrossCorrelateError> + Sync,
+    ) -> Result<(), CrossCorrelateError> {
+        let lane_length = self.lane_length();
+        let items = data.len().div_ceil(item);
+        if items == 0 {
+            return Ok(());
+        }
+        #[cfg(feature = "rayon")]
+        if parallel {
+            let items_per_lane = items.div_ceil(lanes.len() / lane_length);
+            return data
+                .par_chunks_mut(items_per_lane * item)
+                .zip(lanes.par_chunks_exact_mut(lane_length))
+                .enumerate()
+                .try_for_each(|(i, (group, lane))| task(i * items_per_lane, group, lane));
+        }
+        #[cfg(not(feature = "rayon"))]
+        let _ = parallel;
+        task(0, data, &mut lanes[..lane_length])
+    }
+
+    /// Computes the 2D spectrum of a zero padded image into `columns`, stored
+    /// column-major so that every column is contiguous.
+    fn forward(
+        &self,
+        image: &[T],
+        stride: usize,
+        size: (usize, usize),
+        (parallel, lanes): (bool, &mut [T]),
+        rows: &mut [Complex<T>],
+        columns: &mut [Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        let complex_width = self.complex_width();
+        let fft_height = self.column_forward.length();
+        self.for_each_lane(
+            parallel,
+            (rows, complex_width),
+            lanes,
+            |first, group, lane| {
+                let (row, _, work) = self.split_lane(lane);
+                for (i, spectrum) in group.chunks_exact_mut(complex_width).enumerate() {
+                    self.forward_row(image, stride, size, first + i, (row, work), spectrum)?;
+                }
+                Ok(())
+            },
+        )?;
+        let rows = &*rows;
+        self.for_each_lane(
+            parallel,
+            (columns, fft_height),
+            lanes,
+            |first, group, lane| {
+                let (_, _, work) = self.split_lane(lane);
+                for (i, column) in group.chunks_exact_mut(fft_height).enumerate() {
+                    self.forward_column(rows, first + i, column, work)?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Transforms row `y` of the zero padded image, rows past the image are zero.
+    fn forward_row(
+        &self,
+        image: &[T],
+        stride: usize,
+        (width, height): (usize, usize),
+        y: usize,
+        (row, work): (&mut [T], &mut [Complex<T>]),
+        spectrum: &mut [Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        if y < height {
+            pad_signal(row, &image[y * stride..y * stride + width]);
+            self.row_forward.execute_with_scratch(row, spectrum, work)
+        } else {
+            spectrum.fill(Complex::<T>::default());
+            Ok(())
+        }
+    }
+
+    /// Gathers column `x` out of the row-major half-spectra and transforms it.
+    fn forward_column(
+        &self,
+        rows: &[Complex<T>],
+        x: usize,
+        column: &mut [Complex<T>],
+        work: &mut [Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        let complex_width = self.complex_width();
+        for (y, dst) in column.iter_mut().enumerate() {
+            *dst = rows[y * complex_width + x];
+        }
+        self.column_forward.execute_with_scratch(column, work)
+    }
+
+    /// Reconstructs output row `y` from the column-major cross spectrum, whose
+    /// columns have already been inverse transformed.
+    fn inverse_row(
+        &self,
+        columns: &[Complex<T>],
+        y: usize,
+        (row_spectrum, work): (&mut [Complex<T>], &mut [Complex<T>]),
+        row: &mut [T],
+        dst: &mut [T],
+    ) -> Result<(), CrossCorrelateError> {
+        let fft_width = self.row_forward.real_length();
+        let fft_height = self.column_forward.length();
+        let start_x = self.mode.start_index(self.width, self.kernel_width);
+        let start_y = self.mode.start_index(self.height, self.kernel_height);
+        let offset_x = fft_width - (self.kernel_width - 1);
+        let offset_y = fft_height - (self.kernel_height - 1);
+
+        let source_y = (start_y + y + offset_y) % fft_height;
+        for (x, dst) in row_spectrum.iter_mut().enumerate() {
+            *dst = columns[x * fft_height + source_y];
+        }
+        self.row_inverse
+            .execute_with_scratch(row_spectrum, row, work)?;
+        for (x, dst) in dst.iter_mut().enumerate() {
+            *dst = row[(start_x + x + offset_x) % fft_width];
+        }
+        Ok(())
+    }
+}
+
+impl<T: CorrelateSample> CrossCorrelate2d<T> for CrossCorrelateReal2d<T> {
+    fn correlate(
+        &self,
+        output: &mut [T],
+        output_stride: usize,
+        buffer: &[T],
+        buffer_stride: usize,
+        other: &[T],
+        other_stride: usize,
+    ) -> Result<(), CrossCorrelateError> {
+        let mut scratch = try_vec![T::default(); self.scratch_length()];
+        self.correlate_with_scratch(
+            output,
+            output_stride,
+            buffer,
+            buffer_stride,
+            other,
+            other_stride,
+            &mut scratch,
+        )
+    }
+
+    fn correlate_managed(
+        &self,
+        buffer: &[T],
+        buffer_stride: usize,
+        other: &[T],
+        other_stride: usize,
+    ) -> Result<Vec<T>, CrossCorrelateError> {
+        let output_width = self.output_width();
+        let mut output = try_vec![T::default(); output_width * self.output_height()];
+        self.correlate(
+            &mut output,
+            output_width,
+            buffer,
+            buffer_stride,
+            other,
+            other_stride,
+        )
+        .map(|_| output)
+    }
+
+    fn output_width(&self) -> usize {
+        self.mode.get_size(self.width, self.kernel_width)
+    }
+
+    fn output_height(&self) -> usize {
+        self.mode.get_size(self.height, self.kernel_height)
+    }
+
+    fn scratch_length(&self) -> usize {
+        // The work space of every task, then the row-major half-spectrum and two
+        // column-major spectra stored as interleaved scalars.
+        let spectrum_length = self.complex_width() * self.column_forward.length();
+        self.lanes() * self.lane_length() + spectrum_length * 3 * 2
+    }
+
+    fn correlate_with_scratch(
+        &self,
+        output: &mut [T],
+        output_stride: usize,
+        buffer: &[T],
+        buffer_stride: usize,
+        other: &[T],
+        other_stride: usize,
+        scratch: &mut [T],
+    ) -> Result<(), CrossCorrelateError> {
+        if self.width == 0 || self.height == 0 || self.kernel_width == 0 || self.kernel_height == 0
+        {
+            return Err(CrossCorrelateError::BuffersMustNotHaveZeroSize);
+        }
+        let fft_width = self
+            .fft_size_policy
+            .fft_size(self.mode, self.width, self.kernel_width);
+        let fft_height = self
+            .fft_size_policy
+            .fft_size(self.mode, self.height, self.kernel_height);
+        if fft_width != self.row_forward.real_length() {
+            return Err(CrossCorrelateError::FftAndBuffersSizeDoNotMatch(
+                self.row_forward.real_length(),
+                fft_width,
+            ));
+        }
+        if fft_height != self.column_forward.length() {
+            return Err(CrossCorrelateError::FftAndBuffersSizeDoNotMatch(
+                self.column_forward.length(),
+                fft_height,
+            ));
+        }
+        if self.row_inverse.real_length() != fft_width {
+            return Err(CrossCorrelateError::FftSizesDoNotMatch(
+                fft_width,
+                self.row_inverse.real_length(),
+            ));
+        }
+        if self.column_inverse.length() != fft_height {
+            return Err(CrossCorrelateError::FftSizesDoNotMatch(
+                fft_height,
+                self.column_inverse.length(),
+            ));
+        }
+
+        let output_width = self.output_width();
+        let output_height = self.output_height();
+        check_image(buffer, self.width, self.height, buffer_stride)?;
+        check_image(other, self.kernel_width, self.kernel_height, other_stride)?;
+        check_image(output, output_width, output_height, output_stride)?;
+
+        let scratch_length = self.scratch_length();
+        if scratch.len() < scratch_length {
+            return Err(CrossCorrelateError::ScratchSizeIsTooSmall(
+                scratch_length,
+                scratch.len(),
+            ));
+        }
+
+        let spectrum_length = self.complex_width() * fft_height;
+        let (lanes, spectra) =
+            scratch[..scratch_length].split_at_mut(self.lanes() * self.lane_length());
+        let (rows, columns) = as_complex_mut(spectra).split_at_mut(spectrum_length);
+        let (columns_src, columns_other) = columns.split_at_mut(spectrum_length);
+        let parallel = fft_width * fft_height >= PARALLEL_FFT_SIZE;
+
+        self.forward(
+            other,
+            other_stride,
+            (self.kernel_width, self.kernel_height),
+            (parallel, lanes),
+            rows,
+            columns_other,
+        )?;
+        self.forward(
+            buffer,
+            buffer_stride,
+            (self.width, self.height),
+            (parallel, lanes),
+            rows,
+            columns_src,
+        )?;
+        self.weighting.multiply(
+            self.multiplier.as_ref(),
+            columns_src,
+            columns_other,
+            fft_width * fft_height,
+        );
+        self.for_each_lane(
+            parallel,
+            (columns_src, fft_height),
+            lanes,
+            |_, group, lane| {
+                let (_, _, work) = self.split_lane(lane);
+                for column in group.chunks_exact_mut(fft_height) {
+                    self.column_inverse.execute_with_scratch(column, work)?;
+                }
+                Ok(())
+            },
+        )?;
+        let columns_src = &*columns_src;
+        let output = &mut output[..(output_height - 1) * output_stride + output_width];
+        self.for_each_lane(
+            parallel,
+            (output, output_stride),
+            lanes,
+            |first, group, lane| {
+                let (row, row_spectrum, work) = self.split_lane(lane);
+                for (i, dst) in group.chunks_mut(output_stride).enumerate() {
+                    self.inverse_row(
+                        columns_src,
+                        first + i,
+                        (row_spectrum, work),
+                        row,
+                        &mut dst[..output_width],
+                    )?;
+                }
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,301 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::batch;
+use crate::error::try_vec;
+use crate::extract::extract_correlation;
+use crate::fft::{ComplexToRealFft, RealToComplexFft};
+use crate::normalization::signal_energy;
+use crate::pad::pad_signal;
+use crate::scratch::as_complex_mut;
+use crate::spectrum::SpectrumMultiplier;
+use crate::streaming::{correlate_block, streaming_block_size};
+use crate::weighting::SpectrumWeighting;
+use crate::{
+    CorrelateOptions, CorrelateSample, CrossCorrelateError, CrossCorrelateTemplate,
+    CrossCorrelationMode, CrossCorrelationNormalization, FftSizePolicy,
+};
+use num_complex::Complex;
+use std::sync::Arc;
+
+pub(crate) struct CrossCorrelateRealTemplate<T: CorrelateSample> {
+    pub(crate) fft_forward: Arc<dyn RealToComplexFft<T> + Send + Sync>,
+    pub(crate) fft_inverse: Arc<dyn ComplexToRealFft<T> + Send + Sync>,
+    pub(crate) multiplier: Arc<dyn SpectrumMultiplier<T> + Send + Sync>,
+    pub(crate) mode: CrossCorrelationMode,
+    pub(crate) normalization: CrossCorrelationNormalization,
+    pub(crate) weighting: SpectrumWeighting<T>,
+    pub(crate) fft_size_policy: FftSizePolicy,
+    pub(crate) template_spectrum: Vec<Complex<T>>,
+    pub(crate) template_length: usize,
+    pub(crate) template_energy: f64,
+}
+
+/// Whether an unweighted `Valid` correlation of `buffer_length` samples runs overlap-save
+/// blocks of [`streaming_block_size`], matching the streaming correlator bit for bit.
+#[inline]
+fn uses_blocks(
+    mode: CrossCorrelationMode,
+    weighted: bool,
+    buffer_length: usize,
+    template_length: usize,
+) -> bool {
+    mode == CrossCorrelationMode::Valid && !weighted && buffer_length >= template_length
+}
+
+/// FFT size of a real template correlator for buffers of `buffer_length` samples.
+///
+/// Overlap-save blocks always have [`streaming_block_size`] samples, `fft_size_policy`
+/// only sizes the transform of whole buffers.
+pub(crate) fn template_fft_size(
+    mode: CrossCorrelationMode,
+    fft_size_policy: FftSizePolicy,
+    weighted: bool,
+    buffer_length: usize,
+    template_length: usize,
+) -> usize {
+    if uses_blocks(mode, weighted, buffer_length, template_length) {
+        streaming_block_size(template_length)
+    } else {
+        fft_size_policy.fft_size(mode, buffer_length, template_length)
+    }
+}
+
+impl<T: CorrelateSample> CrossCorrelateRealTemplate<T> {
+    pub(crate) fn new(
+        fft_forward: Arc<dyn RealToComplexFft<T> + Send + Sync>,
+        fft_inverse: Arc<dyn ComplexToRealFft<T> + Send + Sync>,
+        multiplier: Arc<dyn SpectrumMultiplier<T> + Send + Sync>,
+        options: &CorrelateOptions,
+        template: &[T],
+    ) -> Result<Self, CrossCorrelateError> {
+        if template.is_empty() {
+            return Err(CrossCorrelateError::BuffersMustNotHaveZeroSize);
+        }
+        if fft_forward.real_length() != fft_inverse.real_length() {
+            return Err(CrossCorrelateError::FftSizesDoNotMatch(
+                fft_forward.real_length(),
+                fft_inverse.real_length(),
+            ));
+        }
+        let mut padded_template = try_vec![T::default(); fft_forward.real_length()];
+        pad_signal(&mut padded_template, template);
+        let mut template_spectrum =
+            try_vec![Complex::<T>::default(); fft_forward.real_length() / 2 + 1];
+        fft_forward.execute(&padded_template, &mut template_spectrum)?;
+        let weighting = options
+            .weighting
+            .resolve(fft_forward.real_length() / 2 + 1)?;
+        Ok(CrossCorrelateRealTemplate {
+            fft_forward,
+            fft_inverse,
+            multiplier,
+            mode: options.mode,
+            normalization: options.normalization,
+            weighting,
+            fft_size_policy: options.fft_size_policy,
+            template_spectrum,
+            template_length: template.len(),
+            template_energy: signal_energy(template),
+        })
+    }
+
+    /// Complex values of work space handed to one transform at a time.
+    pub(crate) fn executor_scratch_length(&self) -> usize {
+        self.fft_forward
+            .scratch_length()
+            .max(self.fft_inverse.scratch_length())
+    }
+
+    #[inline]
+    fn is_weighted(&self) -> bool {
+        !matches!(self.weighting, SpectrumWeighting::None)
+    }
+}
+
+impl<T: CorrelateSample> CrossCorrelateTemplate<T> for CrossCorrelateRealTemplate<T> {
+    fn correlate(&self, output: &mut [T], buffer: &[T]) -> Result<(), CrossCorrelateError> {
+        let mut scratch = try_vec![T::default(); self.scratch_length()];
+        self.correlate_with_scratch(output, buffer, &mut scratch)
+    }
+
+    fn correlate_managed(&self, buffer: &[T]) -> Result<Vec<T>, CrossCorrelateError> {
+        let data_length = self.mode.get_size(buffer.len(), self.template_length);
+        let mut output = try_vec![T::default(); data_length];
+        self.correlate(&mut output, buffer).map(|_| output)
+    }
+
+    fn scratch_length(&self) -> usize {
+        // Padded buffer, the overlap-save output block if there can be one, then the
+        // half-spectrum and the work space of the executors as interleaved scalars.
+        let real_length = self.fft_forward.real_length();
+        let blocks = if self.mode == CrossCorrelationMode::Valid && !self.is_weighted() {
+            real_length
+        } else {
+            0
+        };
+        real_length + blocks + (real_length / 2 + 1 + self.executor_scratch_length()) * 2
+    }
+
+    fn template_length(&self) -> usize {
+        self.template_length
+    }
+
+    fn output_length(&self, buffer_length: usize) -> usize {
+        self.mode.get_size(buffer_length, self.template_length)
+    }
+
+    fn correlate_with_scratch(
+        &self,
+        output: &mut [T],
+        buffer: &[T],
+        scratch: &mut [T],
+    ) -> Result<(), CrossCorrelateError> {
+        if buffer.is_empty() || output.is_empty() {
+            return Err(CrossCorrelateError::BuffersMustNotHaveZeroSize);
+        }
+        let data_length = self.mode.get_size(buffer.len(), self.template_length);
+        let fft_size = template_fft_size(
+            self.mode,
+            self.fft_size_policy,
+            self.is_weighted(),
+            buffer.len(),
+            self.template_length,
+        );
+
+        if fft_size != self.fft_forward.real_length() {
+            return Err(CrossCorrelateError::FftAndBuffersSizeDoNotMatch(
+                self.fft_forward.real_length(),
+                fft_size,
+            ));
+        }
+
+        if output.len() != data_length {
+            return Err(CrossCorrelateError::OutputSizeDoNotMatch(
+                data_length,
+                output.len(),
+            ));
+        }
+
+        let scratch_length = self.scratch_length();
+        if scratch.len() < scratch_length {
+            return Err(CrossCorrelateError::ScratchSizeIsTooSmall(
+                scratch_length,
+                scratch.len(),
+            ));
+        }
+
+        let scale =
+            self.normalization
+                .lag_scale(self.mode, buffer.len(), self.template_length, || {
+                    signal_energy(buffer) * self.template_energy
+                });
+
+        if uses_blocks(
+            self.mode,
+            self.is_weighted(),
+            buffer.len(),
+            self.template_length,
+        ) {
+            let (window, rest) = scratch[..scratch_length].split_at_mut(fft_size);
+            let (block, spectrum_scratch) = rest.split_at_mut(fft_size);
+            let (spectrum, work) = as_complex_mut(spectrum_scratch).split_at_mut(fft_size / 2 + 1);
+            let step = fft_size - (self.template_length - 1);
+            for (start, dst) in (0..).step_by(step).zip(output.chunks_mut(step)) {
+                let end = (start + fft_size).min(buffer.len());
+                pad_signal(window, &buffer[start..end]);
+                correlate_block(
+                    self.fft_forward.as_ref(),
+                    self.fft_inverse.as_ref(),
+                    self.multiplier.as_ref(),
+                    &self.template_spectrum,
+                    window,
+                    (spectrum, work),
+                    block,
+                )?;
+                dst.copy_from_slice(&block[..dst.len()]);
+            }
+            scale.apply(output);
+            return Ok(());
+        }
+
+        let complex_length = fft_size / 2 + 1;
+        let work_length = self.executor_scratch_length();
+        let (padded_src, spectrum_scratch) = scratch.split_at_mut(fft_size);
+        let (complex_src, work) = as_complex_mut(spectrum_scratch).split_at_mut(complex_length);
+        let work = &mut work[..work_length];
+
+        pad_signal(padded_src, buffer);
+        self.fft_forward
+            .execute_with_scratch(padded_src, complex_src, work)?;
+        self.weighting.multiply(
+            self.multiplier.as_ref(),
+            complex_src,
+            &self.template_spectrum,
+            fft_size,
+        );
+        self.fft_inverse
+            .execute_with_scratch(complex_src, padded_src, work)?;
+
+        extract_correlation(
+            output,
+            padded_src,
+            self.mode,
+            buffer.len(),
+            self.template_length,
+            &scale,
+        );
+
+        Ok(())
+    }
+
+    fn correlate_channels(
+        &self,
+        output: &mut [T],
+        output_stride: usize,
+        channels: &[T],
+        channel_stride: usize,
+        channel_length: usize,
+        channel_count: usize,
+    ) -> Result<(), CrossCorrelateError> {
+        batch::correlate_channels(
+            self,
+            output,
+            output_stride,
+            channels,
+            (channel_stride, channel_length, channel_count),
+        )
+    }
+
+    fn correlate_channels_managed(
+        &self,
+        channels: &[&[T]],
+    ) -> Result<Vec<Vec<T>>, CrossCorrelateError> {
+        batch::correlate_channels_managed(self, channels)
+    }
+}
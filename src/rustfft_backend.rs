@@ -0,0 +1,298 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::error::try_vec;
+use crate::{ComplexFft, ComplexToRealFft, CrossCorrelateError, FftPlanner, RealToComplexFft};
+use num_complex::Complex;
+use rustfft::{Fft, FftDirection, FftNum};
+use std::sync::{Arc, Mutex};
+
+fn check_length(expected: usize, actual: usize) -> Result<(), CrossCorrelateError> {
+    if expected != actual {
+        return Err(CrossCorrelateError::FftError(format!(
+            "rustfft executor expects {expected} samples but received {actual}"
+        )));
+    }
+    Ok(())
+}
+
+fn check_scratch(required: usize, actual: usize) -> Result<(), CrossCorrelateError> {
+    if actual < required {
+        return Err(CrossCorrelateError::ScratchSizeIsTooSmall(required, actual));
+    }
+    Ok(())
+}
+
+fn check_direction<T: FftNum>(
+    fft: &Arc<dyn Fft<T>>,
+    direction: FftDirection,
+) -> Result<(), CrossCorrelateError> {
+    if fft.fft_direction() != direction {
+        return Err(CrossCorrelateError::FftError(format!(
+            "rustfft executor must be planned as {direction}"
+        )));
+    }
+    Ok(())
+}
+
+/// Complex FFT backed by a `rustfft` plan, in whatever direction it was planned.
+pub struct RustFftComplex<T: FftNum> {
+    fft: Arc<dyn Fft<T>>,
+}
+
+impl<T: FftNum> RustFftComplex<T> {
+    /// Wraps a plan from `rustfft::FftPlanner::plan_fft_forward` or `plan_fft_inverse`.
+    pub fn new(fft: Arc<dyn Fft<T>>) -> RustFftComplex<T> {
+        RustFftComplex { fft }
+    }
+}
+
+impl<T: FftNum> ComplexFft<T> for RustFftComplex<T> {
+    fn execute(&self, buffer: &mut [Complex<T>]) -> Result<(), CrossCorrelateError> {
+        let mut scratch = try_vec![Complex::new(T::zero(), T::zero()); self.scratch_length()];
+        self.execute_with_scratch(buffer, &mut scratch)
+    }
+
+    fn scratch_length(&self) -> usize {
+        self.fft.get_inplace_scratch_len()
+    }
+
+    fn execute_with_scratch(
+        &self,
+        buffer: &mut [Complex<T>],
+        scratch: &mut [Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        check_length(self.fft.len(), buffer.len())?;
+        let scratch_length = self.scratch_length();
+        check_scratch(scratch_length, scratch.len())?;
+        self.fft
+            .process_with_scratch(buffer, &mut scratch[..scratch_length]);
+        Ok(())
+    }
+
+    fn length(&self) -> usize {
+        self.fft.len()
+    }
+}
+
+/// Real-to-complex FFT backed by a full length forward `rustfft` plan.
+///
+/// `rustfft` has no real transforms, so the signal is widened to complex
+/// and only the non-redundant half of the spectrum is kept. The widened signal
+/// lives in the scratch of [`RealToComplexFft::execute_with_scratch`], which
+/// correlators provide out of their own scratch buffers.
+pub struct RustFftRealToComplex<T: FftNum> {
+    fft: Arc<dyn Fft<T>>,
+}
+
+impl<T: FftNum> RustFftRealToComplex<T> {
+    /// Wraps a plan from `rustfft::FftPlanner::plan_fft_forward`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError::FftError`] if `fft` is an inverse transform.
+    pub fn new(fft: Arc<dyn Fft<T>>) -> Result<RustFftRealToComplex<T>, CrossCorrelateError> {
+        check_direction(&fft, FftDirection::Forward)?;
+        Ok(RustFftRealToComplex { fft })
+    }
+}
+
+impl<T: FftNum> RealToComplexFft<T> for RustFftRealToComplex<T> {
+    fn execute(&self, input: &[T], output: &mut [Complex<T>]) -> Result<(), CrossCorrelateError> {
+        let mut scratch = try_vec![Complex::new(T::zero(), T::zero()); self.scratch_length()];
+        self.execute_with_scratch(input, output, &mut scratch)
+    }
+
+    fn scratch_length(&self) -> usize {
+        self.fft.len() + self.fft.get_inplace_scratch_len()
+    }
+
+    fn execute_with_scratch(
+        &self,
+        input: &[T],
+        output: &mut [Complex<T>],
+        scratch: &mut [Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        let length = self.fft.len();
+        check_length(length, input.len())?;
+        check_length(length / 2 + 1, output.len())?;
+        let scratch_length = self.scratch_length();
+        check_scratch(scratch_length, scratch.len())?;
+        let (buffer, fft_scratch) = scratch[..scratch_length].split_at_mut(length);
+        for (dst, &src) in buffer.iter_mut().zip(input.iter()) {
+            *dst = Complex::new(src, T::zero());
+        }
+        self.fft.process_with_scratch(buffer, fft_scratch);
+        output.copy_from_slice(&buffer[..output.len()]);
+        Ok(())
+    }
+
+    fn real_length(&self) -> usize {
+        self.fft.len()
+    }
+}
+
+/// Complex-to-real FFT backed by a full length inverse `rustfft` plan.
+///
+/// The half spectrum is expanded to its Hermitian full form in the scratch of
+/// [`ComplexToRealFft::execute_with_scratch`] before the transform and the real
+/// part of the result is kept.
+pub struct RustFftComplexToReal<T: FftNum> {
+    fft: Arc<dyn Fft<T>>,
+}
+
+impl<T: FftNum> RustFftComplexToReal<T> {
+    /// Wraps a plan from `rustfft::FftPlanner::plan_fft_inverse`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CrossCorrelateError::FftError`] if `fft` is a forward transform.
+    pub fn new(fft: Arc<dyn Fft<T>>) -> Result<RustFftComplexToReal<T>, CrossCorrelateError> {
+        check_direction(&fft, FftDirection::Inverse)?;
+        Ok(RustFftComplexToReal { fft })
+    }
+}
+
+impl<T: FftNum> ComplexToRealFft<T> for RustFftComplexToReal<T> {
+    fn execute(
+        &self,
+        input: &mut [Complex<T>],
+        output: &mut [T],
+    ) -> Result<(), CrossCorrelateError> {
+        let mut scratch = try_vec![Complex::new(T::zero(), T::zero()); self.scratch_length()];
+        self.execute_with_scratch(input, output, &mut scratch)
+    }
+
+    fn scratch_length(&self) -> usize {
+        self.fft.len() + self.fft.get_inplace_scratch_len()
+    }
+
+    fn execute_with_scratch(
+        &self,
+        input: &mut [Complex<T>],
+        output: &mut [T],
+        scratch: &mut [Complex<T>],
+    ) -> Result<(), CrossCorrelateError> {
+        let length = self.fft.len();
+        check_length(length / 2 + 1, input.len())?;
+        check_length(length, output.len())?;
+        let scratch_length = self.scratch_length();
+        check_scratch(scratch_length, scratch.len())?;
+        let (buffer, fft_scratch) = scratch[..scratch_length].split_at_mut(length);
+        buffer[..input.len()].copy_from_slice(input);
+        for k in input.len()..length {
+            buffer[k] = input[length - k].conj();
+        }
+        self.fft.process_with_scratch(buffer, fft_scratch);
+        for (dst, src) in output.iter_mut().zip(buffer.iter()) {
+            *dst = src.re;
+        }
+        Ok(())
+    }
+
+    fn real_length(&self) -> usize {
+        self.fft.len()
+    }
+}
+
+/// [`FftPlanner`] handing out plans of a `rustfft::FftPlanner`.
+///
+/// `rustfft` keeps every plan it made, so one instance shared across an application,
+/// for example through [`FftPlanCache::with_planner`](crate::FftPlanCache::with_planner),
+/// plans each size only once.
+pub struct RustFftPlanner<T: FftNum> {
+    planner: Mutex<rustfft::FftPlanner<T>>,
+}
+
+impl<T: FftNum> RustFftPlanner<T> {
+    /// Creates a planner with an empty `rustfft::FftPlanner`.
+    pub fn new() -> RustFftPlanner<T> {
+        RustFftPlanner::from(rustfft::FftPlanner::new())
+    }
+
+    fn plan(&self, size: usize, direction: FftDirection) -> Arc<dyn Fft<T>> {
+        // Planning can only panic before the plan is cached, so a poisoned planner is still consistent
+        self.planner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .plan_fft(size, direction)
+    }
+}
+
+impl<T: FftNum> Default for RustFftPlanner<T> {
+    fn default() -> RustFftPlanner<T> {
+        RustFftPlanner::new()
+    }
+}
+
+impl<T: FftNum> From<rustfft::FftPlanner<T>> for RustFftPlanner<T> {
+    /// Takes over a planner that may already hold plans.
+    fn from(planner: rustfft::FftPlanner<T>) -> RustFftPlanner<T> {
+        RustFftPlanner {
+            planner: Mutex::new(planner),
+        }
+    }
+}
+
+impl<T: FftNum> FftPlanner<T> for RustFftPlanner<T> {
+    fn plan_real_to_complex(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn RealToComplexFft<T> + Send + Sync>, CrossCorrelateError> {
+        Ok(Arc::new(RustFftRealToComplex::new(
+            self.plan(size, FftDirection::Forward),
+        )?))
+    }
+
+    fn plan_complex_to_real(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn ComplexToRealFft<T> + Send + Sync>, CrossCorrelateError> {
+        Ok(Arc::new(RustFftComplexToReal::new(
+            self.plan(size, FftDirection::Inverse),
+        )?))
+    }
+
+    fn plan_forward(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn ComplexFft<T> + Send + Sync>, CrossCorrelateError> {
+        Ok(Arc::new(RustFftComplex::new(
+            self.plan(size, FftDirection::Forward),
+        )))
+    }
+
+    fn plan_inverse(
+        &self,
+        size: usize,
+    ) -> Result<Arc<dyn ComplexFft<T> + Send + Sync>, CrossCorrelateError> {
+        Ok(Arc::new(RustFftComplex::new(
+            self.plan(size, FftDirection::Inverse),
+        )))
+    }
+}
@@ -0,0 +1,142 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::CrossCorrelateError;
+use crate::accumulate::{ScaledAccumulate, make_accumulate};
+use crate::normalization::NormalizeSample;
+use crate::spectrum::{
+    SpectrumMultiplier, make_spectrum_multiplier_f32, make_spectrum_multiplier_f64,
+};
+use num_complex::Complex;
+use num_traits::Float;
+use std::fmt::Debug;
+use std::sync::Arc;
+use zaft::{C2RFftExecutor, FftExecutor, R2CFftExecutor, Zaft};
+
+/// Floating-point sample types the correlators are implemented for.
+///
+/// Generic code can use this bound with [`Correlate::create`](crate::Correlate::create)
+/// and [`Correlate::create_complex`](crate::Correlate::create_complex) instead of
+/// dispatching on the concrete `f32` and `f64` factories.
+///
+/// This trait is sealed and implemented for `f32` and `f64` only.
+pub trait CorrelateSample:
+    Copy + 'static + Clone + Default + Debug + Send + Sync + SealedSample
+{
+}
+
+impl CorrelateSample for f32 {}
+impl CorrelateSample for f64 {}
+
+/// Per-type construction of the FFT plans, the spectrum multiplier and the
+/// direct accumulators.
+///
+/// Public only so it can seal [`CorrelateSample`], the module is private.
+pub trait SealedSample: NormalizeSample + Float {
+    fn make_r2c_fft(
+        size: usize,
+    ) -> Result<Arc<dyn R2CFftExecutor<Self> + Send + Sync>, CrossCorrelateError>;
+    fn make_c2r_fft(
+        size: usize,
+    ) -> Result<Arc<dyn C2RFftExecutor<Self> + Send + Sync>, CrossCorrelateError>;
+    fn make_forward_fft(
+        size: usize,
+    ) -> Result<Arc<dyn FftExecutor<Self> + Send + Sync>, CrossCorrelateError>;
+    fn make_inverse_fft(
+        size: usize,
+    ) -> Result<Arc<dyn FftExecutor<Self> + Send + Sync>, CrossCorrelateError>;
+    fn make_spectrum_multiplier() -> Arc<dyn SpectrumMultiplier<Self> + Send + Sync>;
+    fn make_accumulate() -> Arc<dyn ScaledAccumulate<Self> + Send + Sync>;
+    fn make_complex_accumulate() -> Arc<dyn ScaledAccumulate<Complex<Self>> + Send + Sync>;
+}
+
+macro_rules! impl_sealed_sample {
+    (
+        $t:ty,
+        $r2c:ident,
+        $c2r:ident,
+        $forward:ident,
+        $inverse:ident,
+        $multiplier:ident
+    ) => {
+        impl SealedSample for $t {
+            fn make_r2c_fft(
+                size: usize,
+            ) -> Result<Arc<dyn R2CFftExecutor<$t> + Send + Sync>, CrossCorrelateError> {
+                Zaft::$r2c(size).map_err(|x| CrossCorrelateError::FftError(x.to_string()))
+            }
+
+            fn make_c2r_fft(
+                size: usize,
+            ) -> Result<Arc<dyn C2RFftExecutor<$t> + Send + Sync>, CrossCorrelateError> {
+                Zaft::$c2r(size).map_err(|x| CrossCorrelateError::FftError(x.to_string()))
+            }
+
+            fn make_forward_fft(
+                size: usize,
+            ) -> Result<Arc<dyn FftExecutor<$t> + Send + Sync>, CrossCorrelateError> {
+                Zaft::$forward(size).map_err(|x| CrossCorrelateError::FftError(x.to_string()))
+            }
+
+            fn make_inverse_fft(
+                size: usize,
+            ) -> Result<Arc<dyn FftExecutor<$t> + Send + Sync>, CrossCorrelateError> {
+                Zaft::$inverse(size).map_err(|x| CrossCorrelateError::FftError(x.to_string()))
+            }
+
+            fn make_spectrum_multiplier() -> Arc<dyn SpectrumMultiplier<$t> + Send + Sync> {
+                $multiplier()
+            }
+
+            fn make_accumulate() -> Arc<dyn ScaledAccumulate<$t> + Send + Sync> {
+                make_accumulate::<$t>()
+            }
+
+            fn make_complex_accumulate() -> Arc<dyn ScaledAccumulate<Complex<$t>> + Send + Sync> {
+                make_accumulate::<Complex<$t>>()
+            }
+        }
+    };
+}
+
+impl_sealed_sample!(
+    f32,
+    make_r2c_fft_f32,
+    make_c2r_fft_f32,
+    make_forward_fft_f32,
+    make_inverse_fft_f32,
+    make_spectrum_multiplier_f32
+);
+impl_sealed_sample!(
+    f64,
+    make_r2c_fft_f64,
+    make_c2r_fft_f64,
+    make_forward_fft_f64,
+    make_inverse_fft_f64,
+    make_spectrum_multiplier_f64
+);
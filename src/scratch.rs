@@ -0,0 +1,51 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use num_complex::Complex;
+
+/// Reinterprets a scratch slice of scalars as a slice of complex numbers.
+///
+/// `Complex<T>` is `#[repr(C)]` with two `T` fields, so it has the same alignment
+/// as `T` and exactly twice its size. A trailing odd element is ignored.
+#[inline]
+pub(crate) fn as_complex_mut<T>(scratch: &mut [T]) -> &mut [Complex<T>] {
+    // SAFETY: `Complex<T>` is `#[repr(C)]` with exactly two `T` fields, so it has the
+    // alignment of `T` and twice its size. `len / 2` complex values cover at most `len`
+    // scalars of the same exclusively borrowed allocation.
+    unsafe {
+        std::slice::from_raw_parts_mut(scratch.as_mut_ptr().cast::<Complex<T>>(), scratch.len() / 2)
+    }
+}
+
+/// Reinterprets a scratch slice of complex numbers as twice as many scalars.
+#[inline]
+pub(crate) fn as_scalar_mut<T>(scratch: &mut [Complex<T>]) -> &mut [T] {
+    // SAFETY: `Complex<T>` is `#[repr(C)]` with exactly two `T` fields and the alignment
+    // of `T`, so `len` complex values are `len * 2` contiguous scalars.
+    unsafe { std::slice::from_raw_parts_mut(scratch.as_mut_ptr().cast::<T>(), scratch.len() * 2) }
+}
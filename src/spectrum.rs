@@ -28,9 +28,35 @@
  */
 use num_complex::Complex;
 use num_traits::{AsPrimitive, Float};
+use std::sync::Arc;
 
-pub(crate) trait SpectrumMultiplier<V> {
+pub trait SpectrumMultiplier<V> {
     fn mul_spectrum(&self, buffer: &mut [Complex<V>], other: &[Complex<V>], len: usize);
+    /// Computes `buffer * other / len`, the spectrum of a convolution.
+    fn mul_spectrum_convolve(&self, buffer: &mut [Complex<V>], other: &[Complex<V>], len: usize);
+    /// Computes `buffer * conj(other) * gain / (|buffer * conj(other)| * len)`.
+    ///
+    /// `gains` holds one real gain per bin, or is empty for unit gains.
+    /// Bins with vanishing cross power are set to zero.
+    fn mul_spectrum_phat(
+        &self,
+        buffer: &mut [Complex<V>],
+        other: &[Complex<V>],
+        gains: &[V],
+        len: usize,
+    );
+    /// Computes `buffer * conj(other) / (|other|^2 * len)`.
+    ///
+    /// Bins where `other` has vanishing power are set to zero.
+    fn mul_spectrum_roth(&self, buffer: &mut [Complex<V>], other: &[Complex<V>], len: usize);
+    /// Computes `buffer * conj(other) * gain / len` with one real gain per bin.
+    fn mul_spectrum_gains(
+        &self,
+        buffer: &mut [Complex<V>],
+        other: &[Complex<V>],
+        gains: &[V],
+        len: usize,
+    );
 }
 
 #[derive(Copy, Clone, Default, Debug)]
@@ -43,18 +69,84 @@ pub(crate) struct SpectrumMultiplierDouble {}
 
 impl SpectrumMultiplier<f32> for SpectrumMultiplierSingle {
     fn mul_spectrum(&self, buffer: &mut [Complex<f32>], other: &[Complex<f32>], len: usize) {
-        mul_spectrum_in_place_impl(buffer, other, len);
+        mul_spectrum_in_place_impl::<f32, true>(buffer, other, len);
+    }
+
+    fn mul_spectrum_convolve(
+        &self,
+        buffer: &mut [Complex<f32>],
+        other: &[Complex<f32>],
+        len: usize,
+    ) {
+        mul_spectrum_in_place_impl::<f32, false>(buffer, other, len);
+    }
+
+    fn mul_spectrum_phat(
+        &self,
+        buffer: &mut [Complex<f32>],
+        other: &[Complex<f32>],
+        gains: &[f32],
+        len: usize,
+    ) {
+        mul_spectrum_phat_impl(buffer, other, gains, len);
+    }
+
+    fn mul_spectrum_roth(&self, buffer: &mut [Complex<f32>], other: &[Complex<f32>], len: usize) {
+        mul_spectrum_roth_impl(buffer, other, len);
+    }
+
+    fn mul_spectrum_gains(
+        &self,
+        buffer: &mut [Complex<f32>],
+        other: &[Complex<f32>],
+        gains: &[f32],
+        len: usize,
+    ) {
+        mul_spectrum_gains_impl(buffer, other, gains, len);
     }
 }
 
 impl SpectrumMultiplier<f64> for SpectrumMultiplierDouble {
     fn mul_spectrum(&self, buffer: &mut [Complex<f64>], other: &[Complex<f64>], len: usize) {
-        mul_spectrum_in_place_impl(buffer, other, len);
+        mul_spectrum_in_place_impl::<f64, true>(buffer, other, len);
+    }
+
+    fn mul_spectrum_convolve(
+        &self,
+        buffer: &mut [Complex<f64>],
+        other: &[Complex<f64>],
+        len: usize,
+    ) {
+        mul_spectrum_in_place_impl::<f64, false>(buffer, other, len);
+    }
+
+    fn mul_spectrum_phat(
+        &self,
+        buffer: &mut [Complex<f64>],
+        other: &[Complex<f64>],
+        gains: &[f64],
+        len: usize,
+    ) {
+        mul_spectrum_phat_impl(buffer, other, gains, len);
+    }
+
+    fn mul_spectrum_roth(&self, buffer: &mut [Complex<f64>], other: &[Complex<f64>], len: usize) {
+        mul_spectrum_roth_impl(buffer, other, len);
+    }
+
+    fn mul_spectrum_gains(
+        &self,
+        buffer: &mut [Complex<f64>],
+        other: &[Complex<f64>],
+        gains: &[f64],
+        len: usize,
+    ) {
+        mul_spectrum_gains_impl(buffer, other, gains, len);
     }
 }
 
 #[inline(always)]
-fn mul_spectrum_in_place_impl<V: Copy + 'static + Float>(
+fn mul_spectrum_in_place_impl<V: Copy + 'static + Float, const CONJ: bool>(
     value1: &mut [Complex<V>],
     other: &[Complex<V>],
     len: usize,
@@ -63,6 +155,299 @@ fn mul_spectrum_in_place_impl<V: Copy + 'static + Float>(
 {
     let normalization_factor = (1f64 / len as f64).as_();
     for (dst, kernel) in value1.iter_mut().zip(other.iter()) {
-        *dst = (*dst) * kernel.conj() * normalization_factor;
+        let kernel = if CONJ { kernel.conj() } else { *kernel };
+        *dst = (*dst) * kernel * normalization_factor;
+    }
+}
+
+/// Scalar PHAT weighted multiply, also used for the tails of the SIMD kernels.
+#[inline]
+pub(crate) fn mul_spectrum_phat_impl<V: Copy + 'static + Float>(
+    value1: &mut [Complex<V>],
+    other: &[Complex<V>],
+    gains: &[V],
+    len: usize,
+) where
+    f64: AsPrimitive<V>,
+{
+    let normalization_factor: V = (1f64 / len as f64).as_();
+    for (i, (dst, kernel)) in value1.iter_mut().zip(other.iter()).enumerate() {
+        let product = (*dst) * kernel.conj();
+        let power = product.norm_sqr();
+        *dst = if power > V::min_positive_value() {
+            let gain = gains.get(i).copied().unwrap_or(V::one());
+            product * (gain * normalization_factor / power.sqrt())
+        } else {
+            Complex::new(V::zero(), V::zero())
+        };
+    }
+}
+
+/// Scalar Roth weighted multiply, also used for the tails of the SIMD kernels.
+#[inline]
+pub(crate) fn mul_spectrum_roth_impl<V: Copy + 'static + Float>(
+    value1: &mut [Complex<V>],
+    other: &[Complex<V>],
+    len: usize,
+) where
+    f64: AsPrimitive<V>,
+{
+    let normalization_factor: V = (1f64 / len as f64).as_();
+    for (dst, kernel) in value1.iter_mut().zip(other.iter()) {
+        let power = kernel.norm_sqr();
+        *dst = if power > V::min_positive_value() {
+            (*dst) * kernel.conj() * (normalization_factor / power)
+        } else {
+            Complex::new(V::zero(), V::zero())
+        };
+    }
+}
+
+/// Scalar multiply with real per-bin gains, also used for the tails of the SIMD kernels.
+#[inline]
+pub(crate) fn mul_spectrum_gains_impl<V: Copy + 'static + Float>(
+    value1: &mut [Complex<V>],
+    other: &[Complex<V>],
+    gains: &[V],
+    len: usize,
+) where
+    f64: AsPrimitive<V>,
+{
+    let normalization_factor: V = (1f64 / len as f64).as_();
+    for ((dst, kernel), &gain) in value1.iter_mut().zip(other.iter()).zip(gains.iter()) {
+        *dst = (*dst) * kernel.conj() * (gain * normalization_factor);
+    }
+}
+
+/// Replaces `spectrum` with `|X|^2 / len`, the spectrum of its autocorrelation.
+#[inline]
+pub(crate) fn power_spectrum_in_place<V: Copy + 'static + Float>(
+    spectrum: &mut [Complex<V>],
+    len: usize,
+) where
+    f64: AsPrimitive<V>,
+{
+    let normalization_factor: V = (1f64 / len as f64).as_();
+    for dst in spectrum.iter_mut() {
+        *dst = Complex::new(dst.norm_sqr() * normalization_factor, V::zero());
+    }
+}
+
+/// Selects the fastest available `f32` spectrum multiplier for the running CPU.
+pub(crate) fn make_spectrum_multiplier_f32() -> Arc<dyn SpectrumMultiplier<f32> + Send + Sync> {
+    #[cfg(all(target_arch = "x86_64", feature = "avx"))]
+    {
+        if std::arch::is_x86_feature_detected!("avx2") && std::arch::is_x86_feature_detected!("fma")
+        {
+            use crate::avx::MulSpectrumSingleAvxFma;
+            return Arc::new(MulSpectrumSingleAvxFma::default());
+        }
+    }
+    #[cfg(all(target_arch = "x86_64", feature = "sse"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.2") {
+            use crate::sse::MulSpectrumSingleSse4_2;
+            return Arc::new(MulSpectrumSingleSse4_2::default());
+        }
+    }
+    #[cfg(all(target_arch = "aarch64", feature = "fcma"))]
+    {
+        if std::arch::is_aarch64_feature_detected!("fcma") {
+            use crate::neon::SpectrumMulSingleFcma;
+            return Arc::new(SpectrumMulSingleFcma::default());
+        }
+    }
+    #[cfg(all(target_arch = "aarch64", feature = "neon"))]
+    {
+        use crate::neon::SpectrumMulSingleNeon;
+        Arc::new(SpectrumMulSingleNeon::default())
+    }
+    #[cfg(not(all(target_arch = "aarch64", feature = "neon")))]
+    {
+        Arc::new(SpectrumMultiplierSingle::default())
+    }
+}
+
+/// Selects the fastest available `f64` spectrum multiplier for the running CPU.
+pub(crate) fn make_spectrum_multiplier_f64() -> Arc<dyn SpectrumMultiplier<f64> + Send + Sync> {
+    #[cfg(all(target_arch = "x86_64", feature = "avx"))]
+    {
+        if std::arch::is_x86_feature_detected!("avx2") && std::arch::is_x86_feature_detected!("fma")
+        {
+            use crate::avx::MulSpectrumDoubleAvxFma;
+            return Arc::new(MulSpectrumDoubleAvxFma::default());
+        }
+    }
+    #[cfg(all(target_arch = "x86_64", feature = "sse"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.2") {
+            use crate::sse::MulSpectrumDoubleSse4_2;
+            return Arc::new(MulSpectrumDoubleSse4_2::default());
+        }
+    }
+    #[cfg(all(target_arch = "aarch64", feature = "fcma"))]
+    {
+        if std::arch::is_aarch64_feature_detected!("fcma") {
+            use crate::neon::SpectrumMulDoubleFcma;
+            return Arc::new(SpectrumMulDoubleFcma::default());
+        }
+    }
+    #[cfg(all(target_arch = "aarch64", feature = "neon"))]
+    {
+        use crate::neon::SpectrumMulDoubleNeon;
+        Arc::new(SpectrumMulDoubleNeon::default())
+    }
+    #[cfg(not(all(target_arch = "aarch64", feature = "neon")))]
+    {
+        Arc::new(SpectrumMultiplierDouble::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_spectrum(len: usize, seed: f64) -> Vec<Complex<f64>> {
+        (0..len)
+            .map(|i| {
+                let x = i as f64 + seed;
+                Complex::new((x * 0.731).sin() * 3., (x * 1.37).cos())
+            })
+            .collect()
+    }
+
+    /// Compares the weighted kernels of one backend with the scalar implementations.
+    fn check_weighted_multipliers(
+        multiplier_f32: &dyn SpectrumMultiplier<f32>,
+        multiplier_f64: &dyn SpectrumMultiplier<f64>,
+    ) {
+        for len in 0..19 {
+            let buffer = make_spectrum(len, 0.3);
+            let mut other = make_spectrum(len, 7.1);
+            if len > 3 {
+                // Silent bins must come out as zero instead of NaN
+                other[2] = Complex::new(0., 0.);
+            }
+            let gains = (0..len).map(|i| i as f64 * 0.25).collect::<Vec<_>>();
+            let fft_size = len.max(1) * 2;
+
+            let mut reference = buffer.clone();
+            mul_spectrum_gains_impl(&mut reference, &other, &gains, fft_size);
+
+            let mut result = buffer.clone();
+            multiplier_f64.mul_spectrum_gains(&mut result, &other, &gains, fft_size);
+            for (a, b) in result.iter().zip(reference.iter()) {
+                assert!((a - b).norm() < 1e-12, "{len} {a} {b}");
+            }
+
+            let mut result = buffer
+                .iter()
+                .map(|x| Complex::new(x.re as f32, x.im as f32))
+                .collect::<Vec<_>>();
+            let other32 = other
+                .iter()
+                .map(|x| Complex::new(x.re as f32, x.im as f32))
+                .collect::<Vec<_>>();
+            let gains32 = gains.iter().map(|&x| x as f32).collect::<Vec<_>>();
+            multiplier_f32.mul_spectrum_gains(&mut result, &other32, &gains32, fft_size);
+            for (a, b) in result.iter().zip(reference.iter()) {
+                assert!((a.re as f64 - b.re).abs() < 1e-5, "{len} {a} {b}");
+                assert!((a.im as f64 - b.im).abs() < 1e-5, "{len} {a} {b}");
+            }
+
+            for gains in [&[][..], &gains[..]] {
+                let mut reference = buffer.clone();
+                mul_spectrum_phat_impl(&mut reference, &other, gains, fft_size);
+
+                let mut result = buffer.clone();
+                multiplier_f64.mul_spectrum_phat(&mut result, &other, gains, fft_size);
+                for (a, b) in result.iter().zip(reference.iter()) {
+                    assert!((a - b).norm() < 1e-12, "{len} {a} {b}");
+                }
+
+                let mut result = buffer
+                    .iter()
+                    .map(|x| Complex::new(x.re as f32, x.im as f32))
+                    .collect::<Vec<_>>();
+                let other = other
+                    .iter()
+                    .map(|x| Complex::new(x.re as f32, x.im as f32))
+                    .collect::<Vec<_>>();
+                let gains = gains.iter().map(|&x| x as f32).collect::<Vec<_>>();
+                multiplier_f32.mul_spectrum_phat(&mut result, &other, &gains, fft_size);
+                for (a, b) in result.iter().zip(reference.iter()) {
+                    assert!((a.re as f64 - b.re).abs() < 1e-5, "{len} {a} {b}");
+                    assert!((a.im as f64 - b.im).abs() < 1e-5, "{len} {a} {b}");
+                }
+            }
+
+            let mut reference = buffer.clone();
+            mul_spectrum_roth_impl(&mut reference, &other, fft_size);
+
+            let mut result = buffer.clone();
+            multiplier_f64.mul_spectrum_roth(&mut result, &other, fft_size);
+            for (a, b) in result.iter().zip(reference.iter()) {
+                assert!((a - b).norm() < 1e-12, "{len} {a} {b}");
+            }
+
+            let mut result = buffer
+                .iter()
+                .map(|x| Complex::new(x.re as f32, x.im as f32))
+                .collect::<Vec<_>>();
+            let other = other
+                .iter()
+                .map(|x| Complex::new(x.re as f32, x.im as f32))
+                .collect::<Vec<_>>();
+            multiplier_f32.mul_spectrum_roth(&mut result, &other, fft_size);
+            for (a, b) in result.iter().zip(reference.iter()) {
+                assert!((a.re as f64 - b.re).abs() < 1e-5, "{len} {a} {b}");
+                assert!((a.im as f64 - b.im).abs() < 1e-5, "{len} {a} {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_multipliers() {
+        check_weighted_multipliers(
+            &SpectrumMultiplierSingle::default(),
+            &SpectrumMultiplierDouble::default(),
+        );
+        check_weighted_multipliers(
+            make_spectrum_multiplier_f32().as_ref(),
+            make_spectrum_multiplier_f64().as_ref(),
+        );
+        #[cfg(all(target_arch = "x86_64", feature = "sse"))]
+        if std::arch::is_x86_feature_detected!("sse4.2") {
+            use crate::sse::{MulSpectrumDoubleSse4_2, MulSpectrumSingleSse4_2};
+            check_weighted_multipliers(
+                &MulSpectrumSingleSse4_2::default(),
+                &MulSpectrumDoubleSse4_2::default(),
+            );
+        }
+        #[cfg(all(target_arch = "x86_64", feature = "avx"))]
+        if std::arch::is_x86_feature_detected!("avx2") && std::arch::is_x86_feature_detected!("fma")
+        {
+            use crate::avx::{MulSpectrumDoubleAvxFma, MulSpectrumSingleAvxFma};
+            check_weighted_multipliers(
+                &MulSpectrumSingleAvxFma::default(),
+                &MulSpectrumDoubleAvxFma::default(),
+            );
+        }
+        #[cfg(all(target_arch = "aarch64", feature = "neon"))]
+        {
+            use crate::neon::{SpectrumMulDoubleNeon, SpectrumMulSingleNeon};
+            check_weighted_multipliers(
+                &SpectrumMulSingleNeon::default(),
+                &SpectrumMulDoubleNeon::default(),
+            );
+        }
+        #[cfg(all(target_arch = "aarch64", feature = "fcma"))]
+        if std::arch::is_aarch64_feature_detected!("fcma") {
+            use crate::neon::{SpectrumMulDoubleFcma, SpectrumMulSingleFcma};
+            check_weighted_multipliers(
+                &SpectrumMulSingleFcma::default(),
+                &SpectrumMulDoubleFcma::default(),
+            );
+        }
     }
 }
@@ -0,0 +1,157 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::accumulate::ScaledAccumulate;
+use num_complex::Complex;
+use std::arch::x86_64::*;
+
+#[derive(Copy, Clone, Default)]
+pub(crate) struct AccumulateDoubleSse4_2 {}
+
+impl ScaledAccumulate<f64> for AccumulateDoubleSse4_2 {
+    fn accumulate(&self, dst: &mut [f64], src: &[f64], tap: f64) {
+        unsafe {
+            accumulate_f64_impl(dst, src, tap);
+        }
+    }
+}
+
+impl ScaledAccumulate<Complex<f64>> for AccumulateDoubleSse4_2 {
+    fn accumulate(&self, dst: &mut [Complex<f64>], src: &[Complex<f64>], tap: Complex<f64>) {
+        unsafe {
+            accumulate_complex_f64_impl(dst, src, tap.conj());
+        }
+    }
+}
+
+#[target_feature(enable = "sse4.2")]
+unsafe fn accumulate_f64_impl(dst: &mut [f64], src: &[f64], scale: f64) {
+    unsafe {
+        let v_scale = _mm_set1_pd(scale);
+
+        for (dst, src) in dst.chunks_exact_mut(8).zip(src.chunks_exact(8)) {
+            let vs0 = _mm_loadu_pd(src.as_ptr());
+            let vs1 = _mm_loadu_pd(src.get_unchecked(2..).as_ptr());
+            let vs2 = _mm_loadu_pd(src.get_unchecked(4..).as_ptr());
+            let vs3 = _mm_loadu_pd(src.get_unchecked(6..).as_ptr());
+            let vd0 = _mm_loadu_pd(dst.as_ptr());
+            let vd1 = _mm_loadu_pd(dst.get_unchecked(2..).as_ptr());
+            let vd2 = _mm_loadu_pd(dst.get_unchecked(4..).as_ptr());
+            let vd3 = _mm_loadu_pd(dst.get_unchecked(6..).as_ptr());
+
+            _mm_storeu_pd(dst.as_mut_ptr(), _mm_add_pd(_mm_mul_pd(vs0, v_scale), vd0));
+            _mm_storeu_pd(
+                dst.get_unchecked_mut(2..).as_mut_ptr(),
+                _mm_add_pd(_mm_mul_pd(vs1, v_scale), vd1),
+            );
+            _mm_storeu_pd(
+                dst.get_unchecked_mut(4..).as_mut_ptr(),
+                _mm_add_pd(_mm_mul_pd(vs2, v_scale), vd2),
+            );
+            _mm_storeu_pd(
+                dst.get_unchecked_mut(6..).as_mut_ptr(),
+                _mm_add_pd(_mm_mul_pd(vs3, v_scale), vd3),
+            );
+        }
+
+        let dst_rem = dst.chunks_exact_mut(8).into_remainder();
+        let src_rem = src.chunks_exact(8).remainder();
+
+        for (dst, src) in dst_rem.chunks_exact_mut(2).zip(src_rem.chunks_exact(2)) {
+            let vs = _mm_loadu_pd(src.as_ptr());
+            let vd = _mm_loadu_pd(dst.as_ptr());
+            _mm_storeu_pd(dst.as_mut_ptr(), _mm_add_pd(_mm_mul_pd(vs, v_scale), vd));
+        }
+
+        let dst_rem = dst_rem.chunks_exact_mut(2).into_remainder();
+        let src_rem = src_rem.chunks_exact(2).remainder();
+
+        for (dst, &src) in dst_rem.iter_mut().zip(src_rem.iter()) {
+            *dst += src * scale;
+        }
+    }
+}
+
+#[target_feature(enable = "sse4.2")]
+unsafe fn accumulate_complex_f64_impl(
+    dst: &mut [Complex<f64>],
+    src: &[Complex<f64>],
+    scale: Complex<f64>,
+) {
+    unsafe {
+        // src * scale = src * re(scale) + swap(src) * [-im(scale), im(scale)]
+        let v_re = _mm_set1_pd(scale.re);
+        let v_im = _mm_setr_pd(-scale.im, scale.im);
+
+        for (dst, src) in dst.chunks_exact_mut(4).zip(src.chunks_exact(4)) {
+            let vs0 = _mm_loadu_pd(src.as_ptr().cast());
+            let vs1 = _mm_loadu_pd(src.get_unchecked(1..).as_ptr().cast());
+            let vs2 = _mm_loadu_pd(src.get_unchecked(2..).as_ptr().cast());
+            let vs3 = _mm_loadu_pd(src.get_unchecked(3..).as_ptr().cast());
+            let vd0 = _mm_loadu_pd(dst.as_ptr().cast());
+            let vd1 = _mm_loadu_pd(dst.get_unchecked(1..).as_ptr().cast());
+            let vd2 = _mm_loadu_pd(dst.get_unchecked(2..).as_ptr().cast());
+            let vd3 = _mm_loadu_pd(dst.get_unchecked(3..).as_ptr().cast());
+
+            let p0 = _mm_add_pd(
+                _mm_mul_pd(_mm_shuffle_pd::<0b01>(vs0, vs0), v_im),
+                _mm_add_pd(_mm_mul_pd(vs0, v_re), vd0),
+            );
+            let p1 = _mm_add_pd(
+                _mm_mul_pd(_mm_shuffle_pd::<0b01>(vs1, vs1), v_im),
+                _mm_add_pd(_mm_mul_pd(vs1, v_re), vd1),
+            );
+            let p2 = _mm_add_pd(
+                _mm_mul_pd(_mm_shuffle_pd::<0b01>(vs2, vs2), v_im),
+                _mm_add_pd(_mm_mul_pd(vs2, v_re), vd2),
+            );
+            let p3 = _mm_add_pd(
+                _mm_mul_pd(_mm_shuffle_pd::<0b01>(vs3, vs3), v_im),
+                _mm_add_pd(_mm_mul_pd(vs3, v_re), vd3),
+            );
+
+            _mm_storeu_pd(dst.as_mut_ptr().cast(), p0);
+            _mm_storeu_pd(dst.get_unchecked_mut(1..).as_mut_ptr().cast(), p1);
+            _mm_storeu_pd(dst.get_unchecked_mut(2..).as_mut_ptr().cast(), p2);
+            _mm_storeu_pd(dst.get_unchecked_mut(3..).as_mut_ptr().cast(), p3);
+        }
+
+        let dst_rem = dst.chunks_exact_mut(4).into_remainder();
+        let src_rem = src.chunks_exact(4).remainder();
+
+        for (dst, src) in dst_rem.iter_mut().zip(src_rem.iter()) {
+            let vs = _mm_loadu_pd(src as *const Complex<f64> as *const f64);
+            let vd = _mm_loadu_pd(dst as *const Complex<f64> as *const f64);
+            let p = _mm_add_pd(
+                _mm_mul_pd(_mm_shuffle_pd::<0b01>(vs, vs), v_im),
+                _mm_add_pd(_mm_mul_pd(vs, v_re), vd),
+            );
+            _mm_storeu_pd(dst as *mut Complex<f64> as *mut f64, p);
+        }
+    }
+}
@@ -26,8 +26,12 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
+mod accumulate_f32;
+mod accumulate_f64;
 mod mul_spectrum_f32;
 mod mul_spectrum_f64;
 
+pub(crate) use accumulate_f32::AccumulateSingleSse4_2;
+pub(crate) use accumulate_f64::AccumulateDoubleSse4_2;
 pub(crate) use mul_spectrum_f32::MulSpectrumSingleSse4_2;
 pub(crate) use mul_spectrum_f64::MulSpectrumDoubleSse4_2;
@@ -26,7 +26,9 @@
  * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
-use crate::spectrum::SpectrumMultiplier;
+use crate::spectrum::{
+    SpectrumMultiplier, mul_spectrum_gains_impl, mul_spectrum_phat_impl, mul_spectrum_roth_impl,
+};
 use num_complex::Complex;
 use std::arch::x86_64::*;
 
@@ -36,7 +38,48 @@ pub(crate) struct MulSpectrumSingleSse4_2 {}
 impl SpectrumMultiplier<f32> for MulSpectrumSingleSse4_2 {
     fn mul_spectrum(&self, buffer: &mut [Complex<f32>], other: &[Complex<f32>], len: usize) {
         unsafe {
-            mul_spectrum_in_place_f32_impl(buffer, other, len);
+            mul_spectrum_in_place_f32_impl::<true>(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_convolve(
+        &self,
+        buffer: &mut [Complex<f32>],
+        other: &[Complex<f32>],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_in_place_f32_impl::<false>(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_phat(
+        &self,
+        buffer: &mut [Complex<f32>],
+        other: &[Complex<f32>],
+        gains: &[f32],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_phat_f32_impl(buffer, other, gains, len);
+        }
+    }
+
+    fn mul_spectrum_roth(&self, buffer: &mut [Complex<f32>], other: &[Complex<f32>], len: usize) {
+        unsafe {
+            mul_spectrum_roth_f32_impl(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_gains(
+        &self,
+        buffer: &mut [Complex<f32>],
+        other: &[Complex<f32>],
+        gains: &[f32],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_gains_f32_impl(buffer, other, gains, len);
         }
     }
 }
@@ -53,7 +96,7 @@ unsafe fn sse_mul_complex(a: __m128, b: __m128) -> __m128 {
 }
 
 #[target_feature(enable = "sse4.2")]
-unsafe fn mul_spectrum_in_place_f32_impl(
+unsafe fn mul_spectrum_in_place_f32_impl<const CONJ: bool>(
     value1: &mut [Complex<f32>],
     other: &[Complex<f32>],
     len: usize,
@@ -72,7 +115,9 @@ unsafe fn mul_spectrum_in_place_f32_impl(
             let a0 = _mm_loadu_ps(dst.as_ptr().cast());
             let mut b0 = _mm_loadu_ps(kernel.as_ptr().cast());
 
-            b0 = _mm_xor_ps(b0, conj_factors);
+            if CONJ {
+                b0 = _mm_xor_ps(b0, conj_factors);
+            }
 
             let d0 = _mm_mul_ps(sse_mul_complex(a0, b0), v_norm_factor);
 
@@ -86,7 +131,9 @@ unsafe fn mul_spectrum_in_place_f32_impl(
             let v0 = _mm_loadu_si64(dst as *const Complex<f32> as *const _);
             let mut v1 = _mm_loadu_si64(kernel as *const Complex<f32> as *const _);
 
-            v1 = _mm_xor_si128(v1, _mm_castps_si128(conj_factors));
+            if CONJ {
+                v1 = _mm_xor_si128(v1, _mm_castps_si128(conj_factors));
+            }
 
             let d0 = _mm_mul_ps(
                 sse_mul_complex(_mm_castsi128_ps(v0), _mm_castsi128_ps(v1)),
@@ -97,3 +144,126 @@ unsafe fn mul_spectrum_in_place_f32_impl(
         }
     }
 }
+
+// Sums squared re/im pairs, so both lanes of every complex hold its squared magnitude
+#[inline]
+#[target_feature(enable = "sse4.2")]
+fn sse_power_ps(a: __m128) -> __m128 {
+    let squared = _mm_mul_ps(a, a);
+    _mm_add_ps(squared, _mm_shuffle_ps::<0b10110001>(squared, squared))
+}
+
+#[target_feature(enable = "sse4.2")]
+unsafe fn mul_spectrum_phat_f32_impl(
+    value1: &mut [Complex<f32>],
+    other: &[Complex<f32>],
+    gains: &[f32],
+    len: usize,
+) {
+    unsafe {
+        static CONJ_FACTORS: [f32; 4] = [0.0, -0.0, 0.0, -0.0];
+        let conj_factors = _mm_loadu_ps(CONJ_FACTORS.as_ptr());
+        let v_norm_factor = _mm_set1_ps((1f64 / len as f64) as f32);
+        let v_min_power = _mm_set1_ps(f32::MIN_POSITIVE);
+
+        for (i, (dst, kernel)) in value1
+            .chunks_exact_mut(2)
+            .zip(other.chunks_exact(2))
+            .enumerate()
+        {
+            let a0 = _mm_loadu_ps(dst.as_ptr().cast());
+            let b0 = _mm_xor_ps(_mm_loadu_ps(kernel.as_ptr().cast()), conj_factors);
+
+            let product = sse_mul_complex(a0, b0);
+            let power = sse_power_ps(product);
+            let mut factor = _mm_div_ps(v_norm_factor, _mm_sqrt_ps(power));
+            if !gains.is_empty() {
+                let g =
+                    _mm_castsi128_ps(_mm_loadu_si64(gains.get_unchecked(i * 2..).as_ptr().cast()));
+                factor = _mm_mul_ps(factor, _mm_unpacklo_ps(g, g));
+            }
+            let mask = _mm_cmpgt_ps(power, v_min_power);
+            let d0 = _mm_and_ps(_mm_mul_ps(product, factor), mask);
+
+            _mm_storeu_ps(dst.as_mut_ptr().cast(), d0);
+        }
+
+        let processed = value1.len().min(other.len()) / 2 * 2;
+        mul_spectrum_phat_impl(
+            value1.chunks_exact_mut(2).into_remainder(),
+            other.chunks_exact(2).remainder(),
+            gains.get(processed..).unwrap_or_default(),
+            len,
+        );
+    }
+}
+
+#[target_feature(enable = "sse4.2")]
+unsafe fn mul_spectrum_roth_f32_impl(
+    value1: &mut [Complex<f32>],
+    other: &[Complex<f32>],
+    len: usize,
+) {
+    unsafe {
+        static CONJ_FACTORS: [f32; 4] = [0.0, -0.0, 0.0, -0.0];
+        let conj_factors = _mm_loadu_ps(CONJ_FACTORS.as_ptr());
+        let v_norm_factor = _mm_set1_ps((1f64 / len as f64) as f32);
+        let v_min_power = _mm_set1_ps(f32::MIN_POSITIVE);
+
+        for (dst, kernel) in value1.chunks_exact_mut(2).zip(other.chunks_exact(2)) {
+            let a0 = _mm_loadu_ps(dst.as_ptr().cast());
+            let b0 = _mm_loadu_ps(kernel.as_ptr().cast());
+
+            let product = sse_mul_complex(a0, _mm_xor_ps(b0, conj_factors));
+            let power = sse_power_ps(b0);
+            let factor = _mm_div_ps(v_norm_factor, power);
+            let mask = _mm_cmpgt_ps(power, v_min_power);
+            let d0 = _mm_and_ps(_mm_mul_ps(product, factor), mask);
+
+            _mm_storeu_ps(dst.as_mut_ptr().cast(), d0);
+        }
+
+        mul_spectrum_roth_impl(
+            value1.chunks_exact_mut(2).into_remainder(),
+            other.chunks_exact(2).remainder(),
+            len,
+        );
+    }
+}
+
+#[target_feature(enable = "sse4.2")]
+unsafe fn mul_spectrum_gains_f32_impl(
+    value1: &mut [Complex<f32>],
+    other: &[Complex<f32>],
+    gains: &[f32],
+    len: usize,
+) {
+    unsafe {
+        static CONJ_FACTORS: [f32; 4] = [0.0, -0.0, 0.0, -0.0];
+        let conj_factors = _mm_loadu_ps(CONJ_FACTORS.as_ptr());
+        let v_norm_factor = _mm_set1_ps((1f64 / len as f64) as f32);
+
+        for ((dst, kernel), gain) in value1
+            .chunks_exact_mut(2)
+            .zip(other.chunks_exact(2))
+            .zip(gains.chunks_exact(2))
+        {
+            let a0 = _mm_loadu_ps(dst.as_ptr().cast());
+            let b0 = _mm_xor_ps(_mm_loadu_ps(kernel.as_ptr().cast()), conj_factors);
+
+            let g = _mm_castsi128_ps(_mm_loadu_si64(gain.as_ptr().cast()));
+            let factor = _mm_mul_ps(v_norm_factor, _mm_unpacklo_ps(g, g));
+            let d0 = _mm_mul_ps(sse_mul_complex(a0, b0), factor);
+
+            _mm_storeu_ps(dst.as_mut_ptr().cast(), d0);
+        }
+
+        let processed = value1.len().min(other.len()).min(gains.len()) / 2 * 2;
+        mul_spectrum_gains_impl(
+            &mut value1[processed..],
+            other.get(processed..).unwrap_or_default(),
+            gains.get(processed..).unwrap_or_default(),
+            len,
+        );
+    }
+}
@@ -36,7 +36,48 @@ pub(crate) struct MulSpectrumDoubleSse4_2 {}
 impl SpectrumMultiplier<f64> for MulSpectrumDoubleSse4_2 {
     fn mul_spectrum(&self, buffer: &mut [Complex<f64>], other: &[Complex<f64>], len: usize) {
         unsafe {
-            mul_spectrum_in_place_f64_impl(buffer, other, len);
+            mul_spectrum_in_place_f64_impl::<true>(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_convolve(
+        &self,
+        buffer: &mut [Complex<f64>],
+        other: &[Complex<f64>],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_in_place_f64_impl::<false>(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_phat(
+        &self,
+        buffer: &mut [Complex<f64>],
+        other: &[Complex<f64>],
+        gains: &[f64],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_phat_f64_impl(buffer, other, gains, len);
+        }
+    }
+
+    fn mul_spectrum_roth(&self, buffer: &mut [Complex<f64>], other: &[Complex<f64>], len: usize) {
+        unsafe {
+            mul_spectrum_roth_f64_impl(buffer, other, len);
+        }
+    }
+
+    fn mul_spectrum_gains(
+        &self,
+        buffer: &mut [Complex<f64>],
+        other: &[Complex<f64>],
+        gains: &[f64],
+        len: usize,
+    ) {
+        unsafe {
+            mul_spectrum_gains_f64_impl(buffer, other, gains, len);
         }
     }
 }
@@ -53,7 +94,7 @@ unsafe fn sse_mul_complex(a: __m128d, b: __m128d) -> __m128d {
 }
 
 #[target_feature(enable = "sse4.2")]
-unsafe fn mul_spectrum_in_place_f64_impl(
+unsafe fn mul_spectrum_in_place_f64_impl<const CONJ: bool>(
     value1: &mut [Complex<f64>],
     other: &[Complex<f64>],
     len: usize,
@@ -72,7 +113,9 @@ unsafe fn mul_spectrum_in_place_f64_impl(
             let v0 = _mm_loadu_pd(dst as *const Complex<f64> as *const _);
             let mut v1 = _mm_loadu_pd(kernel as *const Complex<f64> as *const _);
 
-            v1 = _mm_xor_pd(v1, conj_factors);
+            if CONJ {
+                v1 = _mm_xor_pd(v1, conj_factors);
+            }
 
             let lo = _mm_mul_pd(sse_mul_complex(v0, v1), v_norm_factor);
 
@@ -80,3 +123,99 @@ unsafe fn mul_spectrum_in_place_f64_impl(
         }
     }
 }
+
+// Sums squared re/im pairs, so both lanes of every complex hold its squared magnitude
+#[inline]
+#[target_feature(enable = "sse4.2")]
+fn sse_power_pd(a: __m128d) -> __m128d {
+    let squared = _mm_mul_pd(a, a);
+    _mm_add_pd(squared, _mm_shuffle_pd::<0b01>(squared, squared))
+}
+
+#[target_feature(enable = "sse4.2")]
+unsafe fn mul_spectrum_phat_f64_impl(
+    value1: &mut [Complex<f64>],
+    other: &[Complex<f64>],
+    gains: &[f64],
+    len: usize,
+) {
+    unsafe {
+        static CONJ_FACTORS: [f64; 2] = [0.0, -0.0];
+        let conj_factors = _mm_loadu_pd(CONJ_FACTORS.as_ptr());
+        let v_norm_factor = _mm_set1_pd(1f64 / len as f64);
+        let v_min_power = _mm_set1_pd(f64::MIN_POSITIVE);
+
+        for (i, (dst, kernel)) in value1.iter_mut().zip(other.iter()).enumerate() {
+            let a0 = _mm_loadu_pd(dst as *const Complex<f64> as *const f64);
+            let b0 = _mm_xor_pd(
+                _mm_loadu_pd(kernel as *const Complex<f64> as *const f64),
+                conj_factors,
+            );
+
+            let product = sse_mul_complex(a0, b0);
+            let power = sse_power_pd(product);
+            let mut factor = _mm_div_pd(v_norm_factor, _mm_sqrt_pd(power));
+            if !gains.is_empty() {
+                factor = _mm_mul_pd(factor, _mm_set1_pd(*gains.get_unchecked(i)));
+            }
+            let mask = _mm_cmpgt_pd(power, v_min_power);
+            let d0 = _mm_and_pd(_mm_mul_pd(product, factor), mask);
+
+            _mm_storeu_pd(dst as *mut Complex<f64> as *mut f64, d0);
+        }
+    }
+}
+
+#[target_feature(enable = "sse4.2")]
+unsafe fn mul_spectrum_roth_f64_impl(
+    value1: &mut [Complex<f64>],
+    other: &[Complex<f64>],
+    len: usize,
+) {
+    unsafe {
+        static CONJ_FACTORS: [f64; 2] = [0.0, -0.0];
+        let conj_factors = _mm_loadu_pd(CONJ_FACTORS.as_ptr());
+        let v_norm_factor = _mm_set1_pd(1f64 / len as f64);
+        let v_min_power = _mm_set1_pd(f64::MIN_POSITIVE);
+
+        for (dst, kernel) in value1.iter_mut().zip(other.iter()) {
+            let a0 = _mm_loadu_pd(dst as *const Complex<f64> as *const f64);
+            let b0 = _mm_loadu_pd(kernel as *const Complex<f64> as *const f64);
+
+            let product = sse_mul_complex(a0, _mm_xor_pd(b0, conj_factors));
+            let power = sse_power_pd(b0);
+            let factor = _mm_div_pd(v_norm_factor, power);
+            let mask = _mm_cmpgt_pd(power, v_min_power);
+            let d0 = _mm_and_pd(_mm_mul_pd(product, factor), mask);
+
+            _mm_storeu_pd(dst as *mut Complex<f64> as *mut f64, d0);
+        }
+    }
+}
+
+#[target_feature(enable = "sse4.2")]
+unsafe fn mul_spectrum_gains_f64_impl(
+    value1: &mut [Complex<f64>],
+    other: &[Complex<f64>],
+    gains: &[f64],
+    len: usize,
+) {
+    unsafe {
+        static CONJ_FACTORS: [f64; 2] = [0.0, -0.0];
+        let conj_factors = _mm_loadu_pd(CONJ_FACTORS.as_ptr());
+        let normalization_factor = 1f64 / len as f64;
+
+        for ((dst, kernel), &gain) in value1.iter_mut().zip(other.iter()).zip(gains.iter()) {
+            let a0 = _mm_loadu_pd(dst as *const Complex<f64> as *const f64);
+            let b0 = _mm_xor_pd(
+                _mm_loadu_pd(kernel as *const Complex<f64> as *const f64),
+                conj_factors,
+            );
+
+            let factor = _mm_set1_pd(gain * normalization_factor);
+            let d0 = _mm_mul_pd(sse_mul_complex(a0, b0), factor);
+
+            _mm_storeu_pd(dst as *mut Complex<f64> as *mut f64, d0);
+        }
+    }
+}
@@ -0,0 +1,226 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::error::try_vec;
+use crate::fft::{ComplexToRealFft, RealToComplexFft};
+use crate::pad::pad_signal;
+use crate::spectrum::SpectrumMultiplier;
+use crate::{CorrelateSample, CrossCorrelateError, fft_next_good_size};
+use num_complex::Complex;
+use std::sync::Arc;
+
+/// Trait for correlating a fixed template against an unbounded stream of samples.
+///
+/// Samples are pushed in chunks of any size, and the [`CrossCorrelationMode::Valid`](crate::CrossCorrelationMode::Valid)
+/// correlation `sum stream[k + n] * template[n]` is emitted in order of `k` as soon as
+/// the block containing it is complete.
+///
+/// For a given block size the emitted samples are bit-identical however the stream is
+/// split into chunks. With a block size of [`streaming_block_size`] they are also
+/// bit-identical to a `Valid` correlation of the whole stream by the template correlator,
+/// which runs the same overlap-save blocks.
+pub trait StreamingCorrelate<V> {
+    /// Pushes `samples` into the stream and returns the correlation samples that became available.
+    fn push(&mut self, samples: &[V]) -> Result<Vec<V>, CrossCorrelateError>;
+    /// Pushes `samples` into the stream and appends the correlation samples that became
+    /// available to `output`.
+    fn push_into(&mut self, samples: &[V], output: &mut Vec<V>) -> Result<(), CrossCorrelateError>;
+    /// Ends the stream, returning the correlation samples still held back, and resets the correlator.
+    ///
+    /// After a stream of `n` samples has been flushed exactly `n - template_length + 1`
+    /// samples have been emitted, the same as a `Valid` correlation of the whole stream.
+    fn flush(&mut self) -> Result<Vec<V>, CrossCorrelateError>;
+    /// Discards any buffered samples and starts a new stream.
+    fn reset(&mut self);
+    /// Returns the length of the template.
+    fn template_length(&self) -> usize;
+    /// Returns the FFT block size.
+    fn block_size(&self) -> usize;
+}
+
+/// Overlap-save block size used for a template of `template_length` samples.
+///
+/// Unweighted [`CrossCorrelationMode::Valid`](crate::CrossCorrelationMode::Valid) template
+/// correlators process buffers at least as long as the template in blocks of this size,
+/// each yielding about three quarters of a block of outputs.
+pub fn streaming_block_size(template_length: usize) -> usize {
+    fft_next_good_size(template_length.max(1) * 4)
+}
+
+/// Correlates one overlap-save `window` with the template spectrum into `block`.
+///
+/// `spectrum` holds the half-spectrum of the window followed by the work space of
+/// the executors. Shared by the streaming and the template correlators so that
+/// both round identically.
+pub(crate) fn correlate_block<T: CorrelateSample>(
+    fft_forward: &dyn RealToComplexFft<T>,
+    fft_inverse: &dyn ComplexToRealFft<T>,
+    multiplier: &dyn SpectrumMultiplier<T>,
+    template_spectrum: &[Complex<T>],
+    window: &[T],
+    (spectrum, work): (&mut [Complex<T>], &mut [Complex<T>]),
+    block: &mut [T],
+) -> Result<(), CrossCorrelateError> {
+    fft_forward.execute_with_scratch(window, spectrum, work)?;
+    multiplier.mul_spectrum(spectrum, template_spectrum, window.len());
+    fft_inverse.execute_with_scratch(spectrum, block, work)
+}
+
+/// Overlap-save correlator.
+///
+/// Every block of `block_size` samples yields `block_size - template_length + 1` outputs
+/// free of circular aliasing, and the last `template_length - 1` samples are carried
+/// over into the next block.
+pub(crate) struct CrossCorrelateStreaming<T: CorrelateSample> {
+    fft_forward: Arc<dyn RealToComplexFft<T> + Send + Sync>,
+    fft_inverse: Arc<dyn ComplexToRealFft<T> + Send + Sync>,
+    multiplier: Arc<dyn SpectrumMultiplier<T> + Send + Sync>,
+    template_spectrum: Vec<Complex<T>>,
+    template_length: usize,
+    window: Vec<T>,
+    filled: usize,
+    spectrum: Vec<Complex<T>>,
+    work: Vec<Complex<T>>,
+    block: Vec<T>,
+}
+
+impl<T: CorrelateSample> CrossCorrelateStreaming<T> {
+    pub(crate) fn new(
+        fft_forward: Arc<dyn RealToComplexFft<T> + Send + Sync>,
+        fft_inverse: Arc<dyn ComplexToRealFft<T> + Send + Sync>,
+        multiplier: Arc<dyn SpectrumMultiplier<T> + Send + Sync>,
+        template: &[T],
+    ) -> Result<Self, CrossCorrelateError> {
+        if template.is_empty() {
+            return Err(CrossCorrelateError::BuffersMustNotHaveZeroSize);
+        }
+        let block_size = fft_forward.real_length();
+        if block_size != fft_inverse.real_length() {
+            return Err(CrossCorrelateError::FftSizesDoNotMatch(
+                block_size,
+                fft_inverse.real_length(),
+            ));
+        }
+        if template.len() > block_size {
+            return Err(CrossCorrelateError::TemplateIsLongerThanBuffer(
+                template.len(),
+                block_size,
+            ));
+        }
+        let complex_length = block_size / 2 + 1;
+        let mut window = try_vec![T::default(); block_size];
+        pad_signal(&mut window, template);
+        let mut template_spectrum = try_vec![Complex::<T>::default(); complex_length];
+        fft_forward.execute(&window, &mut template_spectrum)?;
+        window.fill(T::default());
+        let work_length = fft_forward
+            .scratch_length()
+            .max(fft_inverse.scratch_length());
+        Ok(CrossCorrelateStreaming {
+            spectrum: try_vec![Complex::<T>::default(); complex_length],
+            work: try_vec![Complex::<T>::default(); work_length],
+            block: try_vec![T::default(); block_size],
+            fft_forward,
+            fft_inverse,
+            multiplier,
+            template_spectrum,
+            template_length: template.len(),
+            window,
+            filled: 0,
+        })
+    }
+
+    /// Correlates the current window and appends `count` leading outputs.
+    fn process(&mut self, count: usize, output: &mut Vec<T>) -> Result<(), CrossCorrelateError> {
+        correlate_block(
+            self.fft_forward.as_ref(),
+            self.fft_inverse.as_ref(),
+            self.multiplier.as_ref(),
+            &self.template_spectrum,
+            &self.window,
+            (&mut self.spectrum, &mut self.work),
+            &mut self.block,
+        )?;
+        output.extend_from_slice(&self.block[..count]);
+        Ok(())
+    }
+}
+
+impl<T: CorrelateSample> StreamingCorrelate<T> for CrossCorrelateStreaming<T> {
+    fn push(&mut self, samples: &[T]) -> Result<Vec<T>, CrossCorrelateError> {
+        let mut output = Vec::new();
+        self.push_into(samples, &mut output)?;
+        Ok(output)
+    }
+
+    fn push_into(
+        &mut self,
+        mut samples: &[T],
+        output: &mut Vec<T>,
+    ) -> Result<(), CrossCorrelateError> {
+        let block_size = self.window.len();
+        let overlap = self.template_length - 1;
+        let step = block_size - overlap;
+        while !samples.is_empty() {
+            let taken = (block_size - self.filled).min(samples.len());
+            self.window[self.filled..self.filled + taken].copy_from_slice(&samples[..taken]);
+            self.filled += taken;
+            samples = &samples[taken..];
+            if self.filled == block_size {
+                self.process(step, output)?;
+                self.window.copy_within(step.., 0);
+                self.filled = overlap;
+            }
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<Vec<T>, CrossCorrelateError> {
+        let mut output = Vec::new();
+        // Zero padding the tail keeps the remaining outputs free of aliasing
+        if self.filled >= self.template_length {
+            self.window[self.filled..].fill(T::default());
+            self.process(self.filled - self.template_length + 1, &mut output)?;
+        }
+        self.reset();
+        Ok(output)
+    }
+
+    fn reset(&mut self) {
+        self.window.fill(T::default());
+        self.filled = 0;
+    }
+
+    fn template_length(&self) -> usize {
+        self.template_length
+    }
+
+    fn block_size(&self) -> usize {
+        self.window.len()
+    }
+}
@@ -0,0 +1,142 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::CrossCorrelateError;
+use crate::error::try_vec;
+use crate::spectrum::SpectrumMultiplier;
+use num_complex::Complex;
+use num_traits::Float;
+
+/// Frequency weighting of the generalized cross-correlation (GCC).
+///
+/// The cross spectrum `X * conj(Y)` of `buffer` and `other` is scaled bin by bin
+/// before the inverse FFT, which sharpens the correlation peak for time-delay
+/// estimation in reverberant or colored noise.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum GccWeighting {
+    /// Plain cross-correlation (default)
+    #[default]
+    None,
+    /// Phase transform, `1 / |X * conj(Y)|`, keeps only the phase of the cross spectrum
+    Phat,
+    /// Smoothed coherence transform, `1 / sqrt(Gxx * Gyy)`.
+    ///
+    /// Holds the product `Gxx * Gyy` of the auto power spectra of `buffer` and `other` for every
+    /// spectrum bin, smoothed across frames by the caller, laid out like [`GccWeighting::Ml`].
+    /// Bins without power are set to zero.
+    Scot(Vec<f64>),
+    /// Roth processor, `1 / |Y|^2`, whitens by the power spectrum of `other`
+    Roth,
+    /// Hannan-Thomson maximum likelihood weighting, `|γ|^2 / (|X * conj(Y)| * (1 - |γ|^2))`.
+    ///
+    /// Holds the magnitude-squared coherence `|γ|^2` of every spectrum bin, clamped to `[0, 1)`.
+    /// Real correlators use `fft_size / 2 + 1` bins and complex ones `fft_size` bins, where
+    /// `fft_size` is [`FftSizePolicy::fft_size`](crate::FftSizePolicy::fft_size) of the mode
+    /// and input lengths under the policy set in [`crate::CorrelateOptions`], `Smooth` unless
+    /// changed.
+    Ml(Vec<f64>),
+}
+
+/// [`GccWeighting`] resolved for one spectrum length and sample type.
+pub(crate) enum SpectrumWeighting<T> {
+    None,
+    Phat,
+    Roth,
+    Gains(Vec<T>),
+    Scale(Vec<T>),
+}
+
+impl GccWeighting {
+    pub(crate) fn resolve<T: Float + Default>(
+        &self,
+        bins: usize,
+    ) -> Result<SpectrumWeighting<T>, CrossCorrelateError> {
+        Ok(match self {
+            GccWeighting::None => SpectrumWeighting::None,
+            GccWeighting::Phat => SpectrumWeighting::Phat,
+            GccWeighting::Roth => SpectrumWeighting::Roth,
+            GccWeighting::Ml(coherence) => {
+                if coherence.len() != bins {
+                    return Err(CrossCorrelateError::WeightingSizeDoNotMatch(
+                        bins,
+                        coherence.len(),
+                    ));
+                }
+                let mut gains = try_vec![T::default(); bins];
+                for (dst, &msc) in gains.iter_mut().zip(coherence.iter()) {
+                    let msc = if msc.is_nan() {
+                        0.
+                    } else {
+                        msc.clamp(0., 1. - f64::EPSILON)
+                    };
+                    *dst = T::from(msc / (1. - msc)).unwrap_or_default();
+                }
+                SpectrumWeighting::Gains(gains)
+            }
+            GccWeighting::Scot(power) => {
+                if power.len() != bins {
+                    return Err(CrossCorrelateError::WeightingSizeDoNotMatch(
+                        bins,
+                        power.len(),
+                    ));
+                }
+                let mut gains = try_vec![T::default(); bins];
+                for (dst, &power) in gains.iter_mut().zip(power.iter()) {
+                    if power > f64::MIN_POSITIVE && power.is_finite() {
+                        *dst = T::from(1. / power.sqrt()).unwrap_or_default();
+                    }
+                }
+                SpectrumWeighting::Scale(gains)
+            }
+        })
+    }
+}
+
+impl<T> SpectrumWeighting<T> {
+    /// Forms the weighted cross spectrum `buffer * conj(other)` in place.
+    #[inline]
+    pub(crate) fn multiply(
+        &self,
+        multiplier: &(dyn SpectrumMultiplier<T> + Send + Sync),
+        buffer: &mut [Complex<T>],
+        other: &[Complex<T>],
+        len: usize,
+    ) {
+        match self {
+            SpectrumWeighting::None => multiplier.mul_spectrum(buffer, other, len),
+            SpectrumWeighting::Phat => multiplier.mul_spectrum_phat(buffer, other, &[], len),
+            SpectrumWeighting::Roth => multiplier.mul_spectrum_roth(buffer, other, len),
+            SpectrumWeighting::Gains(gains) => {
+                multiplier.mul_spectrum_phat(buffer, other, gains, len)
+            }
+            SpectrumWeighting::Scale(gains) => {
+                multiplier.mul_spectrum_gains(buffer, other, gains, len)
+            }
+        }
+    }
+}
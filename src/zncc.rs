@@ -0,0 +1,205 @@
+/*
+ * // Copyright (c) Radzivon Bartoshyk 10/2026. All rights reserved.
+ * //
+ * // Redistribution and use in source and binary forms, with or without modification,
+ * // are permitted provided that the following conditions are met:
+ * //
+ * // 1.  Redistributions of source code must retain the above copyright notice, this
+ * // list of conditions and the following disclaimer.
+ * //
+ * // 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * // this list of conditions and the following disclaimer in the documentation
+ * // and/or other materials provided with the distribution.
+ * //
+ * // 3.  Neither the name of the copyright holder nor the names of its
+ * // contributors may be used to endorse or promote products derived from
+ * // this software without specific prior written permission.
+ * //
+ * // THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * // AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * // IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * // DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * // FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * // DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * // SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * // CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * // OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * // OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::batch;
+use crate::error::try_vec;
+use crate::fft::{ComplexToRealFft, RealToComplexFft};
+use crate::real_template::CrossCorrelateRealTemplate;
+use crate::spectrum::SpectrumMultiplier;
+use crate::{
+    CorrelateOptions, CorrelateSample, CrossCorrelateError, CrossCorrelateTemplate,
+    CrossCorrelationMode, FftSizePolicy,
+};
+use num_traits::AsPrimitive;
+use std::sync::Arc;
+
+/// Zero-normalized cross-correlation against a fixed template.
+///
+/// The template is made zero-mean once, so the FFT correlation of the raw buffer
+/// already equals the numerator of the ZNCC at every `Valid` lag. The buffer window
+/// statistics are then taken from running sums in `f64`, which costs `O(n)`
+/// regardless of the template length.
+pub(crate) struct CrossCorrelateZncc<T: CorrelateSample> {
+    pub(crate) inner: CrossCorrelateRealTemplate<T>,
+    pub(crate) template_energy: f64,
+}
+
+impl<T: CorrelateSample> CrossCorrelateZncc<T>
+where
+    T: AsPrimitive<f64>,
+    f64: AsPrimitive<T>,
+{
+    pub(crate) fn new(
+        fft_forward: Arc<dyn RealToComplexFft<T> + Send + Sync>,
+        fft_inverse: Arc<dyn ComplexToRealFft<T> + Send + Sync>,
+        multiplier: Arc<dyn SpectrumMultiplier<T> + Send + Sync>,
+        fft_size_policy: FftSizePolicy,
+        template: &[T],
+    ) -> Result<Self, CrossCorrelateError> {
+        if template.is_empty() {
+            return Err(CrossCorrelateError::BuffersMustNotHaveZeroSize);
+        }
+        let mean = template.iter().map(|&x| x.as_()).sum::<f64>() / template.len() as f64;
+        let mut zero_mean = try_vec![T::default(); template.len()];
+        let mut template_energy = 0f64;
+        for (dst, &src) in zero_mean.iter_mut().zip(template.iter()) {
+            let centered = src.as_() - mean;
+            template_energy += centered * centered;
+            *dst = centered.as_();
+        }
+        let inner = CrossCorrelateRealTemplate::new(
+            fft_forward,
+            fft_inverse,
+            multiplier,
+            &CorrelateOptions::new()
+                .mode(CrossCorrelationMode::Valid)
+                .fft_size_policy(fft_size_policy),
+            &zero_mean,
+        )?;
+        Ok(CrossCorrelateZncc {
+            inner,
+            template_energy,
+        })
+    }
+}
+
+impl<T: CorrelateSample> CrossCorrelateTemplate<T> for CrossCorrelateZncc<T>
+where
+    T: AsPrimitive<f64>,
+    f64: AsPrimitive<T>,
+{
+    fn correlate(&self, output: &mut [T], buffer: &[T]) -> Result<(), CrossCorrelateError> {
+        let mut scratch = try_vec![T::default(); self.scratch_length()];
+        self.correlate_with_scratch(output, buffer, &mut scratch)
+    }
+
+    fn correlate_managed(&self, buffer: &[T]) -> Result<Vec<T>, CrossCorrelateError> {
+        let data_length =
+            CrossCorrelationMode::Valid.get_size(buffer.len(), self.template_length());
+        let mut output = try_vec![T::default(); data_length];
+        self.correlate(&mut output, buffer).map(|_| output)
+    }
+
+    fn scratch_length(&self) -> usize {
+        self.inner.scratch_length()
+    }
+
+    fn template_length(&self) -> usize {
+        self.inner.template_length()
+    }
+
+    fn output_length(&self, buffer_length: usize) -> usize {
+        CrossCorrelationMode::Valid.get_size(buffer_length, self.template_length())
+    }
+
+    fn correlate_with_scratch(
+        &self,
+        output: &mut [T],
+        buffer: &[T],
+        scratch: &mut [T],
+    ) -> Result<(), CrossCorrelateError> {
+        let template_length = self.template_length();
+        if buffer.len() < template_length {
+            return Err(CrossCorrelateError::TemplateIsLongerThanBuffer(
+                template_length,
+                buffer.len(),
+            ));
+        }
+        self.inner.correlate_with_scratch(output, buffer, scratch)?;
+
+        if self.template_energy <= 0. {
+            // A constant template has no defined correlation coefficient
+            for dst in output.iter_mut() {
+                *dst = T::default();
+            }
+            return Ok(());
+        }
+
+        // Shifting by the buffer mean keeps the running sums well conditioned
+        // for signals riding on a large DC offset.
+        let shift = buffer.iter().map(|&x| x.as_()).sum::<f64>() / buffer.len() as f64;
+        let m = template_length as f64;
+        let mut window_sum = 0f64;
+        let mut window_sum_sqr = 0f64;
+        for &x in buffer[..template_length - 1].iter() {
+            let x = x.as_() - shift;
+            window_sum += x;
+            window_sum_sqr += x * x;
+        }
+
+        for ((dst, &head), &tail) in output
+            .iter_mut()
+            .zip(buffer[template_length - 1..].iter())
+            .zip(buffer.iter())
+        {
+            let head = head.as_() - shift;
+            window_sum += head;
+            window_sum_sqr += head * head;
+
+            let window_energy = window_sum_sqr - window_sum * window_sum / m;
+            *dst = if window_energy > window_sum_sqr * 1e-12 {
+                let value = dst.as_() / (window_energy * self.template_energy).sqrt();
+                value.clamp(-1., 1.).as_()
+            } else {
+                // Flat windows carry no shape to match against
+                T::default()
+            };
+
+            let tail = tail.as_() - shift;
+            window_sum -= tail;
+            window_sum_sqr -= tail * tail;
+        }
+
+        Ok(())
+    }
+
+    fn correlate_channels(
+        &self,
+        output: &mut [T],
+        output_stride: usize,
+        channels: &[T],
+        channel_stride: usize,
+        channel_length: usize,
+        channel_count: usize,
+    ) -> Result<(), CrossCorrelateError> {
+        batch::correlate_channels(
+            self,
+            output,
+            output_stride,
+            channels,
+            (channel_stride, channel_length, channel_count),
+        )
+    }
+
+    fn correlate_channels_managed(
+        &self,
+        channels: &[&[T]],
+    ) -> Result<Vec<Vec<T>>, CrossCorrelateError> {
+        batch::correlate_channels_managed(self, channels)
+    }
+}